@@ -1,4 +1,5 @@
 use closest_pair_rs::algorithms::*;
+use closest_pair_rs::solver::ClosestPairSolver;
 use closest_pair_rs::utils::*;
 
 fn main() {
@@ -8,18 +9,10 @@ fn main() {
 
 #[divan::bench(args = [1000, 10000, 100000, 1000000, 5000000, 7000000, 10000000])]
 fn test_closest_pair_bit_shift(n: u32) -> (Point, Point, f32) {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
     let bits = 31;
 
-    // Generate 100 random points
-    let mut points = Vec::new();
-    for _ in 0..n {
-        points.push(Point {
-            x: rng.gen_range(0..(u32::pow(2, bits))),
-            y: rng.gen_range(0..(u32::pow(2, bits))),
-        });
-    }
+    // Generate n random points, the same ones on every run
+    let points = random_points(n as usize, u32::pow(2, bits) - 1, 42);
 
     // Run closest pair algorithm
     let (p1, p2, dist) = closest_pair_bit_shift(points.clone(), bits as u8);
@@ -41,21 +34,13 @@ fn test_closest_pair_bit_shift(n: u32) -> (Point, Point, f32) {
 
 #[divan::bench(args = [1000, 10000, 100000, 1000000, 5000000, 7000000, 10000000])]
 fn test_closest_pair_optimized(n: u32) -> (Point, Point, f32) {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
     let bits = 31;
 
-    // Generate 100 random points
-    let mut points = Vec::new();
-    for _ in 0..n {
-        points.push(Point {
-            x: rng.gen_range(0..(u32::pow(2, bits))),
-            y: rng.gen_range(0..(u32::pow(2, bits))),
-        });
-    }
+    // Generate n random points, the same ones on every run
+    let points = random_points(n as usize, u32::pow(2, bits) - 1, 42);
 
     // Run closest pair algorithm
-    let (p1, p2, dist) = closest_pair_optimized(points.clone());
+    let (p1, p2, dist) = closest_pair_optimized(&points);
 
     // // Compare with brute force result for validation
     // let (bf_p1, bf_p2, bf_dist) = closest_pair_brute_force(points);
@@ -71,3 +56,163 @@ fn test_closest_pair_optimized(n: u32) -> (Point, Point, f32) {
 
     (p1, p2, dist)
 }
+
+#[cfg(feature = "rayon")]
+#[divan::bench(args = [1000, 10000, 100000, 1000000, 5000000, 7000000, 10000000])]
+fn test_closest_pair_optimized_parallel(n: u32) -> (Point, Point, f32) {
+    let bits = 31;
+
+    // Generate n random points, the same ones on every run
+    let points = random_points(n as usize, u32::pow(2, bits) - 1, 42);
+
+    // Run closest pair algorithm
+    closest_pair_optimized_parallel(&points)
+}
+
+#[divan::bench(args = [1000, 10000, 100000, 1000000, 5000000, 7000000, 10000000])]
+fn test_closest_pair_grid(n: u32) -> (Point, Point, f32) {
+    let bits = 31;
+
+    // Generate n random points, the same ones on every run
+    let points = random_points(n as usize, u32::pow(2, bits) - 1, 42);
+
+    // Run closest pair algorithm
+    closest_pair_grid(&points)
+}
+
+// Small inputs on either side of BRUTE_FORCE_THRESHOLD, with the points made
+// outside of the timed loop, to find where divide and conquer starts to win.
+// All three algorithms get the same points for each size.
+const CROSSOVER_SIZES: [usize; 11] = [8, 16, 24, 32, 40, 48, 56, 64, 96, 128, 256];
+
+fn crossover_points(n: usize) -> Vec<Point> {
+    random_points(n, u32::pow(2, 31) - 1, 42)
+}
+
+#[divan::bench(args = CROSSOVER_SIZES)]
+fn crossover_brute_force(bencher: divan::Bencher, n: usize) {
+    let points = crossover_points(n);
+    bencher.bench(|| closest_pair_brute_force(divan::black_box(&points)));
+}
+
+#[divan::bench(args = CROSSOVER_SIZES)]
+fn crossover_optimized(bencher: divan::Bencher, n: usize) {
+    let points = crossover_points(n);
+    bencher.bench(|| closest_pair_optimized(divan::black_box(&points)));
+}
+
+// Takes its points by value, so each run gets a fresh copy made outside the timing
+#[divan::bench(args = CROSSOVER_SIZES)]
+fn crossover_bit_shift(bencher: divan::Bencher, n: usize) {
+    let points = crossover_points(n);
+    bencher
+        .with_inputs(|| points.clone())
+        .bench_values(|points| closest_pair_bit_shift(points, 31));
+}
+
+// 100k points with different brute force cutoffs, to tune DEFAULT_BRUTE_FORCE_CUTOFF
+#[divan::bench(args = [3, 4, 6, 8, 12, 16, 24, 32, 48, 64])]
+fn cutoff_sweep(bencher: divan::Bencher, cutoff: usize) {
+    let points = random_points(100_000, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_optimized_with_cutoff(divan::black_box(&points), cutoff));
+}
+
+// The same inputs solved over and over, as in a per-frame loop, with fresh
+// buffers on every call against buffers kept in a solver between calls
+#[divan::bench(args = [100, 1000, 10000, 100000])]
+fn repeated_free_function(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_optimized(divan::black_box(&points)));
+}
+
+#[divan::bench(args = [100, 1000, 10000, 100000])]
+fn repeated_solver(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    let mut solver = ClosestPairSolver::with_capacity(n);
+    bencher.bench_local(|| solver.solve(divan::black_box(&points)));
+}
+
+// Brute force comparing true distances, with a square root per pair, against
+// the same loop comparing squared distances and against the library's brute
+// force, which compares squared distances and takes a single square root at the end
+const SQRT_SIZES: [usize; 4] = [100, 1000, 3000, 10000];
+
+#[divan::bench(args = SQRT_SIZES)]
+fn brute_force_sqrt_per_pair(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| {
+        let points = divan::black_box(&points);
+        let mut best = (points[0], points[1], eucid_distance(&points[0], &points[1]));
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                let dist = eucid_distance(&points[i], &points[j]);
+                if dist < best.2 {
+                    best = (points[i], points[j], dist);
+                }
+            }
+        }
+        best
+    });
+}
+
+#[divan::bench(args = SQRT_SIZES)]
+fn brute_force_squared_per_pair(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| {
+        let points = divan::black_box(&points);
+        let mut best = (
+            points[0],
+            points[1],
+            eucid_distance_sq(&points[0], &points[1]),
+        );
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+                if dist_sq < best.2 {
+                    best = (points[i], points[j], dist_sq);
+                }
+            }
+        }
+        (best.0, best.1, (best.2 as f64).sqrt() as f32)
+    });
+}
+
+#[divan::bench(args = SQRT_SIZES)]
+fn brute_force_squared(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_brute_force(divan::black_box(&points)));
+}
+
+// The divide-and-conquer search on copies of the points, as the library does it,
+// against the same search carrying each point's index along with its copy, as
+// `closest_pair_indices` does, which triples the memory for `u32` points
+#[divan::bench(args = [100000, 1000000, 10000000])]
+fn optimized_vs_indices_copies(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_optimized(divan::black_box(&points)));
+}
+
+#[divan::bench(args = [100000, 1000000, 10000000])]
+fn optimized_vs_indices_indices(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_indices(divan::black_box(&points)));
+}
+
+// The divide-and-conquer search borrowing its input, as it does now, against the
+// `Vec`-consuming signature it used to have, where callers that keep their points
+// have to clone them for every call
+#[divan::bench(args = [1000, 100000, 1000000, 10000000])]
+fn input_borrowed(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_optimized(divan::black_box(&points)));
+}
+
+#[divan::bench(args = [1000, 100000, 1000000, 10000000])]
+fn input_cloned(bencher: divan::Bencher, n: usize) {
+    let points = random_points(n, u32::pow(2, 31) - 1, 42);
+    bencher.bench(|| closest_pair_optimized_owned(divan::black_box(&points).clone()));
+}
+
+fn closest_pair_optimized_owned(points: Vec<Point>) -> (Point, Point, f32) {
+    closest_pair_optimized(&points)
+}
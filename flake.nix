@@ -90,6 +90,11 @@
                 cargoClippyExtraArgs = "--all-targets -- --deny warnings";
               });
             "${name}-docs" = craneLib.cargoDoc (commonArgs // {inherit cargoArtifacts;});
+            "${name}-no-std" = craneLib.cargoBuild (commonArgs
+              // {
+                inherit cargoArtifacts;
+                cargoExtraArgs = "--locked --no-default-features";
+              });
             "${name}-fmt" = craneLib.cargoFmt {inherit src;};
             "${name}-toml-fmt" = craneLib.taploFmt {
               src = pkgs.lib.sources.sourceFilesBySuffices src [".toml"];
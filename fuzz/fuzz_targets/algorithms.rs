@@ -0,0 +1,68 @@
+//! Feeds arbitrary points to the divide-and-conquer and bit shift algorithms.
+//!
+//! The first byte picks the number of bits for the bit shift packing, and every
+//! following 8 bytes are a point, as little-endian `x` and `y`. Only the `try_*`
+//! functions are called, so empty input and single points come back as errors,
+//! and any panic is a bug.
+
+#![no_main]
+
+use closest_pair_rs::algorithms::*;
+use closest_pair_rs::utils::*;
+use libfuzzer_sys::fuzz_target;
+
+/// Inputs up to this many points are also checked against brute force.
+const CROSS_CHECK_LIMIT: usize = 64;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&bits, data)) = data.split_first() else {
+        return;
+    };
+    let points: Vec<Point> = data
+        .chunks_exact(8)
+        .map(|chunk| Point {
+            x: u32::from_le_bytes(chunk[..4].try_into().unwrap()),
+            y: u32::from_le_bytes(chunk[4..].try_into().unwrap()),
+        })
+        .collect();
+
+    let optimized = try_closest_pair_optimized(&points);
+
+    // 0 and anything above 32 are rejected, and so are coordinates too wide for
+    // `bits`, so mask valid widths to reach the search itself most of the time
+    let bits = bits % 34;
+    let packed: Vec<Point> = if (1..=32).contains(&bits) {
+        let mask = u32::MAX >> (32 - bits);
+        points
+            .iter()
+            .map(|p| Point {
+                x: p.x & mask,
+                y: p.y & mask,
+            })
+            .collect()
+    } else {
+        points.clone()
+    };
+    let bit_shift = try_closest_pair_bit_shift(packed.clone(), bits);
+
+    if points.len() > CROSS_CHECK_LIMIT {
+        return;
+    }
+
+    match optimized {
+        Ok((p1, p2, distance)) => {
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            assert_eq!(distance, expected);
+            assert_eq!(eucid_distance(&p1, &p2), distance);
+        }
+        Err(_) => assert!(points.len() < 2),
+    }
+
+    // The bit shift search is a heuristic: it returns a real pair, but not
+    // always the closest one
+    if let Ok((p1, p2, distance)) = bit_shift {
+        let (_, _, expected) = closest_pair_brute_force(&packed);
+        assert!(distance >= expected);
+        assert_eq!(eucid_distance(&p1, &p2), distance);
+    }
+});
@@ -0,0 +1,23 @@
+//! Feeds arbitrary bytes to the CSV reader, and whatever it accepts to the search.
+//!
+//! Malformed input must come back as an error, never a panic, and points that
+//! were read must survive a round trip through `write_points_csv`.
+
+#![no_main]
+
+use closest_pair_rs::algorithms::*;
+use closest_pair_rs::io::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(points) = read_points_csv(data) else {
+        return;
+    };
+
+    let mut csv = Vec::new();
+    write_points_csv(&mut csv, &points).unwrap();
+    assert_eq!(read_points_csv(csv.as_slice()).unwrap(), points);
+
+    let result = try_closest_pair_optimized(&points);
+    assert_eq!(result.is_ok(), points.len() >= 2);
+});
@@ -1,5 +1,67 @@
+use crate::error::ClosestPairError;
+#[cfg(feature = "std")]
+use crate::result::Timings;
+use crate::result::{ClosestPairResult, Origin};
 use crate::utils::*;
-use std::cmp::min;
+use alloc::collections::{BTreeSet, BinaryHeap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{min, Ordering};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasherDefault, Hasher};
+#[cfg(feature = "std")]
+use core::ops::RangeInclusive;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// An item the closest pair search can run on: a bare point, or a point tagged
+/// with its position in the input slice.
+pub(crate) trait Located<T>: Copy {
+    fn loc(&self) -> Point<T>;
+}
+
+impl<T: Coordinate> Located<T> for Point<T> {
+    #[inline]
+    fn loc(&self) -> Point<T> {
+        *self
+    }
+}
+
+impl<T: Coordinate> Located<T> for (usize, Point<T>) {
+    #[inline]
+    fn loc(&self) -> Point<T> {
+        self.1
+    }
+}
+
+/// Input size up to which [`closest_pair`] uses brute force rather than divide and conquer.
+///
+/// Brute force does no sorting or allocation, which makes up for its O(n²)
+/// comparisons on small inputs. The crossover in the `crossover_*` benchmarks,
+/// on uniformly random points, is at around 32 points: brute force is clearly
+/// faster below it, and divide and conquer clearly faster from about 48 points.
+pub const BRUTE_FORCE_THRESHOLD: usize = 32;
+
+/// Subproblem size at and below which [`closest_pair_optimized`] switches to brute force.
+///
+/// Below this size the recursion costs more than the O(n²) comparisons it saves.
+/// Tuned with the `cutoff_sweep` benchmark, see [`closest_pair_optimized_with_cutoff`].
+pub const DEFAULT_BRUTE_FORCE_CUTOFF: usize = 16;
+
+/// Input size up to which [`closest_pair_checked`] cross-checks its result with brute force.
+///
+/// Brute force on this many points takes a few milliseconds in a release build,
+/// larger inputs are returned unchecked.
+pub const CHECKED_BRUTE_FORCE_LIMIT: usize = 2048;
+
+/// Validates that there are enough points to form a pair.
+pub(crate) fn check_input<P>(points: &[P]) -> Result<(), ClosestPairError> {
+    match points.len() {
+        0 => Err(ClosestPairError::EmptyInput),
+        1 => Err(ClosestPairError::SinglePoint),
+        _ => Ok(()),
+    }
+}
 
 /// Find closest pair of points using brute force algorithm.
 ///
@@ -21,7 +83,8 @@ use std::cmp::min;
 ///
 /// * When the input vector is empty
 /// * When there's only one point in the vector
-/// * When all distances between points are infinite
+/// * When the closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
 ///
 /// # Examples
 ///
@@ -37,52 +100,64 @@ use std::cmp::min;
 /// let (p1, p2, distance) =  closest_pair_brute_force(&points);
 /// assert_eq!(distance, 3.0);
 /// ```
-pub fn closest_pair_brute_force(points: &[Point]) -> (Point, Point, f32) {
-    // Check if points vector is empty
-    if points.is_empty() {
-        panic!("Cannot find closest pair with empty vector");
-    }
-
-    // Check if there's only one point
-    if points.len() < 2 {
-        panic!("Need at least two points to find closest pair");
-    }
+pub fn closest_pair_brute_force<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_brute_force(points).unwrap_or_else(|err| panic!("{err}"))
+}
 
-    let mut min_dist = f32::INFINITY;
-    // Initialize with the first two points
-    let mut point1 = points[0];
-    let mut point2 = points[1];
+/// Find closest pair of points using brute force algorithm, without panicking.
+///
+/// Same as [`closest_pair_brute_force`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * The closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_brute_force;
+///
+/// let points = vec![Point { x: 0, y: 0 }];
+/// assert_eq!(try_closest_pair_brute_force(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_brute_force<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
 
-    for i in 0..points.len() {
-        for j in (i + 1)..points.len() {
-            let dist = eucid_distance(&points[i], &points[j]);
-            if dist < min_dist {
-                min_dist = dist;
-                point1 = points[i];
-                point2 = points[j];
-            }
-        }
-    }
+    let (point1, point2, min_dist_sq) = brute_force_raw(points, Metric::Euclidean);
+    let min_dist = Metric::Euclidean.finish::<T>(min_dist_sq);
 
-    // Einstein was real
+    // Einstein was real. Integer distances always fit, but float coordinates
+    // can be far enough apart to overflow to infinity
     if min_dist == f32::INFINITY {
-        panic!("No closest pair found - all distances might be infinite");
+        return Err(ClosestPairError::InfiniteDistance);
     }
 
-    (point1, point2, min_dist)
+    Ok((point1, point2, min_dist))
 }
 
-/// Recursive helper function for the divide-and-conquer closest pair algorithm.
+/// Find closest pair of points using brute force algorithm, straight from an iterator.
 ///
-/// This function implements the core divide-and-conquer strategy by:
-/// 1. Dividing points into left and right halves based on x-coordinates
-/// 2. Recursively finding closest pairs in each half
-/// 3. Checking for closer pairs that might span the dividing line
+/// Same as [`closest_pair_brute_force`], but the points can come from a lazy
+/// iterator instead of a slice, so they never have to be collected. The iterator
+/// is cloned to walk the points after each one, so it should be cheap to clone and
+/// yield the same points every time. Prefer [`closest_pair_brute_force`] when the
+/// points are already in memory.
 ///
 /// # Arguments
 ///
-/// * `xsorted` - Vector of points sorted by x-coordinate
-/// * `ysorted` - Vector of points sorted by y-coordinate
+/// * `points` - Iterator over the points to analyze
 ///
 /// # Returns
 ///
@@ -90,78 +165,175 @@ pub fn closest_pair_brute_force(points: &[Point]) -> (Point, Point, f32) {
 /// * The first point of the closest pair
 /// * The second point of the closest pair
 /// * The distance between these points as a f32
-fn rec(xsorted: &[Point], ysorted: &[Point]) -> (Point, Point, f32) {
-    let n = xsorted.len();
-
-    if n <= 3 {
-        closest_pair_brute_force(xsorted)
-    } else {
-        let mid_idx = n / 2;
-        let midpoint = xsorted[n / 2];
-
-        let xsorted_left = &xsorted[..mid_idx];
-        let xsorted_right = &xsorted[mid_idx..];
+///
+/// # Panics
+///
+/// * When the iterator is empty
+/// * When the iterator yields only one point
+/// * When the closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_iter;
+///
+/// let points = (0..10u32).map(|i| Point { x: i * i, y: 0 });
+/// let (p1, p2, distance) = closest_pair_brute_force_iter(points);
+/// assert_eq!((p1, p2, distance), (Point { x: 0, y: 0 }, Point { x: 1, y: 0 }, 1.0));
+/// ```
+pub fn closest_pair_brute_force_iter<T: Coordinate>(
+    points: impl Iterator<Item = Point<T>> + Clone,
+) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_brute_force_iter(points).unwrap_or_else(|err| panic!("{err}"))
+}
 
-        let mut ysorted_left = Vec::with_capacity(mid_idx);
-        let mut ysorted_right = Vec::with_capacity(n - mid_idx);
+/// Find closest pair of points using brute force algorithm, straight from an iterator, without panicking.
+///
+/// Same as [`closest_pair_brute_force_iter`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Iterator over the points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The iterator is empty
+/// * The iterator yields only one point
+/// * The closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_brute_force_iter;
+///
+/// let points = std::iter::once(Point { x: 0, y: 0 });
+/// assert_eq!(try_closest_pair_brute_force_iter(points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_brute_force_iter<T: Coordinate>(
+    points: impl Iterator<Item = Point<T>> + Clone,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    let mut first_two = points.clone();
+    let (Some(mut point1), Some(mut point2)) = (first_two.next(), first_two.next()) else {
+        return Err(match points.clone().next() {
+            None => ClosestPairError::EmptyInput,
+            Some(_) => ClosestPairError::SinglePoint,
+        });
+    };
+    let mut min_dist_sq = eucid_distance_sq(&point1, &point2);
 
-        for &point in ysorted {
-            if point.x <= midpoint.x {
-                ysorted_left.push(point);
-            } else {
-                ysorted_right.push(point);
+    let mut rest = points;
+    while let Some(p1) = rest.next() {
+        for p2 in rest.clone() {
+            let dist_sq = eucid_distance_sq(&p1, &p2);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = p1;
+                point2 = p2;
             }
         }
+    }
+    let min_dist = Metric::Euclidean.finish::<T>(min_dist_sq);
 
-        let (p1_left, p2_left, delta_left) = rec(xsorted_left, &ysorted_left);
-        let (p1_right, p2_right, delta_right) = rec(xsorted_right, &ysorted_right);
+    // Einstein was real. Integer distances always fit, but float coordinates
+    // can be far enough apart to overflow to infinity
+    if min_dist == f32::INFINITY {
+        return Err(ClosestPairError::InfiniteDistance);
+    }
 
-        let (mut p1, mut p2, mut delta) = if delta_left < delta_right {
-            (p1_left, p2_left, delta_left)
-        } else {
-            (p1_right, p2_right, delta_right)
-        };
+    Ok((point1, point2, min_dist))
+}
+
+/// Find closest pair of points using brute force algorithm, with a f64 distance.
+///
+/// Same as [`closest_pair_brute_force`], but the distance is computed and returned in double
+/// precision. Past `2^24` a f32 distance is rounded to a multiple of a power of
+/// two, while a f64 one stays accurate for any coordinates.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f64
+///
+/// # Panics
+///
+/// Same as [`closest_pair_brute_force`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_f64;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1 << 30, y: 0 },
+///     Point { x: (1 << 31) + 1, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_brute_force_f64(&points);
+/// assert_eq!(distance, 1073741824.0);
+/// ```
+pub fn closest_pair_brute_force_f64<T: Coordinate>(
+    points: &[Point<T>],
+) -> (Point<T>, Point<T>, f64) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
 
-        // Find points in the band
-        let mut in_band = Vec::new();
-        let midpoint_x = midpoint.x;
+    let (point1, point2, min_dist_sq) = brute_force_raw(points, Metric::Euclidean);
+    let min_dist = Metric::Euclidean.finish_f64::<T>(min_dist_sq);
+    if min_dist == f64::INFINITY {
+        panic!("{}", ClosestPairError::InfiniteDistance);
+    }
 
-        for &point in ysorted {
-            // Notice we need to handle unsigned integers carefully
-            let delta_u32 = delta as u32;
-            let left_bound = midpoint_x.saturating_sub(delta_u32);
-            let right_bound = midpoint_x.saturating_add(delta_u32);
+    (point1, point2, min_dist)
+}
 
-            if point.x >= left_bound && point.x <= right_bound {
-                in_band.push(point);
-            }
-        }
+/// Brute force search returning the raw distance of the closest pair under `metric`.
+///
+/// Expects at least two points.
+fn brute_force_raw<T: Coordinate, P: Located<T>>(
+    points: &[P],
+    metric: Metric,
+) -> (P, P, T::Distance) {
+    // Initialize with the first two points
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+    let mut min_dist_raw = metric.raw_distance(&point1.loc(), &point2.loc());
 
-        // Check points in the band
-        for i in 0..in_band.len() {
-            for j in (i + 1)..min(i + 7, in_band.len()) {
-                let d = eucid_distance(&in_band[i], &in_band[j]);
-                if d < delta {
-                    p1 = in_band[i];
-                    p2 = in_band[j];
-                    delta = d;
-                }
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let dist_raw = metric.raw_distance(&points[i].loc(), &points[j].loc());
+            if dist_raw < min_dist_raw {
+                min_dist_raw = dist_raw;
+                point1 = points[i];
+                point2 = points[j];
             }
         }
-
-        (p1, p2, delta)
     }
+
+    (point1, point2, min_dist_raw)
 }
 
-/// Find the closest pair of points using an optimized divide-and-conquer algorithm.
+/// Find closest pair of points using a brute force search spread across threads.
 ///
-/// This function implements an O(n log n) algorithm for finding the closest pair
-/// of points in a set. It's significantly faster than brute force for large inputs.
+/// The outer loop of [`closest_pair_brute_force`] is split across the rayon thread
+/// pool and the per-thread minimums are reduced into one. Ties are broken by the
+/// position of the pair in the input, so the result is deterministic and matches
+/// the serial version exactly, pair included. NaN distances, which only come from
+/// infinite coordinates, are skipped. Requires the `rayon` feature.
 ///
-/// The algorithm works by:
-/// 1. Sorting points by x and y coordinates
-/// 2. Recursively dividing the problem in half
-/// 3. Combining results and checking points near the dividing line
+/// Runs on the current rayon thread pool: the global one, or the pool of an
+/// enclosing `ThreadPool::install`, which is how to cap the threads it uses.
 ///
 /// # Arguments
 ///
@@ -178,52 +350,77 @@ fn rec(xsorted: &[Point], ysorted: &[Point]) -> (Point, Point, f32) {
 ///
 /// * When the input vector is empty
 /// * When there's only one point in the vector
+/// * When the closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
 ///
 /// # Examples
 ///
 /// ```
 /// use closest_pair_rs::utils::Point;
-/// use closest_pair_rs::algorithms::closest_pair_optimized;
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_parallel;
 ///
 /// let points = vec![
 ///     Point { x: 0, y: 0 },
-///     Point { x: 10, y: 10 },
-///     Point { x: 5, y: 5 },
-///     Point { x: 7, y: 7 }
+///     Point { x: 3, y: 0 },
+///     Point { x: 0, y: 4 }
 /// ];
-/// let (p1, p2, distance) = closest_pair_optimized(points);
-/// // The closest pair should be (5,5) and (7,7) with distance 2√2
+/// let (_, _, distance) = closest_pair_brute_force_parallel(&points);
+/// assert_eq!(distance, 3.0);
 /// ```
-pub fn closest_pair_optimized(points: Vec<Point>) -> (Point, Point, f32) {
-    // Check if points vector is empty
-    if points.is_empty() {
-        panic!("Cannot find closest pair with empty vector");
-    }
+#[cfg(feature = "rayon")]
+pub fn closest_pair_brute_force_parallel<T: Coordinate>(
+    points: &[Point<T>],
+) -> (Point<T>, Point<T>, f32) {
+    use rayon::prelude::*;
 
-    // Check if there's only one point
-    if points.len() < 2 {
-        panic!("Need at least two points to find closest pair");
-    }
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let n = points.len();
+    let best = (0..n - 1)
+        .into_par_iter()
+        .filter_map(|i| {
+            let mut best: Option<(T::Distance, usize, usize)> = None;
+            for j in (i + 1)..n {
+                let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+                // A NaN distance is never less than anything, so the serial
+                // search only keeps one it starts with
+                if dist_sq.partial_cmp(&dist_sq).is_none() {
+                    continue;
+                }
+                if best.is_none_or(|(d, _, _)| dist_sq < d) {
+                    best = Some((dist_sq, i, j));
+                }
+            }
+            best
+        })
+        // Without NaNs distances are totally ordered, and comparing (distance, i, j)
+        // keeps the first pair found in serial order
+        .min_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .expect("NaN distances are skipped")
+                .then((a.1, a.2).cmp(&(b.1, b.2)))
+        });
 
-    // Sort by x and y coordinates
-    let mut xsorted = points.clone();
-    xsorted.sort_by(|a, b| a.x.cmp(&b.x));
+    let (min_dist_sq, i, j) =
+        best.unwrap_or_else(|| (eucid_distance_sq(&points[0], &points[1]), 0, 1));
+    let min_dist = Metric::Euclidean.finish::<T>(min_dist_sq);
 
-    let mut ysorted = points;
-    ysorted.sort_by(|a, b| a.y.cmp(&b.y));
+    // Same check as the serial version, far apart floats can overflow to infinity
+    if min_dist == f32::INFINITY {
+        panic!("{}", ClosestPairError::InfiniteDistance);
+    }
 
-    rec(&xsorted, &ysorted)
+    (points[i], points[j], min_dist)
 }
 
-/// Find closest pair of points using bit shift packing technique.
+/// Find closest pair of points under the given metric using brute force.
 ///
-/// This function uses bit manipulation to pack x and y coordinates into single values,
-/// which are then sorted to find closest pair hehe, and is much easier to understand
+/// Works like [`closest_pair_brute_force`], but measures distances with `metric`.
 ///
 /// # Arguments
 ///
 /// * `points` - Vector of points to analyze
-/// * `bits` - Number of bits to use for each coordinate when packing
+/// * `metric` - The metric to measure distances with
 ///
 /// # Returns
 ///
@@ -236,323 +433,9096 @@ pub fn closest_pair_optimized(points: Vec<Point>) -> (Point, Point, f32) {
 ///
 /// * When the input vector is empty
 /// * When there's only one point in the vector
-/// * When all distances between points are infinite
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{Metric, Point};
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_metric;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 3 },
+///     Point { x: 20, y: 0 },
+///     Point { x: 25, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_brute_force_metric(&points, Metric::Manhattan);
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn closest_pair_brute_force_metric<T: Coordinate>(
+    points: &[Point<T>],
+    metric: Metric,
+) -> (Point<T>, Point<T>, f32) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let (p1, p2, dist_raw) = brute_force_raw(points, metric);
+    (p1, p2, metric.finish::<T>(dist_raw))
+}
+
+/// Find closest pair of points under a distance function of your own, using brute force.
+///
+/// The most flexible way to search: `dist` can be any measure, such as a
+/// great-circle distance or one that reads data kept next to the points. Since
+/// nothing is known about it, every pair is checked in O(n²) time; the
+/// divide-and-conquer speedups rely on properties of the built-in [`Metric`]s
+/// and aren't available here. For those metrics, prefer
+/// [`closest_pair_optimized_metric`].
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `dist` - Distance between two points. NaN and infinite distances never win
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as returned by `dist`
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When no pair has a finite distance
 ///
 /// # Examples
 ///
 /// ```
 /// use closest_pair_rs::utils::Point;
-/// use closest_pair_rs::algorithms::closest_pair_bit_shift;
+/// use closest_pair_rs::algorithms::closest_pair_by;
 ///
 /// let points = vec![
 ///     Point { x: 0, y: 0 },
-///     Point { x: 10, y: 10 },
-///     Point { x: 5, y: 5 },
-///     Point { x: 7, y: 7 }
+///     Point { x: 3, y: 3 },
+///     Point { x: 20, y: 0 },
+///     Point { x: 25, y: 0 }
 /// ];
-/// let (p1, p2, distance) = closest_pair_bit_shift(points, 8);
-/// // The closest pair should be (5,5) and (7,7) with distance 2√2
+/// let manhattan = |a: &Point, b: &Point| (a.x.abs_diff(b.x) + a.y.abs_diff(b.y)) as f32;
+/// let (p1, p2, distance) = closest_pair_by(&points, manhattan);
+/// assert_eq!((p1, p2, distance), (points[2], points[3], 5.0));
 /// ```
-pub fn closest_pair_bit_shift(points: Vec<Point>, bits: u8) -> (Point, Point, f32) {
-    // Check if points vector is empty
-    if points.is_empty() {
-        panic!("Cannot find closest pair with empty vector");
-    }
+pub fn closest_pair_by<T: Coordinate>(
+    points: &[Point<T>],
+    dist: impl Fn(&Point<T>, &Point<T>) -> f32,
+) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_by(points, dist).unwrap_or_else(|err| panic!("{err}"))
+}
 
-    // Check if there's only one point
-    if points.len() < 2 {
-        panic!("Need at least two points to find closest pair");
-    }
+/// Find closest pair of points under a distance function of your own, without panicking.
+///
+/// Same as [`closest_pair_by`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `dist` - Distance between two points. NaN and infinite distances never win
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * No pair has a finite distance
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_by;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+/// assert_eq!(
+///     try_closest_pair_by(&points, |_, _| f32::NAN).unwrap_err(),
+///     ClosestPairError::InfiniteDistance
+/// );
+/// ```
+pub fn try_closest_pair_by<T: Coordinate>(
+    points: &[Point<T>],
+    dist: impl Fn(&Point<T>, &Point<T>) -> f32,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
 
-    let n = points.len();
+    let mut best = None;
     let mut min_dist = f32::INFINITY;
-    // Initialize with the first two points
-    let mut point1 = points[0];
-    let mut point2 = points[1];
-
-    // Pack the points into single values
-    let mut packed: Vec<u64> = points
-        .iter()
-        .map(|p| pack_numbers(p.x, p.y, bits))
-        .collect();
 
-    // can use unstable sort as we do not care about the order of identical elements, win
-    packed.sort_unstable();
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            // NaN compares false, so it never replaces the best pair
+            let d = dist(&points[i], &points[j]);
+            if d < min_dist {
+                min_dist = d;
+                best = Some((points[i], points[j]));
+            }
+        }
+    }
 
-    for i in 0..n - 1 {
-        let (x1, y1) = unpack_numbers(packed[i], bits);
-        let p1 = Point { x: x1, y: y1 };
+    let (p1, p2) = best.ok_or(ClosestPairError::InfiniteDistance)?;
+    Ok((p1, p2, min_dist))
+}
 
-        for j in packed
-            .iter()
-            .take(std::cmp::min(n, i + bits as usize + 1))
-            .skip(i + 1)
-        {
-            let (x2, y2) = unpack_numbers(*j, bits);
-            let p2 = Point { x: x2, y: y2 };
+/// Number of band points, counting the point itself, to compare in the strip step.
+///
+/// Let `delta` be the best distance within either half. A band pair only matters if it
+/// is strictly closer than `delta`, so if `p` and `q` are that pair, with `q` later in y
+/// order, every band point from `p` to `q` lies in the rectangle `[mid - delta, mid + delta]`
+/// x `[p.y, p.y + delta)`, which is open at the top. The points of one half are pairwise at
+/// least `delta` apart (duplicates within a half make `delta` zero, and then no band pair
+/// can beat it), and each half only covers one `delta` x `delta` square of the rectangle.
+///
+/// Four points pairwise `delta` apart only fit in a closed square at its corners, so the
+/// half-open square holds at most 3 under Euclidean distance and 2 under Chebyshev: `q` is
+/// at most 5 places after `p`, and a window of 7 leaves a point of slack. Under Manhattan
+/// distance a square holds up to 5 (the centre fits too), so the window grows to 10.
+///
+/// The bound holds for `delta` as it comes out of the halves. A closer pair found while
+/// scanning only lowers the distance later pairs have to beat, so it never needs more.
+fn strip_window(metric: Metric) -> usize {
+    match metric {
+        Metric::Euclidean | Metric::Chebyshev => 7,
+        Metric::Manhattan => 10,
+    }
+}
 
-            let distance = eucid_distance(&p1, &p2);
+/// A candidate pair, ordered by distance and then by its position in the input.
+///
+/// Breaking ties by position makes the selection of equidistant pairs deterministic.
+#[derive(Debug, Clone)]
+pub(crate) struct Candidate<D> {
+    pub(crate) dist: D,
+    pub(crate) i: usize,
+    pub(crate) j: usize,
+}
 
-            if distance < min_dist {
-                min_dist = distance;
-                point1 = p1;
-                point2 = p2;
-            }
-        }
+impl<D: PartialOrd> Ord for Candidate<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(Ordering::Equal)
+            .then((self.i, self.j).cmp(&(other.i, other.j)))
     }
-    // Einstein was real
-    if min_dist == f32::INFINITY {
-        panic!("No closest pair found - all distances might be infinite");
+}
+
+impl<D: PartialOrd> PartialOrd for Candidate<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    (point1, point2, min_dist)
+impl<D: PartialOrd> PartialEq for Candidate<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<D: PartialOrd> Eq for Candidate<D> {}
+
+/// Find the `k` pairs of points with the smallest distances.
+///
+/// Every pair is visited once while a max-heap keeps the `k` best pairs seen so far,
+/// so this runs in O(n² log k) time and O(k) extra memory. Pairs with equal
+/// distances are ordered by their position in the input (first by the index of
+/// the first point, then of the second), so the output is deterministic.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `k` - Number of pairs to return
+///
+/// # Returns
+///
+/// Up to `k` tuples of (first point, second point, distance), sorted by ascending
+/// distance. When fewer than `k` pairs exist, all of them are returned; `k == 0`
+/// or fewer than two points give an empty vector.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::k_closest_pairs;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 0 },
+///     Point { x: 0, y: 4 }
+/// ];
+/// let pairs = k_closest_pairs(&points, 2);
+/// assert_eq!(pairs.len(), 2);
+/// assert_eq!(pairs[0].2, 3.0);
+/// assert_eq!(pairs[1].2, 4.0);
+/// ```
+pub fn k_closest_pairs<T: Coordinate>(
+    points: &[Point<T>],
+    k: usize,
+) -> Vec<(Point<T>, Point<T>, f32)> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // The heap never holds more than k pairs, nor more pairs than there are
+    let n = points.len();
+    let pair_count = n.saturating_mul(n.saturating_sub(1)) / 2;
+    let mut heap: BinaryHeap<Candidate<T::Distance>> = BinaryHeap::with_capacity(k.min(pair_count));
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let candidate = Candidate {
+                dist: eucid_distance_sq(&points[i], &points[j]),
+                i,
+                j,
+            };
+
+            if heap.len() < k {
+                heap.push(candidate);
+            } else if candidate < *heap.peek().unwrap() {
+                heap.pop();
+                heap.push(candidate);
+            }
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|c| {
+            (
+                points[c.i],
+                points[c.j],
+                Metric::Euclidean.finish::<T>(c.dist),
+            )
+        })
+        .collect()
+}
+
+/// Find the point in a set that is nearest to a query point.
+///
+/// Scans every point once, in O(n) time. To answer many queries against the same
+/// points, build a [`NearestNeighborIndex`](crate::index::NearestNeighborIndex)
+/// once instead.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to search
+/// * `query` - The point to search around, which doesn't need to be in `points`
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The nearest point, the first one in `points` if several are equally near
+/// * The distance between it and `query` as a f32, 0 if `query` is in `points`
+///
+/// # Panics
+///
+/// * When the input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::nearest_neighbor;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 20, y: 0 }
+/// ];
+/// let (nearest, distance) = nearest_neighbor(&points, Point { x: 7, y: 6 });
+/// assert_eq!((nearest, distance), (points[1], 5.0));
+/// ```
+pub fn nearest_neighbor<T: Coordinate>(points: &[Point<T>], query: Point<T>) -> (Point<T>, f32) {
+    try_nearest_neighbor(points, query).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the point in a set that is nearest to a query point, without panicking.
+///
+/// Same as [`nearest_neighbor`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to search
+/// * `query` - The point to search around, which doesn't need to be in `points`
+///
+/// # Returns
+///
+/// The nearest point and its distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_nearest_neighbor;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_nearest_neighbor(&points, Point { x: 1, y: 1 }).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+pub fn try_nearest_neighbor<T: Coordinate>(
+    points: &[Point<T>],
+    query: Point<T>,
+) -> Result<(Point<T>, f32), ClosestPairError> {
+    let (&first, rest) = points.split_first().ok_or(ClosestPairError::EmptyInput)?;
+
+    let mut nearest = (first, eucid_distance_sq(&first, &query));
+    for &p in rest {
+        let dist_sq = eucid_distance_sq(&p, &query);
+        if dist_sq < nearest.1 {
+            nearest = (p, dist_sq);
+        }
+    }
+
+    Ok((nearest.0, Metric::Euclidean.finish::<T>(nearest.1)))
+}
+
+/// Find the nearest point in a set for each position of a query moving along a path.
+///
+/// Builds a [`NearestNeighborIndex`](crate::index::NearestNeighborIndex) over
+/// `points` once, in O(n log n) time, and answers every path position from it,
+/// rather than scanning all of `points` for each position as calling
+/// [`nearest_neighbor`] in a loop would.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to search
+/// * `path` - The positions of the query, in order
+///
+/// # Returns
+///
+/// For each position in `path`, the nearest point and its distance to the
+/// position as a f32. If several points are equally near, any one of them may
+/// be returned.
+///
+/// # Panics
+///
+/// * When the input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::nearest_along_path;
+///
+/// let points = vec![Point { x: 0, y: 10 }, Point { x: 20, y: 10 }];
+/// let path = vec![Point { x: 0, y: 0 }, Point { x: 15, y: 0 }];
+/// assert_eq!(
+///     nearest_along_path(&points, &path),
+///     vec![(points[0], 10.0), (points[1], (125.0f64).sqrt() as f32)]
+/// );
+/// ```
+pub fn nearest_along_path<T: Coordinate>(
+    points: &[Point<T>],
+    path: &[Point<T>],
+) -> Vec<(Point<T>, f32)> {
+    if points.is_empty() {
+        panic!("{}", ClosestPairError::EmptyInput);
+    }
+
+    let index = crate::index::NearestNeighborIndex::new(points);
+    path.iter()
+        .map(|&query| index.nearest(query).expect("index is not empty"))
+        .collect()
+}
+
+/// Find the point in a set that is nearest to the set's centroid.
+///
+/// The centroid, the mean of the coordinates, generally isn't one of the points,
+/// so every point is measured against it, in O(n) time. The centroid and the
+/// distances stay in `f64` until the nearest point is picked, unlike [`centroid`]
+/// and [`distance_to_coord`] which round to `f32`, so points of large coordinates
+/// that are only a few units apart are still told apart. When several points are
+/// equally near, the first of them in `points` is returned.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to search
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The point nearest to the centroid
+/// * The distance between it and the centroid as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_to_centroid;
+///
+/// // The centroid is (4, 3)
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 8, y: 0 },
+///     Point { x: 4, y: 9 }
+/// ];
+/// assert_eq!(closest_to_centroid(&points), (points[0], 5.0));
+/// ```
+pub fn closest_to_centroid<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, f32) {
+    try_closest_to_centroid(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the point in a set that is nearest to the set's centroid, without panicking.
+///
+/// Same as [`closest_to_centroid`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to search
+///
+/// # Returns
+///
+/// The nearest point and its distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_to_centroid;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_closest_to_centroid(&points).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+pub fn try_closest_to_centroid<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, f32), ClosestPairError> {
+    if points.is_empty() {
+        return Err(ClosestPairError::EmptyInput);
+    }
+
+    let (x, y) = mean_f64(points);
+    let dist_sq = |p: &Point<T>| {
+        let (dx, dy) = (p.x.to_f64() - x, p.y.to_f64() - y);
+        dx * dx + dy * dy
+    };
+
+    let mut nearest = (points[0], dist_sq(&points[0]));
+    for p in &points[1..] {
+        let d = dist_sq(p);
+        if d < nearest.1 {
+            nearest = (*p, d);
+        }
+    }
+
+    Ok((nearest.0, sqrt(nearest.1) as f32))
+}
+
+/// Find every pair of points that are each other's nearest neighbor.
+///
+/// Each point's nearest neighbor distance is found by brute force, in O(n²) time,
+/// and a pair is kept when its distance equals the nearest neighbor distance of
+/// both of its points. A point with several neighbors at the same smallest
+/// distance treats all of them as nearest, so it can be in more than one pair.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A vector of (first point, second point, distance) tuples. Each pair appears
+/// once, with the point that comes first in `points` first, and the pairs are
+/// ordered by the positions of their points in the input. Fewer than two points
+/// give an empty vector.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::mutual_nearest_neighbors;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 2, y: 0 },
+///     Point { x: 5, y: 0 },
+///     Point { x: 9, y: 0 }
+/// ];
+/// // (5, 0) is closest to (2, 0), but (2, 0) is closer to (0, 0)
+/// let pairs = mutual_nearest_neighbors(&points);
+/// assert_eq!(pairs, vec![(points[0], points[1], 2.0)]);
+/// ```
+pub fn mutual_nearest_neighbors<T: Coordinate>(
+    points: &[Point<T>],
+) -> Vec<(Point<T>, Point<T>, f32)> {
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    let mut nearest: Vec<Option<T::Distance>> = vec![None; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+            for k in [i, j] {
+                if nearest[k].is_none_or(|best| dist_sq < best) {
+                    nearest[k] = Some(dist_sq);
+                }
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+            if nearest[i] == Some(dist_sq) && nearest[j] == Some(dist_sq) {
+                pairs.push((points[i], points[j], Metric::Euclidean.finish::<T>(dist_sq)));
+            }
+        }
+    }
+    pairs
+}
+
+/// Find the closest pair of points with one point taken from each of two sets.
+///
+/// Pairs within the same set are ignored. Every point of `a` is compared with
+/// every point of `b`, so this runs in O(|a| · |b|) time.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The point from `a`
+/// * The point from `b`
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When either set is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_bichromatic;
+///
+/// let red = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+/// let blue = vec![Point { x: 10, y: 0 }, Point { x: 4, y: 4 }];
+/// let (r, b, distance) = closest_pair_bichromatic(&red, &blue);
+/// assert_eq!((r.x, r.y, b.x, b.y), (1, 0, 4, 4));
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn closest_pair_bichromatic<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_bichromatic(a, b).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points between two sets, without panicking.
+///
+/// Same as [`closest_pair_bichromatic`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// The closest pair and their distance, or [`ClosestPairError::EmptyInput`] when
+/// either set is empty.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_bichromatic;
+///
+/// let red = vec![Point { x: 0, y: 0 }];
+/// let blue: Vec<Point> = Vec::new();
+/// assert_eq!(try_closest_pair_bichromatic(&red, &blue).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_closest_pair_bichromatic<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    if a.is_empty() || b.is_empty() {
+        return Err(ClosestPairError::EmptyInput);
+    }
+
+    let mut point1 = a[0];
+    let mut point2 = b[0];
+    let mut min_dist_sq = eucid_distance_sq(&point1, &point2);
+
+    for p in a {
+        for q in b {
+            let dist_sq = eucid_distance_sq(p, q);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = *p;
+                point2 = *q;
+            }
+        }
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(min_dist_sq)))
+}
+
+/// Find the smallest distance between a point of one set and a point of another.
+///
+/// The distance of [`closest_pair_bichromatic`], for when the points themselves
+/// aren't needed. Sets that share a point are 0.0 apart.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// The distance between the closest point of `a` and point of `b` as a f32
+///
+/// # Panics
+///
+/// * When either set is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::min_distance_between_sets;
+///
+/// let queries = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+/// let references = vec![Point { x: 10, y: 0 }, Point { x: 4, y: 4 }];
+/// assert_eq!(min_distance_between_sets(&queries, &references), 5.0);
+/// ```
+pub fn min_distance_between_sets<T: Coordinate>(a: &[Point<T>], b: &[Point<T>]) -> f32 {
+    try_min_distance_between_sets(a, b).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the smallest distance between two sets of points, without panicking.
+///
+/// Same as [`min_distance_between_sets`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// The smallest distance, or a [`ClosestPairError`] when:
+/// * Either set is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_min_distance_between_sets;
+///
+/// let empty: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_min_distance_between_sets(&empty, &[Point { x: 1, y: 1 }]).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+pub fn try_min_distance_between_sets<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+) -> Result<f32, ClosestPairError> {
+    try_closest_pair_bichromatic(a, b).map(|(_, _, dist)| dist)
+}
+
+/// Find the farthest pair of points with one point taken from each of two sets.
+///
+/// Pairs within the same set are ignored. Every point of `a` is compared with
+/// every point of `b`, so this runs in O(|a| · |b|) time.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The point from `a`
+/// * The point from `b`
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When either set is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::farthest_pair_bichromatic;
+///
+/// let red = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+/// let blue = vec![Point { x: 3, y: 4 }, Point { x: 4, y: 4 }];
+/// let (r, b, distance) = farthest_pair_bichromatic(&red, &blue);
+/// assert_eq!((r, b), (red[0], blue[1]));
+/// assert_eq!(distance, (32.0f64).sqrt() as f32);
+/// ```
+pub fn farthest_pair_bichromatic<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+) -> (Point<T>, Point<T>, f32) {
+    try_farthest_pair_bichromatic(a, b).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the farthest pair of points between two sets, without panicking.
+///
+/// Same as [`farthest_pair_bichromatic`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `a` - First set of points
+/// * `b` - Second set of points
+///
+/// # Returns
+///
+/// The farthest pair and their distance, or [`ClosestPairError::EmptyInput`] when
+/// either set is empty.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_farthest_pair_bichromatic;
+///
+/// let red: Vec<Point> = Vec::new();
+/// let blue = vec![Point { x: 0, y: 0 }];
+/// assert_eq!(try_farthest_pair_bichromatic(&red, &blue).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_farthest_pair_bichromatic<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    if a.is_empty() || b.is_empty() {
+        return Err(ClosestPairError::EmptyInput);
+    }
+
+    let mut point1 = a[0];
+    let mut point2 = b[0];
+    let mut max_dist_sq = eucid_distance_sq(&point1, &point2);
+
+    for p in a {
+        for q in b {
+            let dist_sq = eucid_distance_sq(p, q);
+            if dist_sq > max_dist_sq {
+                max_dist_sq = dist_sq;
+                point1 = *p;
+                point2 = *q;
+            }
+        }
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(max_dist_sq)))
+}
+
+/// Find the distance between two clusters of points under a linkage rule.
+///
+/// [`Linkage::Single`] is the distance of [`closest_pair_bichromatic`] and
+/// [`Linkage::Complete`] the distance of [`farthest_pair_bichromatic`].
+/// [`Linkage::Average`] sums the distance of every pair in `f64` before
+/// dividing. All three compare every point of `a` with every point of `b`, in
+/// O(|a| · |b|) time.
+///
+/// # Arguments
+///
+/// * `a` - First cluster
+/// * `b` - Second cluster
+/// * `linkage` - How the pairwise distances are combined
+///
+/// # Returns
+///
+/// The distance between the clusters as a f32
+///
+/// # Panics
+///
+/// * When either cluster is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{Linkage, Point};
+/// use closest_pair_rs::algorithms::cluster_distance;
+///
+/// let a = vec![Point { x: 0, y: 0 }];
+/// let b = vec![Point { x: 3, y: 4 }, Point { x: 6, y: 8 }];
+/// assert_eq!(cluster_distance(&a, &b, Linkage::Single), 5.0);
+/// assert_eq!(cluster_distance(&a, &b, Linkage::Complete), 10.0);
+/// assert_eq!(cluster_distance(&a, &b, Linkage::Average), 7.5);
+/// ```
+pub fn cluster_distance<T: Coordinate>(a: &[Point<T>], b: &[Point<T>], linkage: Linkage) -> f32 {
+    try_cluster_distance(a, b, linkage).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the distance between two clusters of points, without panicking.
+///
+/// Same as [`cluster_distance`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `a` - First cluster
+/// * `b` - Second cluster
+/// * `linkage` - How the pairwise distances are combined
+///
+/// # Returns
+///
+/// The distance, or a [`ClosestPairError`] when:
+/// * Either cluster is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{Linkage, Point};
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_cluster_distance;
+///
+/// let empty: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_cluster_distance(&empty, &[Point { x: 1, y: 1 }], Linkage::Average).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+pub fn try_cluster_distance<T: Coordinate>(
+    a: &[Point<T>],
+    b: &[Point<T>],
+    linkage: Linkage,
+) -> Result<f32, ClosestPairError> {
+    match linkage {
+        Linkage::Single => try_closest_pair_bichromatic(a, b).map(|(_, _, dist)| dist),
+        Linkage::Complete => try_farthest_pair_bichromatic(a, b).map(|(_, _, dist)| dist),
+        Linkage::Average => {
+            if a.is_empty() || b.is_empty() {
+                return Err(ClosestPairError::EmptyInput);
+            }
+
+            let total: f64 = a
+                .iter()
+                .flat_map(|p| b.iter().map(move |q| eucid_distance_sq(p, q)))
+                .map(|dist_sq| Metric::Euclidean.finish_f64::<T>(dist_sq))
+                .sum();
+            Ok((total / (a.len() as f64 * b.len() as f64)) as f32)
+        }
+    }
+}
+
+/// Find the farthest pair of points (the diameter of the set) using brute force.
+///
+/// This function compares every possible pair of points and keeps the one that
+/// is farthest apart. It runs in O(n²) time.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the farthest pair
+/// * The second point of the farthest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::farthest_pair_brute_force;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 0 },
+///     Point { x: 0, y: 4 }
+/// ];
+/// let (_, _, distance) = farthest_pair_brute_force(&points);
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn farthest_pair_brute_force<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_farthest_pair_brute_force(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the farthest pair of points using brute force, without panicking.
+///
+/// Same as [`farthest_pair_brute_force`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The farthest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_farthest_pair_brute_force;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_farthest_pair_brute_force(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_farthest_pair_brute_force<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+    let mut max_dist_sq = eucid_distance_sq(&point1, &point2);
+
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+            if dist_sq > max_dist_sq {
+                max_dist_sq = dist_sq;
+                point1 = points[i];
+                point2 = points[j];
+            }
+        }
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(max_dist_sq)))
+}
+
+/// Find closest pair of 3D points using brute force algorithm.
+///
+/// This function compares every possible pair of points, so it runs in O(n²) time.
+///
+/// # Arguments
+///
+/// * `points` - Vector of 3D points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point3;
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_3d;
+///
+/// let points = vec![
+///     Point3 { x: 0, y: 0, z: 0 },
+///     Point3 { x: 0, y: 0, z: 9 },
+///     Point3 { x: 2, y: 3, z: 6 }
+/// ];
+/// let (_, _, distance) = closest_pair_brute_force_3d(&points);
+/// assert_eq!(distance, (22.0f64).sqrt() as f32);
+/// ```
+pub fn closest_pair_brute_force_3d<T: Coordinate>(
+    points: &[Point3<T>],
+) -> (Point3<T>, Point3<T>, f32) {
+    try_closest_pair_brute_force_3d(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find closest pair of 3D points using brute force algorithm, without panicking.
+///
+/// Same as [`closest_pair_brute_force_3d`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of 3D points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point3;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_brute_force_3d;
+///
+/// let points = vec![Point3 { x: 1, y: 2, z: 3 }];
+/// assert_eq!(try_closest_pair_brute_force_3d(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_brute_force_3d<T: Coordinate>(
+    points: &[Point3<T>],
+) -> Result<(Point3<T>, Point3<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+    let mut min_dist_sq = eucid_distance_sq_3d(&point1, &point2);
+
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let dist_sq = eucid_distance_sq_3d(&points[i], &points[j]);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = points[i];
+                point2 = points[j];
+            }
+        }
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(min_dist_sq)))
+}
+
+/// Find closest pair of points in any number of dimensions using brute force algorithm.
+///
+/// This function compares every possible pair of points, so it runs in O(n²) time
+/// whatever the dimension. There's no divide and conquer version, as those split
+/// the plane and are 2D only.
+///
+/// # Arguments
+///
+/// * `points` - Vector of `D` dimensional points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::PointN;
+/// use closest_pair_rs::algorithms::closest_pair_brute_force_n;
+///
+/// let points = vec![
+///     PointN { coords: [0, 0, 0, 0] },
+///     PointN { coords: [0, 0, 0, 9] },
+///     PointN { coords: [1, 1, 1, 1] }
+/// ];
+/// let (_, _, distance) = closest_pair_brute_force_n(&points);
+/// assert_eq!(distance, 2.0);
+/// ```
+pub fn closest_pair_brute_force_n<const D: usize, T: Coordinate>(
+    points: &[PointN<D, T>],
+) -> (PointN<D, T>, PointN<D, T>, f32) {
+    try_closest_pair_brute_force_n(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find closest pair of points in any number of dimensions using brute force
+/// algorithm, without panicking.
+///
+/// Same as [`closest_pair_brute_force_n`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of `D` dimensional points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::PointN;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_brute_force_n;
+///
+/// let points = vec![PointN { coords: [1, 2, 3, 4] }];
+/// assert_eq!(try_closest_pair_brute_force_n(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_brute_force_n<const D: usize, T: Coordinate>(
+    points: &[PointN<D, T>],
+) -> Result<(PointN<D, T>, PointN<D, T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+    let mut min_dist_sq = eucid_distance_sq_n(&point1, &point2);
+
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let dist_sq = eucid_distance_sq_n(&points[i], &points[j]);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = points[i];
+                point2 = points[j];
+            }
+        }
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(min_dist_sq)))
+}
+
+/// Find the closest pair of positions on the Earth by great-circle distance.
+///
+/// Distances are measured along the surface with [`haversine_distance`], which
+/// stays correct near the poles and across the antimeridian, where treating
+/// latitude and longitude as plane coordinates goes wrong. The strip of
+/// [`closest_pair_optimized`] relies on plane geometry, so every pair is compared
+/// by brute force, in O(n²) time.
+///
+/// # Arguments
+///
+/// * `points` - Vector of positions to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first position of the closest pair
+/// * The second position of the closest pair
+/// * The distance between these positions in kilometers as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one position in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::GeoPoint;
+/// use closest_pair_rs::algorithms::closest_pair_geo;
+///
+/// let points = vec![
+///     GeoPoint { lat: 0.0, lon: 0.0 },
+///     GeoPoint { lat: 10.0, lon: 179.5 },
+///     GeoPoint { lat: 10.0, lon: -179.5 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_geo(&points);
+/// assert_eq!((p1, p2), (points[1], points[2]));
+/// assert!(distance < 110.0);
+/// ```
+pub fn closest_pair_geo(points: &[GeoPoint]) -> (GeoPoint, GeoPoint, f32) {
+    try_closest_pair_geo(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of positions on the Earth by great-circle distance, without panicking.
+///
+/// Same as [`closest_pair_geo`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of positions to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance in kilometers, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one position in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::GeoPoint;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_geo;
+///
+/// let points = vec![GeoPoint { lat: 51.5, lon: -0.1 }];
+/// assert_eq!(try_closest_pair_geo(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_geo(
+    points: &[GeoPoint],
+) -> Result<(GeoPoint, GeoPoint, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+    let mut min_h = haversine(&point1, &point2);
+
+    // Compare on the haversine itself, which orders pairs the same way as the
+    // distance, and only take the arcsine for the pair returned
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let h = haversine(&points[i], &points[j]);
+            if h < min_h {
+                min_h = h;
+                point1 = points[i];
+                point2 = points[j];
+            }
+        }
+    }
+
+    Ok((point1, point2, haversine_central_angle(min_h) as f32))
+}
+
+/// Find the pair of weighted points with the smallest weighted distance.
+///
+/// Every pair is scored with `mode`, which combines the distance between the
+/// points with their weights, and the pair with the lowest score wins. The
+/// weighting breaks the geometry the strip of [`closest_pair_optimized`] relies
+/// on, so every pair is compared by brute force.
+///
+/// # Arguments
+///
+/// * `points` - Vector of weighted points to analyze
+/// * `mode` - How the weights are combined with the distance
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the pair
+/// * The second point of the pair
+/// * The weighted distance of the pair as a f32, which can be negative for
+///   overlapping circles under [`WeightMode::Surface`]
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When no pair has a finite weighted distance
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{WeightMode, WeightedPoint};
+/// use closest_pair_rs::algorithms::closest_pair_weighted;
+///
+/// let points = vec![
+///     WeightedPoint { x: 0, y: 0, w: 1.0 },
+///     WeightedPoint { x: 2, y: 0, w: 1.0 },
+///     WeightedPoint { x: 10, y: 0, w: 3.0 },
+///     WeightedPoint { x: 13, y: 4, w: 3.0 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_weighted(&points, WeightMode::Surface);
+/// assert_eq!((p1, p2, distance), (points[2], points[3], -1.0));
+///
+/// let (p1, p2, distance) = closest_pair_weighted(&points, WeightMode::Product);
+/// assert_eq!((p1, p2, distance), (points[2], points[3], 5.0 / 9.0));
+/// ```
+pub fn closest_pair_weighted(
+    points: &[WeightedPoint],
+    mode: WeightMode,
+) -> (WeightedPoint, WeightedPoint, f32) {
+    try_closest_pair_weighted(points, mode).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the pair of weighted points with the smallest weighted distance, without panicking.
+///
+/// Same as [`closest_pair_weighted`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of weighted points to analyze
+/// * `mode` - How the weights are combined with the distance
+///
+/// # Returns
+///
+/// The pair and its weighted distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * No pair has a finite weighted distance, for example when every weight is 0
+///   under [`WeightMode::Product`]
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{WeightMode, WeightedPoint};
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_weighted;
+///
+/// let points = vec![
+///     WeightedPoint { x: 0, y: 0, w: 0.0 },
+///     WeightedPoint { x: 1, y: 0, w: 0.0 }
+/// ];
+/// assert_eq!(
+///     try_closest_pair_weighted(&points, WeightMode::Product).unwrap_err(),
+///     ClosestPairError::InfiniteDistance
+/// );
+/// ```
+pub fn try_closest_pair_weighted(
+    points: &[WeightedPoint],
+    mode: WeightMode,
+) -> Result<(WeightedPoint, WeightedPoint, f32), ClosestPairError> {
+    check_input(points)?;
+
+    // Scores that are infinite or NaN (0 / 0) never win
+    let mut best: Option<(usize, usize)> = None;
+    let mut min_score = f64::INFINITY;
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let score = mode.score(&points[i], &points[j]);
+            if score < min_score {
+                min_score = score;
+                best = Some((i, j));
+            }
+        }
+    }
+
+    let (i, j) = best.ok_or(ClosestPairError::InfiniteDistance)?;
+    Ok((points[i], points[j], min_score as f32))
+}
+
+/// Find the convex hull of a set of points using Andrew's monotone chain.
+///
+/// The points are sorted by x then y, and the lower and upper halves of the hull
+/// are then built in a single pass over them each, for O(n log n) time overall.
+///
+/// Points lying on an edge of the hull, between two corners, are dropped, so
+/// only the corners are returned. Duplicate points are returned once.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The corners of the hull in counter-clockwise order, starting from the point
+/// with the smallest x (and the smallest y among those). Inputs whose points are
+/// all collinear return the two ends of the line, and inputs with fewer than 3
+/// distinct points return those points.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::convex_hull;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 4, y: 4 },
+///     Point { x: 2, y: 1 },
+///     Point { x: 4, y: 0 },
+///     Point { x: 0, y: 4 },
+///     Point { x: 2, y: 0 }
+/// ];
+/// let hull = convex_hull(&points);
+/// assert_eq!(
+///     hull,
+///     vec![
+///         Point { x: 0, y: 0 },
+///         Point { x: 4, y: 0 },
+///         Point { x: 4, y: 4 },
+///         Point { x: 0, y: 4 }
+///     ]
+/// );
+/// ```
+pub fn convex_hull<T: Coordinate>(points: &[Point<T>]) -> Vec<Point<T>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.compare(&b.x).then(a.y.compare(&b.y)));
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    monotone_chain(&sorted)
+}
+
+/// Convex hull of points sorted by x then y, using Andrew's monotone chain.
+///
+/// The hull is returned in counter-clockwise order, without duplicate or
+/// collinear points. A set of identical points gives an empty hull.
+fn monotone_chain<T: Coordinate>(sorted: &[Point<T>]) -> Vec<Point<T>> {
+    let mut lower: Vec<Point<T>> = Vec::new();
+    for &p in sorted {
+        while lower.len() >= 2 {
+            let (a, b) = (lower[lower.len() - 2], lower[lower.len() - 1]);
+            if T::cross_sign(a, b, a, p) == Ordering::Greater {
+                break;
+            }
+            lower.pop();
+        }
+        lower.push(p);
+    }
+
+    let mut upper: Vec<Point<T>> = Vec::new();
+    for &p in sorted.iter().rev() {
+        while upper.len() >= 2 {
+            let (a, b) = (upper[upper.len() - 2], upper[upper.len() - 1]);
+            if T::cross_sign(a, b, a, p) == Ordering::Greater {
+                break;
+            }
+            upper.pop();
+        }
+        upper.push(p);
+    }
+
+    // The last point of each chain is the first point of the other
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// Find the farthest pair of points (the diameter of the set) using rotating calipers.
+///
+/// The farthest pair always lies on the convex hull. The hull is built in
+/// O(n log n) time, then its antipodal pairs are walked in O(h) time, where
+/// h is the number of points on the hull.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the farthest pair
+/// * The second point of the farthest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::farthest_pair_optimized;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 6, y: 0 },
+///     Point { x: 3, y: 1 },
+///     Point { x: 6, y: 8 }
+/// ];
+/// let (_, _, distance) = farthest_pair_optimized(points);
+/// assert_eq!(distance, 10.0);
+/// ```
+pub fn farthest_pair_optimized<T: Coordinate>(points: Vec<Point<T>>) -> (Point<T>, Point<T>, f32) {
+    try_farthest_pair_optimized(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the farthest pair of points using rotating calipers, without panicking.
+///
+/// Same as [`farthest_pair_optimized`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The farthest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_farthest_pair_optimized;
+///
+/// let points = vec![Point { x: 0, y: 0 }];
+/// assert_eq!(try_farthest_pair_optimized(points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_farthest_pair_optimized<T: Coordinate>(
+    mut points: Vec<Point<T>>,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(&points)?;
+
+    points.sort_by(|a, b| a.x.compare(&b.x).then(a.y.compare(&b.y)));
+    let hull = monotone_chain(&points);
+
+    // Every point is the same, so any two of them are the farthest pair
+    if hull.len() < 2 {
+        let dist_sq = eucid_distance_sq(&points[0], &points[1]);
+        return Ok((points[0], points[1], Metric::Euclidean.finish::<T>(dist_sq)));
+    }
+
+    let h = hull.len();
+    let mut best = (hull[0], hull[1], eucid_distance_sq(&hull[0], &hull[1]));
+    let mut j = 1;
+
+    for i in 0..h {
+        let next_i = (i + 1) % h;
+
+        // Advance j while it keeps moving away from the edge i -> next_i
+        while T::cross_sign(hull[i], hull[next_i], hull[j], hull[(j + 1) % h]) == Ordering::Greater
+        {
+            j = (j + 1) % h;
+        }
+
+        // When edge j is parallel to edge i, both of its ends are antipodal to the edge
+        for k in [j, (j + 1) % h] {
+            for p in [hull[i], hull[next_i]] {
+                let dist_sq = eucid_distance_sq(&p, &hull[k]);
+                if dist_sq > best.2 {
+                    best = (p, hull[k], dist_sq);
+                }
+            }
+        }
+    }
+
+    Ok((best.0, best.1, Metric::Euclidean.finish::<T>(best.2)))
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of calls to `rec` made on this thread, so tests can tell whether it ran.
+    pub(crate) static REC_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Recursive helper function for the divide-and-conquer closest pair algorithm.
+///
+/// This function implements the core divide-and-conquer strategy by:
+/// 1. Dividing points into left and right halves by position in the x-sorted order
+/// 2. Recursively finding closest pairs in each half
+/// 3. Checking for closer pairs that might span the dividing line
+///
+/// The points come in sorted by x and leave sorted by y: each half is sorted by y
+/// on the way back up and the two are merged, like in a merge sort. Together with
+/// the shared `scratch` buffer, this means nothing is allocated during the recursion.
+///
+/// # Arguments
+///
+/// * `points` - Points sorted by x-coordinate, sorted by y-coordinate on return
+/// * `scratch` - Buffer as long as `points`, whose contents are overwritten
+/// * `metric` - The metric to measure distances with
+/// * `cutoff` - Size at and below which brute force is used instead, at least 3
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The raw distance between these points (squared for Euclidean)
+pub(crate) fn rec<T: Coordinate, P: Located<T>>(
+    points: &mut [P],
+    scratch: &mut [P],
+    metric: Metric,
+    cutoff: usize,
+) -> (P, P, T::Distance) {
+    #[cfg(test)]
+    REC_CALLS.with(|calls| calls.set(calls.get() + 1));
+
+    let n = points.len();
+
+    if n <= cutoff {
+        let result = brute_force_raw(points, metric);
+        points.sort_by(|a, b| a.loc().y.compare(&b.loc().y));
+        return result;
+    }
+
+    let mid_idx = n / 2;
+    let midpoint_x = points[mid_idx].loc().x;
+
+    let (left_points, right_points) = points.split_at_mut(mid_idx);
+    let (left_scratch, right_scratch) = scratch.split_at_mut(mid_idx);
+    let left = rec(left_points, left_scratch, metric, cutoff);
+    let right = rec(right_points, right_scratch, metric, cutoff);
+
+    combine_halves(points, scratch, midpoint_x, left, right, metric)
+}
+
+/// Number of points below which [`rec_parallel`] stops spawning tasks and recurses serially.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 1 << 14;
+
+/// [`rec`], with the two halves of large inputs solved concurrently via `rayon::join`.
+///
+/// Only the recursion is split across threads. Merging the halves and checking
+/// the band still happen on the calling thread, as they do in [`rec`].
+#[cfg(feature = "rayon")]
+fn rec_parallel<T: Coordinate, P: Located<T> + Send>(
+    points: &mut [P],
+    scratch: &mut [P],
+    metric: Metric,
+    cutoff: usize,
+) -> (P, P, T::Distance) {
+    let n = points.len();
+
+    if n <= PARALLEL_THRESHOLD {
+        return rec(points, scratch, metric, cutoff);
+    }
+
+    let mid_idx = n / 2;
+    let midpoint_x = points[mid_idx].loc().x;
+
+    let (left_points, right_points) = points.split_at_mut(mid_idx);
+    let (left_scratch, right_scratch) = scratch.split_at_mut(mid_idx);
+    let (left, right) = rayon::join(
+        || rec_parallel(left_points, left_scratch, metric, cutoff),
+        || rec_parallel(right_points, right_scratch, metric, cutoff),
+    );
+
+    combine_halves(points, scratch, midpoint_x, left, right, metric)
+}
+
+/// Combines the closest pairs of the two halves of `points`, split at `midpoint_x`.
+///
+/// Each half must already be sorted by y. The halves are merged so all of `points`
+/// is sorted by y, and the band around the dividing line is checked for a pair
+/// closer than the best pair of either half.
+fn combine_halves<T: Coordinate, P: Located<T>>(
+    points: &mut [P],
+    scratch: &mut [P],
+    midpoint_x: T,
+    left: (P, P, T::Distance),
+    right: (P, P, T::Distance),
+    metric: Metric,
+) -> (P, P, T::Distance) {
+    let n = points.len();
+    let mid_idx = n / 2;
+
+    // Both halves are now sorted by y, merge them so the whole slice is too
+    merge_by_y(&points[..mid_idx], &points[mid_idx..], &mut scratch[..n]);
+    points.copy_from_slice(&scratch[..n]);
+
+    let (mut p1, mut p2, mut delta) = if left.2 < right.2 { left } else { right };
+
+    // Find points in the band
+    let mut band_len = 0;
+
+    for &point in points.iter() {
+        let dx = point.loc().x.axis_distance(midpoint_x);
+        if metric.raw_axis_distance(dx) <= delta {
+            scratch[band_len] = point;
+            band_len += 1;
+        }
+    }
+    let in_band = &scratch[..band_len];
+
+    // Check points in the band
+    let window = strip_window(metric);
+    for i in 0..in_band.len() {
+        for j in (i + 1)..min(i + window, in_band.len()) {
+            let d = metric.raw_distance(&in_band[i].loc(), &in_band[j].loc());
+            if d < delta {
+                p1 = in_band[i];
+                p2 = in_band[j];
+                delta = d;
+            }
+        }
+    }
+
+    (p1, p2, delta)
+}
+
+/// Merges two runs sorted by y-coordinate into `out`, which holds exactly both runs.
+fn merge_by_y<T: Coordinate, P: Located<T>>(left: &[P], right: &[P], out: &mut [P]) {
+    let (mut i, mut j) = (0, 0);
+
+    for slot in out.iter_mut() {
+        let take_left = j == right.len()
+            || (i < left.len() && left[i].loc().y.compare(&right[j].loc().y) != Ordering::Greater);
+
+        if take_left {
+            *slot = left[i];
+            i += 1;
+        } else {
+            *slot = right[j];
+            j += 1;
+        }
+    }
+}
+
+/// Find the closest pair of points using an optimized divide-and-conquer algorithm.
+///
+/// This function implements an O(n log n) algorithm for finding the closest pair
+/// of points in a set. It's significantly faster than brute force for large inputs.
+///
+/// The algorithm works by:
+/// 1. Sorting points by x and y coordinates
+/// 2. Recursively dividing the problem in half
+/// 3. Combining results and checking points near the dividing line
+///
+/// Two copies of the points are made, one sorted by x that the recursion
+/// re-sorts by y, and a scratch buffer for merging, so the memory used is twice
+/// the input, 16 bytes per `u32` point. Sorting `u32` indices into `points`
+/// instead would halve that, but every comparison would then read a point from a
+/// random place in `points`: on uniformly random points that was 1.7 times slower
+/// on 1M points and 3.2 times slower on 10M. [`closest_pair_indices`] returns the
+/// positions of the pair when they're needed, by carrying each index along with
+/// its copy. That takes 48 bytes per `u32` point and, in the `optimized_vs_indices`
+/// benchmark, about 1.3 times as long.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_optimized(&points);
+/// // The closest pair should be (5,5) and (7,7) with distance 2√2
+/// ```
+pub fn closest_pair_optimized<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_optimized(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points using the divide-and-conquer algorithm, without panicking.
+///
+/// Same as [`closest_pair_optimized`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_optimized;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_closest_pair_optimized(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_closest_pair_optimized<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let (p1, p2, delta) = optimized_raw(points, Metric::Euclidean);
+
+    // Only take the square root once, for the final result
+    Ok((p1, p2, Metric::Euclidean.finish::<T>(delta)))
+}
+
+/// Find the closest pair of points, returned as a [`ClosestPairResult`].
+///
+/// Runs [`closest_pair_optimized`] and wraps its tuple, so call sites can name the
+/// distance with [`ClosestPairResult::distance`] rather than picking `.2` out of
+/// a tuple.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and the distance between them as a f32.
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_result;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// let result = closest_pair_optimized_result(&points);
+/// assert_eq!(result.distance(), 5.0);
+/// ```
+pub fn closest_pair_optimized_result<T: Coordinate>(points: &[Point<T>]) -> ClosestPairResult<T> {
+    try_closest_pair_optimized_result(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points as a [`ClosestPairResult`], without panicking.
+///
+/// Same as [`closest_pair_optimized_result`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_optimized_result;
+///
+/// let points = vec![Point { x: 3, y: 3 }];
+/// assert_eq!(
+///     try_closest_pair_optimized_result(&points).unwrap_err(),
+///     ClosestPairError::SinglePoint
+/// );
+/// ```
+pub fn try_closest_pair_optimized_result<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<ClosestPairResult<T>, ClosestPairError> {
+    try_closest_pair_optimized(points).map(ClosestPairResult::from)
+}
+
+/// Find the closest pair of points, cross-checked against brute force in debug builds.
+///
+/// A safety net for development: runs [`closest_pair_optimized`] and, in debug
+/// builds or with the `verify` feature enabled, also [`closest_pair_brute_force`],
+/// panicking if the two distances differ. Inputs of more than
+/// [`CHECKED_BRUTE_FORCE_LIMIT`] points are not cross-checked, and neither is
+/// anything in a release build without `verify`, so this then costs the same as
+/// [`closest_pair_optimized`].
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When the cross-check runs and brute force finds a closer pair
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_checked;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// let (_, _, distance) = closest_pair_checked(&points);
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn closest_pair_checked<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    checked_with(points, |points| optimized_raw(points, Metric::Euclidean))
+}
+
+/// Runs `search`, and compares its distance with brute force when the cross-check is on.
+fn checked_with<T: Coordinate>(
+    points: &[Point<T>],
+    search: impl FnOnce(&[Point<T>]) -> (Point<T>, Point<T>, T::Distance),
+) -> (Point<T>, Point<T>, f32) {
+    let (p1, p2, delta) = search(points);
+
+    if cfg!(any(debug_assertions, feature = "verify")) && points.len() <= CHECKED_BRUTE_FORCE_LIMIT
+    {
+        let (b1, b2, expected) = brute_force_raw(points, Metric::Euclidean);
+        assert!(
+            delta == expected,
+            "Closest pair check failed: found {p1:?} and {p2:?}, but brute force found {b1:?} and {b2:?}, which are closer"
+        );
+    }
+
+    (p1, p2, Metric::Euclidean.finish::<T>(delta))
+}
+
+/// Find the closest pair of points, timing the sort and the recursion separately.
+///
+/// Runs the same divide-and-conquer algorithm as [`closest_pair_optimized`], with
+/// an [`Instant`](std::time::Instant) read between the phases, to see which one
+/// dominates on a given input. [`closest_pair_optimized`] itself is untouched,
+/// so only callers of this function pay for the clock reads. Requires the `std`
+/// feature.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The closest pair and their distance, the same as [`closest_pair_optimized`] finds
+/// * How long sorting and the recursion took
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_timed;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// let (result, timings) = closest_pair_optimized_timed(&points);
+/// assert_eq!(result.distance(), 5.0);
+/// assert_eq!(timings.total(), timings.sort + timings.recursion);
+/// ```
+#[cfg(feature = "std")]
+pub fn closest_pair_optimized_timed<T: Coordinate>(
+    points: &[Point<T>],
+) -> (ClosestPairResult<T>, Timings) {
+    try_closest_pair_optimized_timed(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points and time each phase, without panicking.
+///
+/// Same as [`closest_pair_optimized_timed`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and the timings, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_optimized_timed;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_closest_pair_optimized_timed(&points).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn try_closest_pair_optimized_timed<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(ClosestPairResult<T>, Timings), ClosestPairError> {
+    use std::time::Instant;
+
+    check_input(points)?;
+
+    let start = Instant::now();
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.compare(&b.x));
+    let mut scratch = sorted.clone();
+    let sorted_at = Instant::now();
+
+    let (p1, p2, delta) = rec(
+        &mut sorted,
+        &mut scratch,
+        Metric::Euclidean,
+        DEFAULT_BRUTE_FORCE_CUTOFF,
+    );
+    let done_at = Instant::now();
+
+    let result = ClosestPairResult::new(p1, p2, Metric::Euclidean.finish::<T>(delta));
+    let timings = Timings {
+        sort: sorted_at - start,
+        recursion: done_at - sorted_at,
+    };
+    Ok((result, timings))
+}
+
+/// Find the closest pair of points and where the divide-and-conquer search found it.
+///
+/// Runs the same search as [`closest_pair_optimized`], and finds the same pair,
+/// on points tagged with their position in the x-sorted order. The positions of
+/// the pair tell which half of the first split each point fell in, and so
+/// whether the pair came from the recursion on the left half, on the right half,
+/// or from the band around the dividing line. The recursion itself is untouched,
+/// so [`closest_pair_optimized`] pays nothing for this. Inputs of up to
+/// [`DEFAULT_BRUTE_FORCE_CUTOFF`] points are solved by brute force without a
+/// split, and the origin then says which half the pair would have come from.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The closest pair and their distance, the same as [`closest_pair_optimized`] finds
+/// * Which side of the first split the pair was found on
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::result::Origin;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_explained;
+///
+/// // Two rows far apart, except for the middle two points
+/// let mut points: Vec<Point> = (0..20).map(|i| Point { x: 100 * i, y: 0 }).collect();
+/// points[10].x -= 99;
+///
+/// let (result, origin) = closest_pair_optimized_explained(&points);
+/// assert_eq!(result.distance(), 1.0);
+/// assert_eq!(origin, Origin::Strip);
+/// ```
+pub fn closest_pair_optimized_explained<T: Coordinate>(
+    points: &[Point<T>],
+) -> (ClosestPairResult<T>, Origin) {
+    try_closest_pair_optimized_explained(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points and where it was found, without panicking.
+///
+/// Same as [`closest_pair_optimized_explained`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and its origin, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_optimized_explained;
+///
+/// let points = vec![Point { x: 1, y: 1 }];
+/// assert_eq!(
+///     try_closest_pair_optimized_explained(&points).unwrap_err(),
+///     ClosestPairError::SinglePoint
+/// );
+/// ```
+pub fn try_closest_pair_optimized_explained<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(ClosestPairResult<T>, Origin), ClosestPairError> {
+    check_input(points)?;
+
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.compare(&b.x));
+
+    // Already sorted, so the recursion splits at the same place it would untagged
+    let mut ranked: Vec<(usize, Point<T>)> = sorted.into_iter().enumerate().collect();
+    let mut scratch = ranked.clone();
+    let ((i, p1), (j, p2), delta) = rec(
+        &mut ranked,
+        &mut scratch,
+        Metric::Euclidean,
+        DEFAULT_BRUTE_FORCE_CUTOFF,
+    );
+
+    let mid_idx = points.len() / 2;
+    let origin = match (i < mid_idx, j < mid_idx) {
+        (true, true) => Origin::Left,
+        (false, false) => Origin::Right,
+        _ => Origin::Strip,
+    };
+
+    let result = ClosestPairResult::new(p1, p2, Metric::Euclidean.finish::<T>(delta));
+    Ok((result, origin))
+}
+
+/// Find the closest pair of points using the divide-and-conquer algorithm, with a f64 distance.
+///
+/// Same as [`closest_pair_optimized`], but the distance is computed and returned in double
+/// precision. Past `2^24` a f32 distance is rounded to a multiple of a power of
+/// two, while a f64 one stays accurate for any coordinates.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f64
+///
+/// # Panics
+///
+/// Same as [`closest_pair_optimized`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_f64;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1 << 30, y: 0 },
+///     Point { x: (1 << 31) + 1, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_optimized_f64(&points);
+/// assert_eq!(distance, 1073741824.0);
+/// ```
+pub fn closest_pair_optimized_f64<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f64) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let (p1, p2, delta) = optimized_raw(points, Metric::Euclidean);
+    (p1, p2, Metric::Euclidean.finish_f64::<T>(delta))
+}
+
+/// Find the closest pair of points, picking the fastest algorithm for the input size.
+///
+/// Inputs of up to [`BRUTE_FORCE_THRESHOLD`] points go to
+/// [`closest_pair_brute_force`], larger ones to [`closest_pair_optimized`]. Both
+/// are exact, so the distance doesn't depend on which one runs, though with ties
+/// the pair returned can. A closest pair too far apart for the distance to fit is
+/// refused the same way whichever one runs.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When the closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair(&points);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+pub fn closest_pair<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points with the fastest algorithm for the input size, without panicking.
+///
+/// Same as [`closest_pair`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * The closest pair is too far apart for a f32 distance, which only
+///   happens with floating point coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_closest_pair(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_closest_pair<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    if points.len() <= BRUTE_FORCE_THRESHOLD {
+        return try_closest_pair_brute_force(points);
+    }
+
+    // Brute force refuses an infinite distance, so larger inputs do too
+    let (p1, p2, dist) = try_closest_pair_optimized(points)?;
+    if dist == f32::INFINITY {
+        return Err(ClosestPairError::InfiniteDistance);
+    }
+    Ok((p1, p2, dist))
+}
+
+/// Find the closest pair of points with the fastest algorithm for the input size, with a f64 distance.
+///
+/// Same as [`closest_pair`], but the distance is computed and returned in double
+/// precision. Past `2^24` a f32 distance is rounded to a multiple of a power of
+/// two, while a f64 one stays accurate for any coordinates.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f64
+///
+/// # Panics
+///
+/// Same as [`closest_pair`], except that the distance only has to fit a f64.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_f64;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1 << 30, y: 0 },
+///     Point { x: (1 << 31) + 1, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_f64(&points);
+/// assert_eq!(distance, 1073741824.0);
+/// ```
+pub fn closest_pair_f64<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f64) {
+    if points.len() <= BRUTE_FORCE_THRESHOLD {
+        return closest_pair_brute_force_f64(points);
+    }
+
+    // Brute force refuses an infinite distance, so larger inputs do too
+    let (p1, p2, dist) = closest_pair_optimized_f64(points);
+    if dist == f64::INFINITY {
+        panic!("{}", ClosestPairError::InfiniteDistance);
+    }
+    (p1, p2, dist)
+}
+
+/// Find the closest pair of points using divide and conquer, with a custom brute force cutoff.
+///
+/// Same as [`closest_pair_optimized`], which uses [`DEFAULT_BRUTE_FORCE_CUTOFF`],
+/// but subproblems of up to `cutoff` points are solved by brute force instead of
+/// being split further. The cutoff only affects speed, never the distance found.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `cutoff` - Largest subproblem to solve by brute force, at least 3. With a
+///   cutoff of 2 a subproblem of 3 points would be split into halves of 1 and 2,
+///   and a single point has no pair to brute force
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When `cutoff` is less than 3
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_with_cutoff;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair_optimized_with_cutoff(&points, 3);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+pub fn closest_pair_optimized_with_cutoff<T: Coordinate>(
+    points: &[Point<T>],
+    cutoff: usize,
+) -> (Point<T>, Point<T>, f32) {
+    assert!(
+        cutoff >= 3,
+        "Brute force cutoff must be at least 3, got {cutoff}"
+    );
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let (p1, p2, delta) = optimized_raw_with_cutoff(points, Metric::Euclidean, cutoff);
+    (p1, p2, Metric::Euclidean.finish::<T>(delta))
+}
+
+/// Find the closest pair of points by solving chunks of the x-sorted points one at a time.
+///
+/// The points are sorted by x and cut into consecutive chunks of `chunk_size`
+/// points. Each chunk is solved on its own with the divide-and-conquer algorithm,
+/// and then its points are checked against the margin: the earlier points less
+/// than the best distance so far behind the chunk along x, kept in a set ordered
+/// by y. The margin points are all at least that distance apart, so each chunk
+/// point only meets a handful of them within the best distance along y, and the
+/// check costs O(log n) per point even when a whole column shares the same x.
+/// The whole search runs in O(n log n) time.
+///
+/// The divide-and-conquer only ever runs on one chunk, but the input is copied to
+/// be sorted and the margin can hold every point of a column, so memory overall
+/// is O(n).
+///
+/// The result is exact. Let `delta` be the best distance once the current chunk
+/// is solved, and take any pair `p`, `q` closer than `delta` with `q` in the
+/// chunk. If `p` is in the chunk too the pair was searched. Otherwise `p` comes
+/// earlier, and every earlier pair is at least `delta` apart, while
+/// `q.x - p.x < delta` and `|q.y - p.y| < delta`. Since no point of the chunk has
+/// a smaller x than its first point, `p` is less than `delta` behind it and in the
+/// margin, within `delta` of `q` along y, so the pair is checked.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `chunk_size` - Number of points in each chunk, at least 1
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When `chunk_size` is 0
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_chunked;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair_chunked(&points, 2);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+pub fn closest_pair_chunked<T: Coordinate>(
+    points: &[Point<T>],
+    chunk_size: usize,
+) -> (Point<T>, Point<T>, f32) {
+    assert!(chunk_size >= 1, "Chunk size must be at least 1, got 0");
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let metric = Metric::Euclidean;
+    let too_far = |a: T, b: T, delta| metric.raw_axis_distance(a.axis_distance(b)) > delta;
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.compare(&b.x));
+
+    // The margin, as y coordinates and positions into `sorted`
+    let mut margin: BTreeSet<ByY<T>> = BTreeSet::new();
+    let mut left = 0;
+    let mut best: Option<(Point<T>, Point<T>, T::Distance)> = None;
+
+    for start in (0..sorted.len()).step_by(chunk_size) {
+        let end = min(start + chunk_size, sorted.len());
+        let chunk = &sorted[start..end];
+
+        if chunk.len() >= 2 {
+            let found = optimized_raw(chunk, metric);
+            if best.is_none_or(|(_, _, delta)| found.2 < delta) {
+                best = Some(found);
+            }
+        }
+
+        let first_x = chunk[0].x;
+        if let Some((_, _, delta)) = best {
+            while too_far(sorted[left].x, first_x, delta) {
+                margin.remove(&ByY(sorted[left].y, left));
+                left += 1;
+            }
+        }
+
+        // Until a pair is found the margin holds at most one point
+        for q in chunk {
+            if best.is_some_and(|(_, _, delta)| too_far(q.x, first_x, delta)) {
+                break;
+            }
+
+            // Walks away from `q` along y, down and then up, until too far to pair
+            let mut within_reach = |&ByY(_, k): &ByY<T>| {
+                let p = sorted[k];
+                if best.is_some_and(|(_, _, delta)| too_far(p.y, q.y, delta)) {
+                    return false;
+                }
+                let dist = metric.raw_distance(&p, q);
+                if best.is_none_or(|(_, _, delta)| dist < delta) {
+                    best = Some((p, *q, dist));
+                }
+                true
+            };
+            for entry in margin.range(..ByY(q.y, 0)).rev() {
+                if !within_reach(entry) {
+                    break;
+                }
+            }
+            for entry in margin.range(ByY(q.y, 0)..) {
+                if !within_reach(entry) {
+                    break;
+                }
+            }
+        }
+
+        margin.extend((start..end).map(|k| ByY(sorted[k].y, k)));
+    }
+
+    // Every chunk after the first is checked against a margin, so some pair was found
+    let (p1, p2, delta) = best.expect("at least two points were checked above");
+    (p1, p2, metric.finish::<T>(delta))
+}
+
+/// A y coordinate with the position of its point, ordering the points of a
+/// margin in [`closest_pair_chunked`] by y.
+struct ByY<T>(T, usize);
+
+impl<T: Coordinate> Ord for ByY<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.compare(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+impl<T: Coordinate> PartialOrd for ByY<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Coordinate> PartialEq for ByY<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Coordinate> Eq for ByY<T> {}
+
+/// Find the closest pair of points and their exact squared distance.
+///
+/// Runs the same divide-and-conquer algorithm as [`closest_pair_optimized`], but
+/// skips the final square root. For integer coordinates the squared distance is an
+/// exact integer, so it can be compared for equality and used to detect ties, which
+/// the rounded f32 distance can't.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The squared distance between these points, as the coordinate type's
+///   [`Coordinate::Distance`]: a u128 for `u32` coordinates and a f64 for floating
+///   point ones
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_sq;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, dist_sq) = closest_pair_optimized_sq(&points);
+/// assert_eq!(dist_sq, 8u128);
+/// ```
+pub fn closest_pair_optimized_sq<T: Coordinate>(
+    points: &[Point<T>],
+) -> (Point<T>, Point<T>, T::Distance) {
+    try_closest_pair_optimized_sq(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points and their exact squared distance, without panicking.
+///
+/// Same as [`closest_pair_optimized_sq`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their squared distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_optimized_sq;
+///
+/// let points = vec![Point { x: 3, y: 3 }];
+/// assert_eq!(try_closest_pair_optimized_sq(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn try_closest_pair_optimized_sq<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, T::Distance), ClosestPairError> {
+    check_input(points)?;
+
+    Ok(optimized_raw(points, Metric::Euclidean))
+}
+
+/// Sorts the points and runs the divide-and-conquer recursion under `metric`.
+///
+/// Expects at least two points.
+fn optimized_raw<T: Coordinate, P: Located<T>>(
+    points: &[P],
+    metric: Metric,
+) -> (P, P, T::Distance) {
+    optimized_raw_with_cutoff(points, metric, DEFAULT_BRUTE_FORCE_CUTOFF)
+}
+
+/// [`optimized_raw`], switching to brute force at `cutoff` points instead of the default.
+pub(crate) fn optimized_raw_with_cutoff<T: Coordinate, P: Located<T>>(
+    points: &[P],
+    metric: Metric,
+    cutoff: usize,
+) -> (P, P, T::Distance) {
+    // Sort by x, the recursion sorts by y as it merges the halves back together
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.loc().x.compare(&b.loc().x));
+
+    // Shared by the whole recursion, so no level has to allocate
+    let mut scratch = sorted.clone();
+
+    rec(&mut sorted, &mut scratch, metric, cutoff)
+}
+
+/// Find the closest pair of points using the divide-and-conquer algorithm spread across threads.
+///
+/// Works like [`closest_pair_optimized`], but once the input is sorted, the two
+/// halves of each large enough subproblem are solved concurrently on the rayon
+/// thread pool. Small subproblems, and the merge and band check that combine the
+/// halves, stay serial to keep the task overhead down. The distance always
+/// matches [`closest_pair_optimized`], and so does the pair. Requires the `rayon`
+/// feature.
+///
+/// Runs on the current rayon thread pool: the global one, or the pool of an
+/// enclosing `ThreadPool::install`. `ClosestPairConfig::threads` sets up such a
+/// pool with a given number of threads.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_optimized_parallel;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair_optimized_parallel(&points);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn closest_pair_optimized_parallel<T: Coordinate>(
+    points: &[Point<T>],
+) -> (Point<T>, Point<T>, f32) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let (p1, p2, delta) =
+        optimized_raw_parallel(points, Metric::Euclidean, DEFAULT_BRUTE_FORCE_CUTOFF);
+    (p1, p2, Metric::Euclidean.finish::<T>(delta))
+}
+
+/// [`optimized_raw_with_cutoff`], with the recursion spread across threads by [`rec_parallel`].
+#[cfg(feature = "rayon")]
+pub(crate) fn optimized_raw_parallel<T: Coordinate>(
+    points: &[Point<T>],
+    metric: Metric,
+    cutoff: usize,
+) -> (Point<T>, Point<T>, T::Distance) {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.compare(&b.x));
+    let mut scratch = sorted.clone();
+
+    rec_parallel(&mut sorted, &mut scratch, metric, cutoff)
+}
+
+/// Find the positions of the closest pair of points in the input slice.
+///
+/// Useful when points carry metadata in a parallel collection. Runs the same
+/// divide-and-conquer algorithm as [`closest_pair_optimized`], but each point is
+/// tagged with its index before sorting, so the indices survive the recursion and
+/// duplicate coordinates still map back to two different positions.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The smaller index of the closest pair
+/// * The larger index of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_indices;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (i, j, _) = closest_pair_indices(&points);
+/// assert_eq!((i, j), (2, 3));
+/// ```
+pub fn closest_pair_indices<T: Coordinate>(points: &[Point<T>]) -> (usize, usize, f32) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let tagged: Vec<(usize, Point<T>)> = points.iter().copied().enumerate().collect();
+    let ((i, _), (j, _), dist_sq) = optimized_raw(&tagged, Metric::Euclidean);
+
+    (i.min(j), i.max(j), Metric::Euclidean.finish::<T>(dist_sq))
+}
+
+/// Find the closest pair of labeled points.
+///
+/// The labels play no part in the distance, they just come back with the pair,
+/// so no parallel collection or index lookup is needed to tell which points were
+/// closest. Runs [`closest_pair_positioned`], so only the two points of the result
+/// are cloned.
+///
+/// # Arguments
+///
+/// * `points` - Vector of labeled points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The point of the closest pair that comes first in `points`
+/// * The point of the closest pair that comes second in `points`
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::LabeledPoint;
+/// use closest_pair_rs::algorithms::closest_pair_labeled;
+///
+/// let points = vec![
+///     LabeledPoint { x: 0, y: 0, label: "depot" },
+///     LabeledPoint { x: 10, y: 10, label: "farm" },
+///     LabeledPoint { x: 3, y: 4, label: "mill" }
+/// ];
+/// let (p1, p2, distance) = closest_pair_labeled(&points);
+/// assert_eq!((p1.label, p2.label, distance), ("depot", "mill", 5.0));
+/// ```
+pub fn closest_pair_labeled<L: Clone>(
+    points: &[LabeledPoint<L>],
+) -> (LabeledPoint<L>, LabeledPoint<L>, f32) {
+    try_closest_pair_labeled(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of labeled points, without panicking.
+///
+/// Same as [`closest_pair_labeled`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of labeled points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::LabeledPoint;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_labeled;
+///
+/// let points = vec![LabeledPoint { x: 1, y: 2, label: 7 }];
+/// assert_eq!(
+///     try_closest_pair_labeled(&points).unwrap_err(),
+///     ClosestPairError::SinglePoint
+/// );
+/// ```
+pub fn try_closest_pair_labeled<L: Clone>(
+    points: &[LabeledPoint<L>],
+) -> Result<(LabeledPoint<L>, LabeledPoint<L>, f32), ClosestPairError> {
+    try_closest_pair_positioned(points)
+}
+
+/// Find the closest pair among items of any type with a location.
+///
+/// Works on your own types through the [`Positioned`] trait, so they don't have
+/// to be converted to [`Point`] and back. The locations are copied out once and
+/// searched with [`closest_pair_indices`], and only the two items of the result
+/// are cloned.
+///
+/// # Arguments
+///
+/// * `items` - Vector of items to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The item of the closest pair that comes first in `items`
+/// * The item of the closest pair that comes second in `items`
+/// * The distance between these items as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one item in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Positioned;
+/// use closest_pair_rs::algorithms::closest_pair_positioned;
+///
+/// #[derive(Clone)]
+/// struct Tree {
+///     species: &'static str,
+///     east: u32,
+///     north: u32,
+/// }
+///
+/// impl Positioned for Tree {
+///     fn x(&self) -> u32 {
+///         self.east
+///     }
+///
+///     fn y(&self) -> u32 {
+///         self.north
+///     }
+/// }
+///
+/// let trees = vec![
+///     Tree { species: "oak", east: 0, north: 0 },
+///     Tree { species: "ash", east: 10, north: 10 },
+///     Tree { species: "elm", east: 3, north: 4 }
+/// ];
+/// let (t1, t2, distance) = closest_pair_positioned(&trees);
+/// assert_eq!((t1.species, t2.species, distance), ("oak", "elm", 5.0));
+/// ```
+pub fn closest_pair_positioned<P: Positioned + Clone>(items: &[P]) -> (P, P, f32) {
+    try_closest_pair_positioned(items).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair among items of any type with a location, without panicking.
+///
+/// Same as [`closest_pair_positioned`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `items` - Vector of items to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one item in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_positioned;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(
+///     try_closest_pair_positioned(&points).unwrap_err(),
+///     ClosestPairError::EmptyInput
+/// );
+/// ```
+pub fn try_closest_pair_positioned<P: Positioned + Clone>(
+    items: &[P],
+) -> Result<(P, P, f32), ClosestPairError> {
+    let locations: Vec<Point> = items
+        .iter()
+        .map(|item| Point {
+            x: item.x(),
+            y: item.y(),
+        })
+        .collect();
+    check_input(&locations)?;
+
+    let (i, j, distance) = closest_pair_indices(&locations);
+    Ok((items[i].clone(), items[j].clone(), distance))
+}
+
+/// Find the positions of the closest pair of points, skipping the points at the given indices.
+///
+/// Handy for greedy matching, where the closest pair is used up and the next one
+/// is wanted among the points that are left: the two indices returned are the
+/// ones to exclude next. The remaining points are tagged with their positions, as
+/// in [`closest_pair_indices`], and go through the same divide-and-conquer search,
+/// so this costs no more than [`closest_pair_indices`] on the remaining points.
+/// Requires the `std` feature.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `excluded` - Indices into `points` to leave out, out of range ones are ignored
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The smaller index of the closest pair
+/// * The larger index of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When no points are left
+/// * When there's only one point left
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_excluding;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 12, y: 12 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (i, j, _) = closest_pair_excluding(&points, &HashSet::from([3]));
+/// assert_eq!((i, j), (0, 2));
+/// ```
+#[cfg(feature = "std")]
+pub fn closest_pair_excluding<T: Coordinate>(
+    points: &[Point<T>],
+    excluded: &HashSet<usize>,
+) -> (usize, usize, f32) {
+    try_closest_pair_excluding(points, excluded).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points outside the given indices, without panicking.
+///
+/// Same as [`closest_pair_excluding`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `excluded` - Indices into `points` to leave out, out of range ones are ignored
+///
+/// # Returns
+///
+/// The positions of the closest pair and their distance, or a [`ClosestPairError`] when:
+/// * No points are left
+/// * There's only one point left
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_excluding;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+/// assert_eq!(
+///     try_closest_pair_excluding(&points, &HashSet::from([0])).unwrap_err(),
+///     ClosestPairError::SinglePoint
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn try_closest_pair_excluding<T: Coordinate>(
+    points: &[Point<T>],
+    excluded: &HashSet<usize>,
+) -> Result<(usize, usize, f32), ClosestPairError> {
+    let remaining: Vec<(usize, Point<T>)> = points
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(i, _)| !excluded.contains(i))
+        .collect();
+    check_input(&remaining)?;
+
+    let ((i, _), (j, _), dist_sq) = optimized_raw(&remaining, Metric::Euclidean);
+    Ok((i.min(j), i.max(j), Metric::Euclidean.finish::<T>(dist_sq)))
+}
+
+/// Find the closest pair of points that isn't on a list of forbidden pairs.
+///
+/// Useful for matching, where some pairs may never be matched however close
+/// they are. Every allowed pair is compared, so this runs in O(n²) time: the
+/// divide-and-conquer search only keeps the best pair of each half, and a
+/// forbidden best pair can hide an allowed one behind it. Requires the `std`
+/// feature.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `forbidden` - Pairs of indices into `points` that may not be returned, in
+///   either order
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest allowed pair
+/// * The second point of the closest allowed pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When every pair is forbidden
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_with_blacklist;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1, y: 0 },
+///     Point { x: 3, y: 0 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_with_blacklist(&points, &HashSet::from([(1, 0)]));
+/// assert_eq!((p1, p2, distance), (points[1], points[2], 2.0));
+/// ```
+#[cfg(feature = "std")]
+pub fn closest_pair_with_blacklist<T: Coordinate>(
+    points: &[Point<T>],
+    forbidden: &HashSet<(usize, usize)>,
+) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_with_blacklist(points, forbidden).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points that isn't forbidden, without panicking.
+///
+/// Same as [`closest_pair_with_blacklist`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `forbidden` - Pairs of indices into `points` that may not be returned, in
+///   either order
+///
+/// # Returns
+///
+/// The closest allowed pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * Every pair is forbidden
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_with_blacklist;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+/// assert_eq!(
+///     try_closest_pair_with_blacklist(&points, &HashSet::from([(0, 1)])).unwrap_err(),
+///     ClosestPairError::AllPairsForbidden
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn try_closest_pair_with_blacklist<T: Coordinate>(
+    points: &[Point<T>],
+    forbidden: &HashSet<(usize, usize)>,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let mut best: Option<(usize, usize, T::Distance)> = None;
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            if forbidden.contains(&(i, j)) || forbidden.contains(&(j, i)) {
+                continue;
+            }
+            let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+            if best.is_none_or(|(_, _, d)| dist_sq < d) {
+                best = Some((i, j, dist_sq));
+            }
+        }
+    }
+
+    let (i, j, dist_sq) = best.ok_or(ClosestPairError::AllPairsForbidden)?;
+    Ok((points[i], points[j], Metric::Euclidean.finish::<T>(dist_sq)))
+}
+
+/// Find the closest pair among the points inside an axis-aligned rectangle.
+///
+/// The points are filtered down to the ones in the rectangle, edges included,
+/// and [`closest_pair_optimized`] runs on what is left. The filter is a linear
+/// pass over every point, so each query costs O(n) on top of the search, however
+/// small the rectangle.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `min` - Corner of the rectangle with the smallest coordinates
+/// * `max` - Corner of the rectangle with the largest coordinates
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When no points are inside the rectangle
+/// * When there's only one point inside the rectangle
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_in_region;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 13, y: 14 }
+/// ];
+/// let (p1, p2, distance) =
+///     closest_pair_in_region(&points, Point { x: 5, y: 5 }, Point { x: 20, y: 20 });
+/// assert_eq!((p1, p2, distance), (points[2], points[3], 5.0));
+/// ```
+pub fn closest_pair_in_region<T: Coordinate>(
+    points: &[Point<T>],
+    min: Point<T>,
+    max: Point<T>,
+) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_in_region(points, min, max).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair among the points inside an axis-aligned rectangle, without panicking.
+///
+/// Same as [`closest_pair_in_region`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `min` - Corner of the rectangle with the smallest coordinates
+/// * `max` - Corner of the rectangle with the largest coordinates
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * No points are inside the rectangle, which is always the case when `min` is
+///   above or to the right of `max`
+/// * There's only one point inside the rectangle
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_in_region;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+/// assert_eq!(
+///     try_closest_pair_in_region(&points, Point { x: 1, y: 1 }, Point { x: 5, y: 5 }).unwrap_err(),
+///     ClosestPairError::SinglePoint
+/// );
+/// ```
+pub fn try_closest_pair_in_region<T: Coordinate>(
+    points: &[Point<T>],
+    min: Point<T>,
+    max: Point<T>,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    let inside: Vec<Point<T>> = points
+        .iter()
+        .filter(|p| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y)
+        .copied()
+        .collect();
+
+    try_closest_pair_optimized(&inside)
+}
+
+/// Find the closest pair of points, returning early when the input has duplicates.
+///
+/// The points are sorted first, so any two points with the same coordinates end up
+/// next to each other. If such a pair exists it is returned straight away with a
+/// distance of 0.0, otherwise this falls through to [`closest_pair_optimized`].
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_dedup;
+///
+/// let points = vec![
+///     Point { x: 4, y: 4 },
+///     Point { x: 0, y: 0 },
+///     Point { x: 4, y: 4 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_dedup(points);
+/// assert_eq!(p1, p2);
+/// assert_eq!(distance, 0.0);
+/// ```
+pub fn closest_pair_dedup<T: Coordinate>(points: Vec<Point<T>>) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_dedup(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points with an early exit for duplicates, without panicking.
+///
+/// Same as [`closest_pair_dedup`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_dedup;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_closest_pair_dedup(points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_closest_pair_dedup<T: Coordinate>(
+    mut points: Vec<Point<T>>,
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(&points)?;
+
+    points.sort_by(|a, b| a.x.compare(&b.x).then(a.y.compare(&b.y)));
+
+    let duplicate = points
+        .windows(2)
+        .find(|w| w[0].x.compare(&w[1].x).then(w[0].y.compare(&w[1].y)) == Ordering::Equal);
+
+    if let Some(w) = duplicate {
+        return Ok((w[0], w[1], 0.0));
+    }
+
+    try_closest_pair_optimized(&points)
+}
+
+/// Collapse points that lie within a Chebyshev radius of each other into one.
+///
+/// Meant for noisy integer data, where readings of the same spot can differ by a
+/// little in each coordinate and would otherwise make a false closest pair. Points
+/// are visited in input order and kept unless an already kept point is at most
+/// `radius` away along both axes, as [`points_equal_within`] checks, in which
+/// case they're dropped in its favour. Kept points are bucketed into a grid of
+/// cells `radius + 1` wide, so each point is only compared with the kept points
+/// in its own and the 8 neighbouring cells, in expected O(n) time overall.
+/// Requires the `std` feature.
+///
+/// The collapsing is greedy: in a chain of points each within `radius` of the
+/// next, the first is kept, and a later one is kept again as soon as it's more
+/// than `radius` from every kept point.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to deduplicate
+/// * `radius` - Largest L∞ distance at which two points count as the same. `0`
+///   only collapses exact duplicates
+///
+/// # Returns
+///
+/// The kept points, in input order
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::dedup_within;
+///
+/// let points = vec![
+///     Point { x: 10, y: 10 },
+///     Point { x: 11, y: 9 },
+///     Point { x: 30, y: 10 },
+///     Point { x: 10, y: 11 }
+/// ];
+/// assert_eq!(dedup_within(&points, 1), vec![points[0], points[2]]);
+/// ```
+#[cfg(feature = "std")]
+pub fn dedup_within(points: &[Point], radius: u32) -> Vec<Point> {
+    let cell = radius as u64 + 1;
+    let key = |p: &Point| (p.x as u64 / cell, p.y as u64 / cell);
+
+    let mut kept: Vec<Point> = Vec::new();
+    let mut grid: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+
+    for p in points {
+        let (cx, cy) = key(p);
+        let near = (cx.saturating_sub(1)..=cx + 1).any(|gx| {
+            (cy.saturating_sub(1)..=cy + 1).any(|gy| {
+                grid.get(&(gx, gy)).is_some_and(|cell| {
+                    cell.iter()
+                        .any(|&k| points_equal_within(&kept[k], p, radius))
+                })
+            })
+        });
+
+        if !near {
+            grid.entry((cx, cy)).or_default().push(kept.len());
+            kept.push(*p);
+        }
+    }
+    kept
+}
+
+/// Find the closest pair of distinct points, ignoring pairs that coincide.
+///
+/// Data with exact duplicates always has a closest pair at distance 0. This skips
+/// those: the points are sorted so coinciding points end up next to each other,
+/// only one of each is kept, and the divide-and-conquer search runs on the rest.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest distinct pair
+/// * The second point of the closest distinct pair
+/// * The distance between these points as a f32, never 0
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When every point coincides with the others, so there are no two distinct points
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_distinct;
+///
+/// let points = vec![
+///     Point { x: 4, y: 4 },
+///     Point { x: 0, y: 0 },
+///     Point { x: 4, y: 4 },
+///     Point { x: 7, y: 8 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_distinct(&points);
+/// assert_ne!(p1, p2);
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn closest_pair_distinct<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_distinct(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of distinct points, without panicking.
+///
+/// Same as [`closest_pair_distinct`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest distinct pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * Every point coincides with the others
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_distinct;
+///
+/// let points = vec![Point { x: 1, y: 1 }, Point { x: 1, y: 1 }];
+/// assert_eq!(try_closest_pair_distinct(&points).unwrap_err(), ClosestPairError::NoDistinctPair);
+/// ```
+pub fn try_closest_pair_distinct<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    // Sorted with `partial_cmp` rather than `compare`, so -0.0 and 0.0 land
+    // together too: they are different values, but no distance apart
+    let by_coords = |a: &Point<T>, b: &Point<T>| {
+        let cmp = |a: &T, b: &T| a.partial_cmp(b).unwrap_or(Ordering::Equal);
+        cmp(&a.x, &b.x).then(cmp(&a.y, &b.y))
+    };
+    let mut sorted = points.to_vec();
+    sorted.sort_by(by_coords);
+    sorted.dedup_by(|a, b| by_coords(a, b) == Ordering::Equal);
+    if sorted.len() < 2 {
+        return Err(ClosestPairError::NoDistinctPair);
+    }
+
+    // Already sorted by x, as the recursion needs
+    let mut scratch = sorted.clone();
+    let (p1, p2, dist_sq) = rec(
+        &mut sorted,
+        &mut scratch,
+        Metric::Euclidean,
+        DEFAULT_BRUTE_FORCE_CUTOFF,
+    );
+    Ok((p1, p2, Metric::Euclidean.finish::<T>(dist_sq)))
+}
+
+/// Find the closest pair of points given as a flat slice of interleaved coordinates.
+///
+/// For callers such as C or NumPy that hold points as `[x0, y0, x1, y1, ...]`
+/// rather than a slice of [`Point`]s. The coordinates are read in pairs and
+/// searched with [`closest_pair_optimized`]. Each point of the result converts
+/// into an `[x, y]` array with `into()`.
+///
+/// # Arguments
+///
+/// * `coords` - The coordinates of the points, x then y for each point
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When `coords` has an odd length
+/// * When there are no points
+/// * When there's only one point
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_from_flat;
+///
+/// let coords = [0, 0, 10, 10, 3, 4];
+/// let (p1, p2, distance) = closest_pair_from_flat(&coords);
+/// assert_eq!(distance, 5.0);
+///
+/// let pair: [[u32; 2]; 2] = [p1.into(), p2.into()];
+/// assert!(pair == [[0, 0], [3, 4]] || pair == [[3, 4], [0, 0]]);
+/// ```
+pub fn closest_pair_from_flat<T: Coordinate>(coords: &[T]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_from_flat(coords).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points given as a flat slice of coordinates, without panicking.
+///
+/// Same as [`closest_pair_from_flat`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `coords` - The coordinates of the points, x then y for each point
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * `coords` has an odd length
+/// * There are no points
+/// * There's only one point
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_from_flat;
+///
+/// assert_eq!(
+///     try_closest_pair_from_flat(&[1u32, 2, 3]).unwrap_err(),
+///     ClosestPairError::OddCoordinateCount(3)
+/// );
+/// ```
+pub fn try_closest_pair_from_flat<T: Coordinate>(
+    coords: &[T],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    if coords.len() % 2 != 0 {
+        return Err(ClosestPairError::OddCoordinateCount(coords.len()));
+    }
+
+    let points: Vec<Point<T>> = coords
+        .chunks_exact(2)
+        .map(|c| Point { x: c[0], y: c[1] })
+        .collect();
+    try_closest_pair_optimized(&points)
+}
+
+/// Find the closest pair of points, picking the same pair every time there's a tie.
+///
+/// When several pairs share the smallest distance, the algorithms each return
+/// whichever one they come across first, so they can disagree on the pair. This
+/// returns the smallest of those pairs instead: each pair is ordered so its first
+/// point comes first by x and then y, and the pairs are compared by their first
+/// point and then their second. The result only depends on the set of points,
+/// not their order or the algorithm.
+///
+/// The smallest distance is found with [`closest_pair_optimized`]. A sweep over
+/// the points sorted by x then collects every pair at that distance, keeping the
+/// points within reach of the sweep line in a set ordered by y, so the whole
+/// search stays O(n log n).
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the canonical closest pair
+/// * The second point of the canonical closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_canonical;
+///
+/// // Every side of the square is a closest pair
+/// let points = vec![
+///     Point { x: 1, y: 1 },
+///     Point { x: 1, y: 0 },
+///     Point { x: 0, y: 1 },
+///     Point { x: 0, y: 0 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_canonical(&points);
+/// assert_eq!((p1, p2), (Point { x: 0, y: 0 }, Point { x: 0, y: 1 }));
+/// assert_eq!(distance, 1.0);
+/// ```
+pub fn closest_pair_canonical<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_canonical(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the canonical closest pair of points, without panicking.
+///
+/// Same as [`closest_pair_canonical`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The canonical closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_canonical;
+///
+/// let points = vec![Point { x: 1, y: 1 }];
+/// assert_eq!(try_closest_pair_canonical(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_canonical<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let by_xy = |a: &Point<T>, b: &Point<T>| a.x.compare(&b.x).then(a.y.compare(&b.y));
+    let mut sorted = points.to_vec();
+    sorted.sort_by(by_xy);
+
+    // The smallest duplicated point makes the smallest pair, at a distance of 0
+    if let Some(w) = sorted
+        .windows(2)
+        .find(|w| by_xy(&w[0], &w[1]) == Ordering::Equal)
+    {
+        return Ok((w[0], w[1], 0.0));
+    }
+
+    let metric = Metric::Euclidean;
+    let (_, _, delta) = optimized_raw(points, metric);
+    let too_far = |a: T, b: T| metric.raw_axis_distance(a.axis_distance(b)) > delta;
+
+    // Positions into `sorted` ordered by y, and each point's rank in that order
+    let mut by_y: Vec<usize> = (0..sorted.len()).collect();
+    by_y.sort_by(|&i, &j| sorted[i].y.compare(&sorted[j].y).then(i.cmp(&j)));
+    let mut rank = vec![0; sorted.len()];
+    for (r, &i) in by_y.iter().enumerate() {
+        rank[i] = r;
+    }
+
+    // Ranks of the points less than `delta` behind the sweep line along x
+    let mut active: BTreeSet<usize> = BTreeSet::new();
+    let mut left = 0;
+    let mut best: Option<(Point<T>, Point<T>)> = None;
+
+    for (i, &p) in sorted.iter().enumerate() {
+        while too_far(sorted[left].x, p.x) {
+            active.remove(&rank[left]);
+            left += 1;
+        }
+
+        let lowest = by_y.partition_point(|&k| {
+            sorted[k].y.compare(&p.y) == Ordering::Less && too_far(sorted[k].y, p.y)
+        });
+        for &r in active.range(lowest..) {
+            let q = sorted[by_y[r]];
+            if q.y.compare(&p.y) == Ordering::Greater && too_far(q.y, p.y) {
+                break;
+            }
+
+            // `q` was swept first, so it's the smaller point of the pair
+            let is_smaller =
+                |(a, b): (Point<T>, Point<T>)| by_xy(&q, &a).then(by_xy(&p, &b)) == Ordering::Less;
+            if metric.raw_distance(&q, &p) <= delta && best.is_none_or(is_smaller) {
+                best = Some((q, p));
+            }
+        }
+
+        active.insert(rank[i]);
+    }
+
+    let (p1, p2) = best.ok_or(ClosestPairError::InfiniteDistance)?;
+    Ok((p1, p2, metric.finish::<T>(delta)))
+}
+
+/// Find the minimum distance between any two points, and how many pairs realize it.
+///
+/// Useful for spotting degenerate inputs such as grids, where the closest pair is
+/// far from unique. Every pair is checked, in O(n²) time. Ties are counted on the
+/// raw squared distance, which is exact for integer coordinates, so pairs whose
+/// distances only round to the same `f32` aren't counted together.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The minimum distance as a f32
+/// * The number of unordered pairs of positions in `points` at that distance
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::min_distance_multiplicity;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 4 },
+///     Point { x: 6, y: 0 },
+///     Point { x: 20, y: 20 }
+/// ];
+/// assert_eq!(min_distance_multiplicity(&points), (5.0, 2));
+/// ```
+pub fn min_distance_multiplicity<T: Coordinate>(points: &[Point<T>]) -> (f32, usize) {
+    try_min_distance_multiplicity(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the minimum distance and how many pairs realize it, without panicking.
+///
+/// Same as [`min_distance_multiplicity`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The minimum distance and the number of pairs at it, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_min_distance_multiplicity;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_min_distance_multiplicity(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_min_distance_multiplicity<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(f32, usize), ClosestPairError> {
+    check_input(points)?;
+
+    let mut min_dist_sq = eucid_distance_sq(&points[0], &points[1]);
+    let mut count = 0;
+
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let dist_sq = eucid_distance_sq(&points[i], &points[j]);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                count = 1;
+            } else if dist_sq == min_dist_sq {
+                count += 1;
+            }
+        }
+    }
+
+    Ok((Metric::Euclidean.finish::<T>(min_dist_sq), count))
+}
+
+/// Find the closest pair of points in a space that wraps around at its edges.
+///
+/// Models periodic boundary conditions, as used in particle simulations: the
+/// plane is a `width` x `height` torus, so along x the distance is
+/// `min(|dx|, width - |dx|)` with `|dx|` taken modulo `width`, and the same for y
+/// with `height`. Points at `x = 0` and `x = width - 1` are then 1 apart.
+///
+/// Every pair is checked, in O(n²) time. The band check of
+/// [`closest_pair_optimized`] doesn't carry over, since a pair near opposite
+/// edges can be close without either point being near the dividing line.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `width` - Period of the space along x
+/// * `height` - Period of the space along y
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The wrapped distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When `width` or `height` is 0
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_periodic;
+///
+/// let points = vec![
+///     Point { x: 0, y: 5 },
+///     Point { x: 50, y: 5 },
+///     Point { x: 98, y: 5 }
+/// ];
+/// // (98, 5) is 2 away from (0, 5) across the edge
+/// let (_, _, distance) = closest_pair_periodic(&points, 100, 100);
+/// assert_eq!(distance, 2.0);
+/// ```
+pub fn closest_pair_periodic(points: &[Point], width: u32, height: u32) -> (Point, Point, f32) {
+    try_closest_pair_periodic(points, width, height).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find the closest pair of points in a wrapping space, without panicking.
+///
+/// Same as [`closest_pair_periodic`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `width` - Period of the space along x
+/// * `height` - Period of the space along y
+///
+/// # Returns
+///
+/// The closest pair and their wrapped distance, or a [`ClosestPairError`] when:
+/// * `width` or `height` is 0
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_periodic;
+///
+/// let points = vec![Point { x: 1, y: 1 }];
+/// assert_eq!(try_closest_pair_periodic(&points, 10, 10).unwrap_err(), ClosestPairError::SinglePoint);
+/// assert_eq!(
+///     try_closest_pair_periodic(&points, 0, 10).unwrap_err(),
+///     ClosestPairError::InvalidPeriod { width: 0, height: 10 }
+/// );
+/// ```
+pub fn try_closest_pair_periodic(
+    points: &[Point],
+    width: u32,
+    height: u32,
+) -> Result<(Point, Point, f32), ClosestPairError> {
+    if width == 0 || height == 0 {
+        return Err(ClosestPairError::InvalidPeriod { width, height });
+    }
+    check_input(points)?;
+
+    // Shortest way between two coordinates, going either direction around the period
+    let wrapped = |a: u32, b: u32, period: u32| {
+        let d = a.abs_diff(b) % period;
+        d.min(period - d) as u64
+    };
+    let dist_sq = |p1: &Point, p2: &Point| {
+        let dx = wrapped(p1.x, p2.x, width);
+        let dy = wrapped(p1.y, p2.y, height);
+        dx * dx + dy * dy
+    };
+
+    let mut best = (points[0], points[1], dist_sq(&points[0], &points[1]));
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            let d = dist_sq(&points[i], &points[j]);
+            if d < best.2 {
+                best = (points[i], points[j], d);
+            }
+        }
+    }
+
+    Ok((
+        best.0,
+        best.1,
+        Metric::Euclidean.finish::<u32>(best.2.into()),
+    ))
+}
+
+/// Find the closest pair of points under the given metric using divide-and-conquer.
+///
+/// Works like [`closest_pair_optimized`], but measures distances with `metric`.
+/// The strip step relies on every supported metric being at least as large as the
+/// difference along a single axis, and widens the neighbour window for Manhattan
+/// distance, where more points fit into the pruning rectangle.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `metric` - The metric to measure distances with
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{Metric, Point};
+/// use closest_pair_rs::algorithms::closest_pair_optimized_metric;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 3 },
+///     Point { x: 20, y: 0 },
+///     Point { x: 25, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_optimized_metric(&points, Metric::Chebyshev);
+/// assert_eq!(distance, 3.0);
+/// ```
+pub fn closest_pair_optimized_metric<T: Coordinate>(
+    points: &[Point<T>],
+    metric: Metric,
+) -> (Point<T>, Point<T>, f32) {
+    check_input(points).unwrap_or_else(|err| panic!("{err}"));
+
+    let (p1, p2, dist_raw) = optimized_raw(points, metric);
+    (p1, p2, metric.finish::<T>(dist_raw))
+}
+
+/// Multiplicative hasher for grid cell keys, much cheaper than the default SipHash.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct CellHasher(u64);
+
+#[cfg(feature = "std")]
+impl Hasher for CellHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(b as u64);
+        }
+    }
+
+    fn write_i64(&mut self, v: i64) {
+        self.write_u64(v as u64);
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.0 = (self.0.rotate_left(5) ^ v).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+/// Buckets point indices into square cells, so finding the points near another
+/// only takes looking through the cells around it.
+///
+/// A grid built with cell side `cell` finds every point within `cell` of a given
+/// point. Cell keys come from dividing the coordinates, as `f64`, by the cell side,
+/// and both the conversion and the division round, so a point exactly `cell` away
+/// can get a key one further out than the exact quotient would give. The searches
+/// make up for it by reaching a slack of a few units in the last place of the
+/// coordinates past `cell`, which covers any such rounding as long as the keys
+/// round the same way for every point. That mostly keeps a search to the 3x3 cells
+/// around the point, and a fourth row or column only comes in when the point is
+/// within the slack of a cell edge. Coordinates so large that the slack spans more
+/// cells than there are points are searched by going through the occupied cells.
+///
+/// Each cell is a linked list threaded through `next`, so no cell allocates.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub(crate) struct CellGrid {
+    cell: f64,
+    heads: HashMap<(i64, i64), usize, BuildHasherDefault<CellHasher>>,
+    next: Vec<usize>,
+}
+
+#[cfg(feature = "std")]
+impl CellGrid {
+    /// Creates a grid of cells with side `cell`, with room for point indices below `n`.
+    pub(crate) fn new(cell: f64, n: usize) -> Self {
+        CellGrid {
+            cell,
+            heads: HashMap::with_capacity_and_hasher(n, Default::default()),
+            next: vec![usize::MAX; n],
+        }
+    }
+
+    /// The cell holding `p`.
+    pub(crate) fn key<T: Coordinate>(&self, p: &Point<T>) -> (i64, i64) {
+        (self.key_of(p.x.to_f64()), self.key_of(p.y.to_f64()))
+    }
+
+    fn key_of(&self, v: f64) -> i64 {
+        (v / self.cell).floor() as i64
+    }
+
+    /// Largest error that rounding puts into a cell key or a cell edge, as a
+    /// distance, for coordinates up to `v` in size.
+    fn slack(&self, v: f64) -> f64 {
+        4.0 * f64::EPSILON * (v.abs() + self.cell)
+    }
+
+    /// Adds the point at index `i` to the cell `key`.
+    pub(crate) fn insert(&mut self, key: (i64, i64), i: usize) {
+        if i >= self.next.len() {
+            self.next.resize(i + 1, usize::MAX);
+        }
+        self.next[i] = self.heads.insert(key, i).unwrap_or(usize::MAX);
+    }
+
+    /// Takes the point at index `i` out of the cell `key`, if it's there.
+    pub(crate) fn remove(&mut self, key: (i64, i64), i: usize) {
+        let Some(&head) = self.heads.get(&key) else {
+            return;
+        };
+
+        if head == i {
+            match self.next[i] {
+                usize::MAX => self.heads.remove(&key),
+                after => self.heads.insert(key, after),
+            };
+            return;
+        }
+
+        let mut j = head;
+        while self.next[j] != usize::MAX {
+            if self.next[j] == i {
+                self.next[j] = self.next[i];
+                return;
+            }
+            j = self.next[j];
+        }
+    }
+
+    /// Calls `f` with the key of every cell that can hold a point within `cell` of
+    /// `p`, until it returns an index.
+    #[inline]
+    pub(crate) fn find_cell<T: Coordinate>(
+        &self,
+        p: &Point<T>,
+        mut f: impl FnMut((i64, i64)) -> Option<usize>,
+    ) -> Option<usize> {
+        let span = |v: f64| {
+            let reach = self.cell + self.slack(v);
+            self.key_of(v - reach)..=self.key_of(v + reach)
+        };
+        let (xs, ys) = (span(p.x.to_f64()), span(p.y.to_f64()));
+
+        let width = |r: &RangeInclusive<i64>| *r.end() as i128 - *r.start() as i128 + 1;
+        if width(&xs) * width(&ys) > self.next.len().max(16) as i128 {
+            return self
+                .heads
+                .keys()
+                .filter(|(gx, gy)| xs.contains(gx) && ys.contains(gy))
+                .find_map(|&key| f(key));
+        }
+
+        for gx in xs {
+            for gy in ys.clone() {
+                if let Some(j) = f((gx, gy)) {
+                    return Some(j);
+                }
+            }
+        }
+        None
+    }
+
+    /// Squared lower bound on the distance from `p` to any point in the cell `key`.
+    #[inline]
+    pub(crate) fn gap_sq<T: Coordinate>(&self, p: &Point<T>, key: (i64, i64)) -> f64 {
+        let gap = |v: f64, k: i64| {
+            let out = (k as f64 * self.cell - v).max(v - (k as f64 + 1.0) * self.cell);
+            if out <= 0.0 {
+                return 0.0;
+            }
+            (out - self.slack(v.abs() + out)).max(0.0)
+        };
+        let (dx, dy) = (gap(p.x.to_f64(), key.0), gap(p.y.to_f64(), key.1));
+        dx * dx + dy * dy
+    }
+
+    /// Finds the index nearest to `p`, with its squared distance as `dist` gives it.
+    ///
+    /// Indices `dist` gives `None` for are skipped. Rings of cells are searched
+    /// outwards from `p` until a whole ring is farther away than the nearest index
+    /// found. Once a ring would hold more cells than are occupied, the occupied cells
+    /// are gone through instead, so the search stays bounded when few points are
+    /// spread over a large area.
+    pub(crate) fn nearest<T: Coordinate>(
+        &self,
+        p: &Point<T>,
+        mut dist: impl FnMut(usize) -> Option<T::Distance>,
+    ) -> Option<(usize, T::Distance)> {
+        let mut best: Option<(usize, T::Distance)> = None;
+        let mut search = |key: (i64, i64), best: &mut Option<(usize, T::Distance)>| {
+            if best.is_some_and(|(_, d)| self.gap_sq(p, key) >= T::distance_to_f64(d)) {
+                return false;
+            }
+            self.find_in(key, &mut |j| {
+                if let Some(d) = dist(j) {
+                    if best.is_none_or(|(_, b)| d < b) {
+                        *best = Some((j, d));
+                    }
+                }
+                false
+            });
+            true
+        };
+
+        let (cx, cy) = self.key(p);
+        for r in 0i64.. {
+            let side = 2 * r as u128 + 1;
+            if r > 0 && side * side > self.heads.len() as u128 {
+                for &key in self.heads.keys() {
+                    search(key, &mut best);
+                }
+                break;
+            }
+
+            let mut searched = false;
+            for dx in -r..=r {
+                // Only the top and bottom of the ring in its inner columns
+                let step = if dx.abs() == r { 1 } else { 2 * r as usize };
+                for dy in (-r..=r).step_by(step) {
+                    let key = (cx.saturating_add(dx), cy.saturating_add(dy));
+                    searched |= search(key, &mut best);
+                }
+            }
+            if !searched {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Calls `f` with every index in a cell that can hold a point within `cell` of `p`.
+    pub(crate) fn for_each_near<T: Coordinate>(&self, p: &Point<T>, mut f: impl FnMut(usize)) {
+        self.find_near(p, |j| {
+            f(j);
+            false
+        });
+    }
+
+    /// Like [`CellGrid::for_each_near`], but stops at the first index `f` accepts.
+    pub(crate) fn find_near<T: Coordinate>(
+        &self,
+        p: &Point<T>,
+        mut f: impl FnMut(usize) -> bool,
+    ) -> Option<usize> {
+        self.find_cell(p, |key| self.find_in(key, &mut f))
+    }
+
+    /// Calls `f` with the indices in the cell `key` alone, until it accepts one.
+    #[inline]
+    pub(crate) fn find_in(
+        &self,
+        key: (i64, i64),
+        f: &mut impl FnMut(usize) -> bool,
+    ) -> Option<usize> {
+        let mut j = self.heads.get(&key).copied().unwrap_or(usize::MAX);
+        while j != usize::MAX {
+            if f(j) {
+                return Some(j);
+            }
+            j = self.next[j];
+        }
+        None
+    }
+}
+
+/// Find closest pair of points by bucketing them into a grid.
+///
+/// This is Rabin's randomized approach, in two passes:
+/// 1. The closest distance `d` within a random sample of about n^(2/3) points is
+///    found with the divide-and-conquer algorithm, in o(n) time. It is an upper
+///    bound on the true closest distance.
+/// 2. Every point is bucketed into a grid of cells about `d` wide, and only
+///    compared with the points already in its own and the 8 neighbouring cells.
+///
+/// For uniformly distributed points each cell holds O(1) points, so this runs in
+/// expected O(n) time. The result is always exact, but heavily clustered input can
+/// crowd many points into a few cells and degrade towards O(n²), so prefer
+/// [`closest_pair_optimized`] when the distribution is unknown. The sample is drawn
+/// from a generator seeded with the input length, so results are reproducible.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_grid;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair_grid(&points);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+#[cfg(feature = "std")]
+pub fn closest_pair_grid<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+    try_closest_pair_grid(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find closest pair of points by bucketing them into a grid, without panicking.
+///
+/// Same as [`closest_pair_grid`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_grid;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_closest_pair_grid(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+#[cfg(feature = "std")]
+pub fn try_closest_pair_grid<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let n = points.len();
+
+    // First pass: the closest pair of a random sample bounds the closest distance
+    let (mut point1, mut point2, mut min_dist_sq) = sample_bound(points);
+
+    let cell = T::distance_to_f64(min_dist_sq).sqrt();
+
+    // Two points at the same spot can't be beaten
+    if cell == 0.0 {
+        return Ok((point1, point2, 0.0));
+    }
+
+    // Second pass: compare each point with the earlier points in neighbouring cells
+    let mut grid = CellGrid::new(cell, n);
+
+    for (i, p) in points.iter().enumerate() {
+        grid.for_each_near(p, |j| {
+            let dist_sq = eucid_distance_sq(&points[j], p);
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = points[j];
+                point2 = *p;
+            }
+        });
+
+        grid.insert(grid.key(p), i);
+    }
+
+    Ok((point1, point2, Metric::Euclidean.finish::<T>(min_dist_sq)))
+}
+
+/// The closest pair within a random sample of about n^(2/3) of the points.
+///
+/// Its distance is an upper bound on the closest distance of all the points. The
+/// sample is drawn from a generator seeded with the input length, so it is the
+/// same on every call. Expects at least two points.
+#[cfg(feature = "std")]
+fn sample_bound<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, T::Distance) {
+    let n = points.len();
+
+    let mut rng = SplitMix64::new(n as u64);
+    let mut sample: Vec<usize> = (0..(n as f64).powf(2.0 / 3.0) as usize)
+        .map(|_| (rng.next_u64() % n as u64) as usize)
+        .collect();
+    sample.sort_unstable();
+    sample.dedup();
+    if sample.len() < 2 {
+        sample = vec![0, 1];
+    }
+
+    let sampled: Vec<Point<T>> = sample.iter().map(|&i| points[i]).collect();
+    optimized_raw(&sampled, Metric::Euclidean)
+}
+
+/// Find every pair of points at most `r` apart.
+///
+/// Points are bucketed into a grid of cells about `r` wide, and each point is only
+/// compared with the points in its own and the 8 neighbouring cells. For sparse
+/// results this runs in expected O(n + k) time, where k is the number of pairs
+/// returned, instead of the O(n²) of checking every pair.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `r` - Largest distance a pair may have. `0.0` finds coincident points only
+///
+/// # Returns
+///
+/// A vector of (first point, second point, distance) tuples. Each pair appears
+/// once, with the point that comes first in `points` first, and the pairs are
+/// ordered by the positions of their points in the input. A negative or NaN `r`
+/// gives an empty vector.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::pairs_within_radius;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 4 },
+///     Point { x: 3, y: 5 },
+///     Point { x: 50, y: 50 }
+/// ];
+/// let pairs = pairs_within_radius(&points, 5.0);
+/// assert_eq!(pairs.len(), 2);
+/// assert_eq!(pairs[0], (points[0], points[1], 5.0));
+/// assert_eq!(pairs[1], (points[1], points[2], 1.0));
+/// ```
+#[cfg(feature = "std")]
+pub fn pairs_within_radius<T: Coordinate>(
+    points: &[Point<T>],
+    r: f32,
+) -> Vec<(Point<T>, Point<T>, f32)> {
+    radius_pairs(points, r)
+        .into_iter()
+        .map(|(i, j, dist_sq)| (points[i], points[j], Metric::Euclidean.finish::<T>(dist_sq)))
+        .collect()
+}
+
+/// Indices of every pair at most `r` apart with their squared distances, sorted
+/// by the indices, as [`pairs_within_radius`] returns them.
+#[cfg(feature = "std")]
+fn radius_pairs<T: Coordinate>(points: &[Point<T>], r: f32) -> Vec<(usize, usize, T::Distance)> {
+    if r.is_nan() || r < 0.0 {
+        return Vec::new();
+    }
+
+    let mut pairs: Vec<(usize, usize, T::Distance)> = Vec::new();
+
+    if r == 0.0 {
+        // Sort so coincident points end up next to each other
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        let by_coords = |a: &Point<T>, b: &Point<T>| a.x.compare(&b.x).then(a.y.compare(&b.y));
+        order.sort_by(|&a, &b| by_coords(&points[a], &points[b]).then(a.cmp(&b)));
+
+        for run in order.chunk_by(|&a, &b| by_coords(&points[a], &points[b]) == Ordering::Equal) {
+            for (k, &i) in run.iter().enumerate() {
+                for &j in &run[k + 1..] {
+                    pairs.push((i, j, eucid_distance_sq(&points[i], &points[j])));
+                }
+            }
+        }
+    } else {
+        let r_sq = (r as f64) * (r as f64);
+
+        let mut grid = CellGrid::new(r as f64, points.len());
+
+        for (i, p) in points.iter().enumerate() {
+            grid.for_each_near(p, |j| {
+                let dist_sq = eucid_distance_sq(&points[j], p);
+                if T::distance_to_f64(dist_sq) <= r_sq {
+                    pairs.push((j, i, dist_sq));
+                }
+            });
+
+            grid.insert(grid.key(p), i);
+        }
+    }
+
+    pairs.sort_unstable_by_key(|&(i, j, _)| (i, j));
+    pairs
+}
+
+/// Group points into clusters where any two points at most `r` apart are linked.
+///
+/// This is single linkage clustering: two points end up in the same cluster when
+/// a chain of points, each at most `r` from the next, joins them. The pairs come
+/// from the same grid search as [`pairs_within_radius`] and are merged with a
+/// union-find, so dense clusters cost as much as the pairs in them.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to cluster
+/// * `r` - Largest distance at which two points are linked
+///
+/// # Returns
+///
+/// The clusters, each holding its points in input order, ordered by their first
+/// point's position in `points`. A point with no other point within `r` forms a
+/// cluster on its own, as does every point when `r` is negative or NaN.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::connected_components;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 50, y: 50 },
+///     Point { x: 3, y: 4 },
+///     Point { x: 6, y: 8 }
+/// ];
+/// assert_eq!(
+///     connected_components(&points, 5.0),
+///     vec![vec![points[0], points[2], points[3]], vec![points[1]]]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn connected_components<T: Coordinate>(points: &[Point<T>], r: f32) -> Vec<Vec<Point<T>>> {
+    let mut parent: Vec<usize> = (0..points.len()).collect();
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for (i, j, _) in radius_pairs(points, r) {
+        let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+        // The smaller index as the root, so each cluster's root is its first point
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut clusters: Vec<Vec<Point<T>>> = Vec::new();
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::new();
+    for (i, &p) in points.iter().enumerate() {
+        let top = root(&mut parent, i);
+        let cluster = *cluster_of_root.entry(top).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(p);
+    }
+    clusters
+}
+
+/// Find any pair of points strictly closer than `t`.
+///
+/// Useful when only the existence of a close pair matters, as in collision
+/// detection, and the exact closest pair would be wasted work. Points are bucketed
+/// into a grid of cells about `t` wide, each compared with the points already in
+/// its own and the 8 neighbouring cells, and the search stops at the first pair
+/// found. When no pair qualifies this runs in expected O(n) time for points that
+/// are spread out.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `t` - Threshold a pair's distance must be below
+///
+/// # Returns
+///
+/// The first qualifying pair found, as (first point, second point, distance) with
+/// the point that comes first in `points` first, or `None` if every pair is at
+/// least `t` apart. The distance is compared as the f32 returned, so a pair that
+/// is found always has a distance below `t`. Which pair is found when several
+/// qualify is unspecified, and need not be the closest. A zero, negative or NaN
+/// `t` gives `None`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::exists_pair_closer_than;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// assert_eq!(exists_pair_closer_than(&points, 6.0), Some((points[0], points[2], 5.0)));
+/// assert_eq!(exists_pair_closer_than(&points, 5.0), None);
+/// ```
+#[cfg(feature = "std")]
+pub fn exists_pair_closer_than<T: Coordinate>(
+    points: &[Point<T>],
+    t: f32,
+) -> Option<(Point<T>, Point<T>, f32)> {
+    if t.is_nan() || t <= 0.0 {
+        return None;
+    }
+
+    let mut grid = CellGrid::new(t as f64, points.len());
+
+    for (i, p) in points.iter().enumerate() {
+        // Compares the f32 distance that gets reported, so a pair whose distance
+        // only rounds to `t` doesn't come back as closer than it
+        let distance = |j: usize| Metric::Euclidean.finish::<T>(eucid_distance_sq(&points[j], p));
+        if let Some(j) = grid.find_near(p, |j| distance(j) < t) {
+            return Some((points[j], *p, distance(j)));
+        }
+
+        grid.insert(grid.key(p), i);
+    }
+
+    None
+}
+
+/// Find closest pair of points using bit shift packing technique.
+///
+/// This function uses bit manipulation to pack x and y coordinates into single values,
+/// which are then sorted to find closest pair hehe, and is much easier to understand
+///
+/// Packing only makes sense for unsigned integers, so unlike the other algorithms
+/// this one is restricted to `u32` points.
+///
+/// This is a heuristic, not an exact algorithm. Packing as `x << bits | y` sorts
+/// the points by x and then y, and each point is only compared with the next
+/// `bits` points in that order. Two points that are close in space can be split
+/// by more than `bits` points sharing a smaller x (a tall column of points, for
+/// example) and never get compared. The returned pair is always a real pair of
+/// input points with its exact distance, so the result is an upper bound on the
+/// closest distance. Use [`closest_pair_optimized`] when the answer must be exact.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `bits` - Number of bits to use for each coordinate when packing, from 1 to 32
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+/// * When `bits` is 0 or more than 32
+/// * When a coordinate doesn't fit in `bits` bits
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_bit_shift;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (p1, p2, distance) = closest_pair_bit_shift(points, 8);
+/// // The closest pair should be (5,5) and (7,7) with distance 2√2
+/// ```
+pub fn closest_pair_bit_shift(points: Vec<Point>, bits: u8) -> (Point, Point, f32) {
+    try_closest_pair_bit_shift(points, bits).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find closest pair of points using bit shift packing technique, without panicking.
+///
+/// Same as [`closest_pair_bit_shift`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `bits` - Number of bits to use for each coordinate when packing, from 1 to 32
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+/// * `bits` is 0 or more than 32
+/// * A coordinate doesn't fit in `bits` bits, which packing would silently truncate.
+///   [`validate_fits`] lists every point that doesn't fit
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::try_closest_pair_bit_shift;
+///
+/// let points = vec![Point { x: 1, y: 1 }, Point { x: 4, y: 5 }];
+/// let (_, _, distance) = try_closest_pair_bit_shift(points, 8).unwrap();
+/// assert_eq!(distance, 5.0);
+/// ```
+pub fn try_closest_pair_bit_shift(
+    points: Vec<Point>,
+    bits: u8,
+) -> Result<(Point, Point, f32), ClosestPairError> {
+    check_input(&points)?;
+
+    if !(1..=32).contains(&bits) {
+        return Err(ClosestPairError::InvalidBits(bits));
+    }
+    if let Err(offenders) = validate_fits(&points, bits) {
+        let p = points[offenders[0]];
+        return Err(ClosestPairError::CoordinateOutOfRange {
+            value: p.x.max(p.y),
+            bits,
+        });
+    }
+
+    let n = points.len();
+    let mut min_dist_sq = u128::MAX;
+    // Initialize with the first two points
+    let mut point1 = points[0];
+    let mut point2 = points[1];
+
+    // Pack the points into single values, every coordinate fits after the check above
+    let mut packed: Vec<u64> = points
+        .iter()
+        .map(|p| pack_numbers(p.x, p.y, bits))
+        .collect();
+
+    // can use unstable sort as we do not care about the order of identical elements, win
+    packed.sort_unstable();
+
+    for i in 0..n - 1 {
+        let (x1, y1) = unpack_numbers(packed[i], bits);
+        let p1 = Point { x: x1, y: y1 };
+
+        for j in packed
+            .iter()
+            .take(min(n, i + bits as usize + 1))
+            .skip(i + 1)
+        {
+            let (x2, y2) = unpack_numbers(*j, bits);
+            let p2 = Point { x: x2, y: y2 };
+
+            let dist_sq = eucid_distance_sq(&p1, &p2);
+
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                point1 = p1;
+                point2 = p2;
+            }
+        }
+    }
+    // Coordinates of at most 32 bits are always a finite distance apart
+    Ok((point1, point2, Metric::Euclidean.finish::<u32>(min_dist_sq)))
+}
+
+/// Find closest pair of points using bit shift packing, with the number of bits picked automatically.
+///
+/// Scans the points for their largest coordinate and packs with the fewest bits
+/// that hold it, so no coordinate gets truncated. The bit count is also the size
+/// of the comparison window of [`closest_pair_bit_shift`], so small coordinates
+/// mean fewer comparisons, and more chance of missing the closest pair.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f32
+///
+/// # Panics
+///
+/// * When the input vector is empty
+/// * When there's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_bit_shift_auto;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1000, y: 1000 },
+///     Point { x: 5, y: 5 },
+///     Point { x: 7, y: 7 }
+/// ];
+/// let (_, _, distance) = closest_pair_bit_shift_auto(&points);
+/// assert_eq!(distance, (8.0f64).sqrt() as f32);
+/// ```
+pub fn closest_pair_bit_shift_auto(points: &[Point]) -> (Point, Point, f32) {
+    try_closest_pair_bit_shift_auto(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Find closest pair of points using bit shift packing with automatic bits, without panicking.
+///
+/// Same as [`closest_pair_bit_shift_auto`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+///
+/// # Returns
+///
+/// The closest pair and their distance, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+/// * There's only one point in the vector
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::error::ClosestPairError;
+/// use closest_pair_rs::algorithms::try_closest_pair_bit_shift_auto;
+///
+/// let points = vec![Point { x: 1, y: 1 }];
+/// assert_eq!(try_closest_pair_bit_shift_auto(&points).unwrap_err(), ClosestPairError::SinglePoint);
+/// ```
+pub fn try_closest_pair_bit_shift_auto(
+    points: &[Point],
+) -> Result<(Point, Point, f32), ClosestPairError> {
+    check_input(points)?;
+
+    let (_, max) = try_bounding_box(points)?;
+    let bits = auto_bits(max.x.max(max.y));
+
+    try_closest_pair_bit_shift(points.to_vec(), bits)
+}
+
+/// Fewest bits that can hold `max_coord`, and at least 1.
+fn auto_bits(max_coord: u32) -> u8 {
+    (u32::BITS - max_coord.leading_zeros()).max(1) as u8
+}
+
+/// Find closest pair of points using bit shift packing technique, with a f64 distance.
+///
+/// Same as [`closest_pair_bit_shift`], but the distance is computed and returned in double
+/// precision. Past `2^24` a f32 distance is rounded to a multiple of a power of
+/// two, while a f64 one stays accurate for any coordinates.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to analyze
+/// * `bits` - Number of bits to use for each coordinate when packing, from 1 to 32
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * The first point of the closest pair
+/// * The second point of the closest pair
+/// * The distance between these points as a f64
+///
+/// # Panics
+///
+/// Same as [`closest_pair_bit_shift`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::algorithms::closest_pair_bit_shift_f64;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 1 << 30, y: 0 },
+///     Point { x: (1 << 31) + 1, y: 0 }
+/// ];
+/// let (_, _, distance) = closest_pair_bit_shift_f64(points, 32);
+/// assert_eq!(distance, 1073741824.0);
+/// ```
+pub fn closest_pair_bit_shift_f64(points: Vec<Point>, bits: u8) -> (Point, Point, f64) {
+    let (p1, p2, _) = closest_pair_bit_shift(points, bits);
+    (p1, p2, eucid_distance_f64(&p1, &p2))
+}
+
+#[cfg(test)]
+mod closest_pair_optimized_tests {
+    use super::*;
+    use std::f32;
+
+    #[test]
+    fn test_small_set() {
+        // Basic test with known distances
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 0, y: 4 },
+            Point { x: 10, y: 10 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        assert_eq!(dist, 3.0);
+        assert!(
+            (p1.x == 0 && p1.y == 0 && p2.x == 3 && p2.y == 0)
+                || (p2.x == 0 && p2.y == 0 && p1.x == 3 && p1.y == 0)
+        );
+    }
+
+    #[test]
+    fn test_single_pair() {
+        // Test with just two points
+        let points = vec![Point { x: 5, y: 10 }, Point { x: 8, y: 14 }];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert!(approx_eq(dist, 5.0, DISTANCE_EPSILON)); // Distance should be 5.0
+    }
+
+    #[test]
+    fn test_collinear_points() {
+        // Test with points in a straight line
+        let points = vec![
+            Point { x: 1, y: 1 },
+            Point { x: 3, y: 3 },
+            Point { x: 5, y: 5 },
+            Point { x: 7, y: 7 },
+            Point { x: 9, y: 9 },
+        ];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert!(approx_eq(dist, 2.0 * f32::sqrt(2.0), DISTANCE_EPSILON)); // Distance should be 2√2
+    }
+
+    #[test]
+    fn test_grid_points() {
+        // Test with points arranged in a grid
+        let mut points = Vec::new();
+
+        // Create a 5x5 grid with points at integer coordinates
+        for x in 0..5 {
+            for y in 0..5 {
+                points.push(Point { x, y });
+            }
+        }
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert_eq!(dist, 1.0); // Minimum distance in a grid is 1.0
+    }
+
+    #[test]
+    fn test_duplicate_points() {
+        // Test with duplicate points (should give distance 0)
+        let points = vec![
+            Point { x: 10, y: 20 },
+            Point { x: 30, y: 40 },
+            Point { x: 10, y: 20 }, // Duplicate
+            Point { x: 50, y: 60 },
+        ];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_large_range() {
+        // Test with points spread over a large range
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10000, y: 10000 },
+            Point { x: 20000, y: 20000 },
+            Point { x: 20005, y: 20005 }, // Closest to the previous point
+        ];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert!(approx_eq(dist, 5.0 * f32::sqrt(2.0), DISTANCE_EPSILON)); // Should be 5√2
+    }
+
+    #[test]
+    fn test_random_points() {
+        // Generate 50000 random points
+        let points = random_points(50000, u32::pow(2, 31) - 1, 1);
+
+        // Run closest pair algorithm
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+
+        // Compare with brute force result for validation
+        let (bf_p1, bf_p2, bf_dist) = closest_pair_brute_force(&points);
+
+        // Points can be different, but squared distances of integer points are
+        // exact, so they must match exactly
+        assert_eq!(
+            eucid_distance_sq(&p1, &p2),
+            eucid_distance_sq(&bf_p1, &bf_p2)
+        );
+        assert_eq!(
+            closest_pair_optimized_sq(&points).2,
+            eucid_distance_sq(&bf_p1, &bf_p2)
+        );
+
+        // The f32 distances only agree up to rounding
+        assert!(approx_eq(dist, bf_dist, DISTANCE_EPSILON));
+    }
+
+    #[test]
+    fn test_shared_x() {
+        // Every point on the same vertical line, one pair 3 apart and the rest 7 apart
+        let mut points: Vec<Point> = (0..1000).map(|i| Point { x: 5, y: 7 * i }).collect();
+        points[600].y -= 4;
+
+        let before = REC_CALLS.with(|calls| calls.get());
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        let calls = REC_CALLS.with(|calls| calls.get()) - before;
+
+        assert_eq!(dist, 3.0);
+        assert_eq!((p1.y.min(p2.y), p1.y.max(p2.y)), (7 * 599, 7 * 600 - 4));
+        // Splitting by position keeps the halves balanced, giving about
+        // 2n / cutoff calls, where splitting by x value would never shrink them
+        assert!(
+            calls < 4 * 1000 / DEFAULT_BRUTE_FORCE_CUTOFF,
+            "calls = {calls}"
+        );
+    }
+
+    #[test]
+    fn test_mostly_shared_x() {
+        let mut points: Vec<Point> = (0..1000).map(|i| Point { x: 5, y: 11 * i }).collect();
+        points.extend((0..100).map(|i| Point { x: 5 + 3 * i, y: 5 }));
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+        assert_eq!(dist, 3.0);
+    }
+
+    #[test]
+    fn test_unit_pair_straddles_midline() {
+        // With a cutoff of 3 the halves are split at x = 10, and the closest pair
+        // is one unit apart, one point on either side of the dividing line
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 3 },
+            Point { x: 9, y: 50 },
+            Point { x: 10, y: 50 },
+            Point { x: 20, y: 0 },
+            Point { x: 20, y: 3 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized_with_cutoff(&points, 3);
+        assert_eq!(dist, 1.0);
+        assert_eq!(p1.x.min(p2.x), 9);
+        assert_eq!(p1.x.max(p2.x), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_vector() {
+        // This should panic because we need at least 2 points
+        let points: Vec<Point> = Vec::new();
+        closest_pair_optimized(&points);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_checked_tests {
+    use super::*;
+
+    /// Divide and conquer without the band check, so pairs crossing the
+    /// dividing line are never found.
+    fn without_band<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>, T::Distance) {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.compare(&b.x));
+        let (left, right) = sorted.split_at(sorted.len() / 2);
+
+        let left = brute_force_raw(left, Metric::Euclidean);
+        let right = brute_force_raw(right, Metric::Euclidean);
+        if left.2 < right.2 {
+            left
+        } else {
+            right
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_optimized() {
+        for seed in 0..10 {
+            let points = random_points(500, 10_000, seed);
+            assert_eq!(
+                closest_pair_checked(&points),
+                closest_pair_optimized(&points)
+            );
+        }
+    }
+
+    #[test]
+    fn test_above_limit_is_unchecked() {
+        let points = random_points(CHECKED_BRUTE_FORCE_LIMIT + 1, 1 << 20, 3);
+        let (_, _, dist) = checked_with(&points, without_band);
+        assert!(dist >= closest_pair_optimized(&points).2);
+    }
+
+    #[test]
+    #[cfg_attr(
+        any(debug_assertions, feature = "verify"),
+        should_panic(expected = "Closest pair check failed")
+    )]
+    fn test_wrong_search_is_caught() {
+        // The closest pair straddles the dividing line at x = 10
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 5 },
+            Point { x: 9, y: 50 },
+            Point { x: 10, y: 50 },
+            Point { x: 20, y: 0 },
+            Point { x: 20, y: 5 },
+        ];
+        let (_, _, dist) = checked_with(&points, without_band);
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_single_point() {
+        closest_pair_checked(&[Point { x: 1, y: 1 }]);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_chunked_tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..5 {
+            let points = random_points(600, 5000, seed);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+
+            for chunk_size in [1, 2, 3, 7, 50, 599, 600, 1000] {
+                let (p1, p2, dist) = closest_pair_chunked(&points, chunk_size);
+                assert_eq!(dist, expected, "seed {seed}, chunk size {chunk_size}");
+                assert_eq!(eucid_distance(&p1, &p2), dist);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pair_across_chunks() {
+        // Sorted by x, chunks of 2 are [0, 10], [20, 29], [30, 100], and the closest
+        // pair, (29, 0) and (30, 0), sits across the last boundary
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 0 },
+            Point { x: 20, y: 0 },
+            Point { x: 29, y: 0 },
+            Point { x: 30, y: 0 },
+            Point { x: 100, y: 0 },
+        ];
+        let (p1, p2, dist) = closest_pair_chunked(&points, 2);
+        assert_eq!(dist, 1.0);
+        assert_eq!((p1.x.min(p2.x), p1.x.max(p2.x)), (29, 30));
+    }
+
+    #[test]
+    fn test_shared_x_columns() {
+        // Whole columns land in the margin, and the closest pair is many chunks apart
+        let mut points = Vec::new();
+        for x in [0, 3, 6] {
+            for y in 0..40 {
+                points.push(Point { x, y: y * 10 });
+            }
+        }
+        points.push(Point { x: 4, y: 395 });
+
+        let (_, _, dist) = closest_pair_chunked(&points, 4);
+        assert_eq!(dist, closest_pair_brute_force(&points).2);
+    }
+
+    #[test]
+    fn test_single_column() {
+        // Every point shares the same x, so the margin is the whole column so far,
+        // and chunks of 1 check each point against all of it
+        let points: Vec<Point> = random_points(20_000, 1_000_000_000, 17)
+            .into_iter()
+            .map(|p| Point { x: 5, y: p.y })
+            .collect();
+
+        let (p1, p2, dist) = closest_pair_chunked(&points, 1);
+        assert_eq!(dist, closest_pair_optimized(&points).2);
+        assert_eq!((p1.x, p2.x), (5, 5));
+        assert_eq!(eucid_distance(&p1, &p2), dist);
+    }
+
+    #[test]
+    fn test_signed_and_float() {
+        let points: Vec<Pointi> = random_points(300, 2000, 11)
+            .into_iter()
+            .map(|p| Pointi {
+                x: p.x as i32 - 1000,
+                y: p.y as i32 - 1000,
+            })
+            .collect();
+        assert_eq!(
+            closest_pair_chunked(&points, 5).2,
+            closest_pair_brute_force(&points).2
+        );
+
+        let points: Vec<PointF64> = points
+            .iter()
+            .map(|p| PointF64 {
+                x: p.x as f64 / 7.0,
+                y: p.y as f64 / 3.0,
+            })
+            .collect();
+        assert_eq!(
+            closest_pair_chunked(&points, 5).2,
+            closest_pair_brute_force(&points).2
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk size must be at least 1, got 0")]
+    fn test_zero_chunk_size() {
+        closest_pair_chunked(&[Point { x: 0, y: 0 }, Point { x: 1, y: 1 }], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_single_point() {
+        closest_pair_chunked(&[Point { x: 1, y: 1 }], 4);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_optimized_result_tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_tuple() {
+        for seed in 0..5 {
+            let points = random_points(1000, 10_000, seed);
+            let result = closest_pair_optimized_result(&points);
+            let (p1, p2, distance) = closest_pair_optimized(&points);
+
+            assert_eq!(result.pair(), (p1, p2));
+            assert_eq!(result.distance(), distance);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_optimized_result::<u32>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_optimized_result(&[Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod closest_pair_optimized_timed_tests {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn test_timings_populated() {
+        let points = random_points(100_000, 1 << 24, 12);
+        let (result, timings) = closest_pair_optimized_timed(&points);
+
+        assert!(timings.sort > Duration::ZERO);
+        assert!(timings.recursion > Duration::ZERO);
+        assert_eq!(timings.total(), timings.sort + timings.recursion);
+        assert_eq!(
+            result,
+            ClosestPairResult::from(closest_pair_optimized(&points))
+        );
+    }
+
+    #[test]
+    fn test_matches_untimed() {
+        for seed in 0..5 {
+            let points = random_points(1000, 10_000, seed);
+            let (result, _) = closest_pair_optimized_timed(&points);
+            assert_eq!(
+                result,
+                ClosestPairResult::from(closest_pair_optimized(&points))
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_optimized_timed::<u32>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_optimized_timed(&[Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_optimized_explained_tests {
+    use super::*;
+
+    /// A row of points 100 apart, with the pair `gap` apart starting at `at`.
+    fn row(n: u32, at: usize, gap: u32) -> Vec<Point> {
+        let mut points: Vec<Point> = (0..n).map(|i| Point { x: 100 * i, y: 0 }).collect();
+        points[at + 1].x = points[at].x + gap;
+        points
+    }
+
+    #[test]
+    fn test_strip() {
+        // The closest pair straddles the first split, between positions 49 and 50
+        let points = row(100, 49, 1);
+        let (result, origin) = closest_pair_optimized_explained(&points);
+        assert_eq!(result.pair(), (points[49], points[50]));
+        assert_eq!(origin, Origin::Strip);
+    }
+
+    #[test]
+    fn test_left_and_right() {
+        let points = row(100, 10, 3);
+        assert_eq!(closest_pair_optimized_explained(&points).1, Origin::Left);
+
+        let points = row(100, 80, 3);
+        assert_eq!(closest_pair_optimized_explained(&points).1, Origin::Right);
+    }
+
+    #[test]
+    fn test_matches_optimized() {
+        for seed in 0..10 {
+            let points = random_points(1000, 10_000, seed);
+            let (result, _) = closest_pair_optimized_explained(&points);
+            assert_eq!(
+                result,
+                ClosestPairResult::from(closest_pair_optimized(&points))
+            );
+        }
+    }
+
+    #[test]
+    fn test_small_input() {
+        // Solved by brute force, but still reported against the split
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+        assert_eq!(closest_pair_optimized_explained(&points).1, Origin::Strip);
+
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 50, y: 0 },
+            Point { x: 99, y: 0 },
+        ];
+        assert_eq!(closest_pair_optimized_explained(&points).1, Origin::Left);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_optimized_explained::<u32>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_bit_shift_tests {
+    use super::*;
+    use std::f32;
+
+    #[test]
+    fn test_small_set() {
+        // Basic test with known distances
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 0, y: 4 },
+            Point { x: 10, y: 10 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_bit_shift(points, 8);
+        assert_eq!(dist, 3.0);
+        assert!(
+            (p1.x == 0 && p1.y == 0 && p2.x == 3 && p2.y == 0)
+                || (p2.x == 0 && p2.y == 0 && p1.x == 3 && p1.y == 0)
+        );
+    }
+
+    #[test]
+    fn test_single_pair() {
+        // Test with just two points
+        let points = vec![Point { x: 5, y: 10 }, Point { x: 8, y: 14 }];
+
+        let (_, _, dist) = closest_pair_bit_shift(points, 8);
+        assert!(approx_eq(dist, 5.0, DISTANCE_EPSILON)); // Distance should be 5.0
+    }
+
+    #[test]
+    fn test_collinear_points() {
+        // Test with points in a straight line
+        let points = vec![
+            Point { x: 1, y: 1 },
+            Point { x: 3, y: 3 },
+            Point { x: 5, y: 5 },
+            Point { x: 7, y: 7 },
+            Point { x: 9, y: 9 },
+        ];
+
+        let (_, _, dist) = closest_pair_bit_shift(points, 8);
+        assert!(approx_eq(dist, 2.0 * f32::sqrt(2.0), DISTANCE_EPSILON)); // Distance should be 2√2
+    }
+
+    #[test]
+    fn test_grid_points() {
+        // Test with points arranged in a grid
+        let mut points = Vec::new();
+
+        // Create a 5x5 grid with points at integer coordinates
+        for x in 0..5 {
+            for y in 0..5 {
+                points.push(Point { x, y });
+            }
+        }
+
+        let (_, _, dist) = closest_pair_bit_shift(points, 8);
+        assert_eq!(dist, 1.0); // Minimum distance in a grid is 1.0
+    }
+
+    #[test]
+    fn test_duplicate_points() {
+        // Test with duplicate points (should give distance 0)
+        let points = vec![
+            Point { x: 10, y: 20 },
+            Point { x: 30, y: 40 },
+            Point { x: 10, y: 20 }, // Duplicate
+            Point { x: 50, y: 60 },
+        ];
+
+        let (_, _, dist) = closest_pair_bit_shift(points, 8);
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_large_range() {
+        // Test with points spread over a large range
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10000, y: 10000 },
+            Point { x: 20000, y: 20000 },
+            Point { x: 20005, y: 20005 }, // Closest to the previous point
+        ];
+
+        let (_, _, dist) = closest_pair_bit_shift(points, 16);
+        assert!(approx_eq(dist, 5.0 * f32::sqrt(2.0), DISTANCE_EPSILON)); // Should be 5√2
+    }
+
+    #[test]
+    fn test_random_points() {
+        // Generate 50000 random points
+        let points = random_points(50000, u32::pow(2, 31) - 1, 2);
+
+        // Run closest pair algorithm
+        let (p1, p2, dist) = closest_pair_bit_shift(points.clone(), 32);
+
+        // Compare with brute force result for validation
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+
+        // The window is a heuristic, so the result is a real pair that is at
+        // least as far apart as the true closest pair
+        assert_eq!(eucid_distance(&p1, &p2), dist);
+        assert!(dist >= bf_dist);
+    }
+
+    #[test]
+    fn test_window_misses_closest_pair() {
+        // A column of points at x = 0 sorts between (0, 0) and (1, 0),
+        // pushing the true closest pair further apart than the window
+        let bits = 8;
+        let mut points: Vec<Point> = (0..=bits).map(|k| Point { x: 0, y: 10 * k }).collect();
+        points.push(Point { x: 1, y: 0 });
+
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(bf_dist, 1.0);
+
+        let (_, _, dist) = closest_pair_bit_shift(points, bits as u8);
+        assert_eq!(dist, 10.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_vector() {
+        // This should panic because we need at least 2 points
+        let points: Vec<Point> = Vec::new();
+        closest_pair_bit_shift(points, 8);
+    }
+
+    #[test]
+    fn test_invalid_bits() {
+        let points = vec![Point { x: 1, y: 1 }, Point { x: 4, y: 5 }];
+        assert_eq!(
+            try_closest_pair_bit_shift(points.clone(), 0).unwrap_err(),
+            ClosestPairError::InvalidBits(0)
+        );
+        assert_eq!(
+            try_closest_pair_bit_shift(points, 33).unwrap_err(),
+            ClosestPairError::InvalidBits(33)
+        );
+    }
+
+    #[test]
+    fn test_coordinate_out_of_range() {
+        // 256 needs 9 bits and would be packed as 0
+        let points = vec![Point { x: 255, y: 3 }, Point { x: 0, y: 256 }];
+        assert_eq!(
+            try_closest_pair_bit_shift(points.clone(), 8).unwrap_err(),
+            ClosestPairError::CoordinateOutOfRange {
+                value: 256,
+                bits: 8
+            }
+        );
+        let (_, _, dist) = closest_pair_bit_shift(points, 9);
+        assert_eq!(
+            dist,
+            eucid_distance(&Point { x: 255, y: 3 }, &Point { x: 0, y: 256 })
+        );
+    }
+
+    #[test]
+    fn test_full_width_coordinates() {
+        let points = vec![
+            Point { x: u32::MAX, y: 0 },
+            Point {
+                x: u32::MAX,
+                y: u32::MAX,
+            },
+            Point { x: u32::MAX, y: 1 },
+        ];
+        let (_, _, dist) = closest_pair_bit_shift(points, 32);
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bits per coordinate must be between 1 and 32, got 0")]
+    fn test_zero_bits_panics() {
+        closest_pair_bit_shift(vec![Point { x: 0, y: 0 }, Point { x: 0, y: 0 }], 0);
+    }
+}
+
+#[cfg(test)]
+mod try_closest_pair_tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_input() {
+        let points: Vec<Point> = Vec::new();
+
+        assert_eq!(
+            try_closest_pair_brute_force(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_optimized(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_bit_shift(points, 8).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+
+    #[test]
+    fn test_single_point() {
+        let points = vec![Point { x: 7, y: 7 }];
+
+        assert_eq!(
+            try_closest_pair_brute_force(&points).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+        assert_eq!(
+            try_closest_pair_optimized(&points).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+        assert_eq!(
+            try_closest_pair_bit_shift(points, 8).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+
+    #[test]
+    fn test_two_points() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+
+        let (_, _, dist) = try_closest_pair_brute_force(&points).unwrap();
+        assert_eq!(dist, 5.0);
+        let (_, _, dist) = try_closest_pair_optimized(&points).unwrap();
+        assert_eq!(dist, 5.0);
+        let (_, _, dist) = try_closest_pair_bit_shift(points, 8).unwrap();
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_panicking_version_keeps_message() {
+        closest_pair_brute_force(&[Point { x: 1, y: 2 }]);
+    }
+}
+
+#[cfg(test)]
+mod signed_coordinates_tests {
+    use super::*;
+
+    #[test]
+    fn test_all_quadrants() {
+        // Closest pair straddles the origin across the third and first quadrants
+        let points = vec![
+            Pointi { x: 10, y: 10 },
+            Pointi { x: -10, y: 10 },
+            Pointi { x: -10, y: -10 },
+            Pointi { x: 10, y: -10 },
+            Pointi { x: -1, y: -1 },
+            Pointi { x: 1, y: 1 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        assert!(approx_eq(dist, 2.0 * f32::sqrt(2.0), DISTANCE_EPSILON));
+        assert_eq!(p1.x.min(p2.x), -1);
+        assert_eq!(p1.x.max(p2.x), 1);
+
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+    }
+
+    #[test]
+    fn test_negative_only() {
+        let points = vec![
+            Pointi { x: -100, y: -100 },
+            Pointi { x: -50, y: -60 },
+            Pointi { x: -47, y: -56 },
+            Pointi { x: -1, y: -90 },
+        ];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    fn test_random_points() {
+        let range = 1 << 30;
+
+        // Generate 2000 random points spanning all four quadrants
+        let points: Vec<Pointi> = random_points(2000, 2 * range - 1, 3)
+            .into_iter()
+            .map(|p| Pointi {
+                x: p.x as i32 - range as i32,
+                y: p.y as i32 - range as i32,
+            })
+            .collect();
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+
+        assert!(dist == bf_dist);
+    }
+}
+
+#[cfg(test)]
+mod wide_coordinates_tests {
+    use super::*;
+
+    /// Random points spread over `0..2^62`, far beyond `u32::MAX`.
+    fn wide_points(n: usize, seed: u64) -> Vec<PointU64> {
+        random_points(n, u32::MAX, seed)
+            .into_iter()
+            .map(|p| PointU64 {
+                x: (p.x as u64) << 30,
+                y: (p.y as u64) << 30,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_u32_extremes() {
+        // The diagonal between opposite corners squares to more than 2^64
+        let corners = vec![
+            Point { x: 0, y: 0 },
+            Point {
+                x: u32::MAX,
+                y: u32::MAX,
+            },
+        ];
+        let diagonal = (u32::MAX as f64 * 2f64.sqrt()) as f32;
+        assert!(approx_eq(diagonal, 6.074e9, 1e6));
+        assert_eq!(closest_pair_brute_force(&corners).2, diagonal);
+        assert_eq!(closest_pair_optimized(&corners).2, diagonal);
+        assert_eq!(closest_pair_bit_shift(corners.clone(), 32).2, diagonal);
+        assert_eq!(
+            closest_pair_optimized_sq(&corners).2,
+            2 * (u32::MAX as u128).pow(2)
+        );
+
+        // With all four corners the sides are the closest pairs
+        let mut points = corners;
+        points.push(Point { x: u32::MAX, y: 0 });
+        points.push(Point { x: 0, y: u32::MAX });
+        assert_eq!(
+            closest_pair_optimized_sq(&points).2,
+            (u32::MAX as u128).pow(2)
+        );
+        assert_eq!(closest_pair_brute_force(&points).2, u32::MAX as f32);
+        assert_eq!(farthest_pair_brute_force(&points).2, diagonal);
+    }
+
+    #[test]
+    fn test_three_far_points() {
+        // Every pair is more than 2^32 apart, so each squared distance needs 65 bits
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point {
+                x: u32::MAX,
+                y: 1_150_000_000,
+            },
+            Point {
+                x: 1_155_000_000,
+                y: u32::MAX,
+            },
+        ];
+        let expected = (points[1], points[2], eucid_distance(&points[1], &points[2]));
+        assert!(approx_eq(expected.2, 4.444e9, 1e6));
+
+        assert_eq!(closest_pair_brute_force(&points), expected);
+        assert_eq!(closest_pair_optimized(&points).2, expected.2);
+        assert_eq!(
+            farthest_pair_brute_force(&points).2,
+            eucid_distance(&points[0], &points[2])
+        );
+    }
+
+    #[test]
+    fn test_beyond_u32() {
+        let base = 1u64 << 50;
+        let points = vec![
+            PointU64 { x: base, y: base },
+            PointU64 {
+                x: base + 3,
+                y: base + 4,
+            },
+            PointU64 { x: 7, y: 1 << 40 },
+            PointU64 { x: 1 << 63, y: 0 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        assert_eq!(dist, 5.0);
+        assert_eq!(p1.x.min(p2.x), base);
+        assert_eq!(closest_pair_brute_force(&points).2, 5.0);
+    }
+
+    #[test]
+    fn test_random_points() {
+        // Differences of up to 2^62, far more than f64 can tell apart
+        let mut points = wide_points(5000, 11);
+        points.push(PointU64 {
+            x: points[0].x + 1,
+            y: points[0].y + 1,
+        });
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+        assert_eq!(dist, (2.0f64).sqrt() as f32);
+    }
+
+    #[test]
+    fn test_max_corners() {
+        // Each squared difference is just under 2^128, so their sum needs 129 bits
+        let corners = vec![
+            PointU64 { x: 0, y: 0 },
+            PointU64 {
+                x: u64::MAX,
+                y: u64::MAX,
+            },
+        ];
+        let expected = (u64::MAX as f64 * 2f64.sqrt()) as f32;
+        assert_eq!(closest_pair_brute_force(&corners).2, expected);
+        assert_eq!(closest_pair_optimized(&corners).2, expected);
+        assert_eq!(farthest_pair_brute_force(&corners).2, expected);
+
+        let side = WideDistance::from((u64::MAX as u128).pow(2));
+        assert_eq!(closest_pair_optimized_sq(&corners).2, side + side);
+
+        let corners = vec![
+            Point::<i64> {
+                x: i64::MIN,
+                y: i64::MIN,
+            },
+            Point::<i64> {
+                x: i64::MAX,
+                y: i64::MAX,
+            },
+        ];
+        assert_eq!(closest_pair_brute_force(&corners).2, expected);
+        assert_eq!(closest_pair_optimized(&corners).2, expected);
+        assert_eq!(closest_pair_optimized_sq(&corners).2, side + side);
+    }
+
+    #[test]
+    fn test_signed() {
+        let points = vec![
+            Point::<i64> {
+                x: -(1 << 62),
+                y: 0,
+            },
+            Point::<i64> {
+                x: -(1 << 40),
+                y: 1 << 40,
+            },
+            Point::<i64> {
+                x: -(1 << 40) - 6,
+                y: (1 << 40) + 8,
+            },
+            Point::<i64> { x: 1 << 62, y: 0 },
+        ];
+        assert_eq!(closest_pair_optimized(&points).2, 10.0);
+        assert_eq!(closest_pair_brute_force(&points).2, 10.0);
+    }
+
+    #[test]
+    fn test_farthest_pair() {
+        let points = wide_points(1000, 12);
+        let (_, _, dist) = farthest_pair_optimized(points.clone());
+        let (_, _, bf_dist) = farthest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+
+        // The hull of a square with an extreme corner, where cross products need 130 bits
+        let points = vec![
+            PointU64 { x: 0, y: 0 },
+            PointU64 { x: u64::MAX, y: 0 },
+            PointU64 {
+                x: u64::MAX,
+                y: u64::MAX,
+            },
+            PointU64 { x: 0, y: u64::MAX },
+            PointU64 {
+                x: 1 << 63,
+                y: 1 << 63,
+            },
+        ];
+        assert_eq!(convex_hull(&points).len(), 4);
+    }
+}
+
+#[cfg(test)]
+mod precision_tests {
+    use super::*;
+
+    fn large_coordinates() -> Vec<Point> {
+        vec![
+            Point { x: 0, y: 0 },
+            Point {
+                x: 2_000_000_000,
+                y: 0,
+            },
+            Point {
+                x: 2_000_000_001,
+                y: 0,
+            },
+        ]
+    }
+
+    fn is_expected_pair(p1: Point, p2: Point) -> bool {
+        p1.x.min(p2.x) == 2_000_000_000 && p1.x.max(p2.x) == 2_000_000_001
+    }
+
+    #[test]
+    fn test_brute_force_large_coordinates() {
+        let (p1, p2, dist) = closest_pair_brute_force(&large_coordinates());
+        assert!(is_expected_pair(p1, p2));
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    fn test_optimized_large_coordinates() {
+        let (p1, p2, dist) = closest_pair_optimized(&large_coordinates());
+        assert!(is_expected_pair(p1, p2));
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    fn test_bit_shift_large_coordinates() {
+        let (p1, p2, dist) = closest_pair_bit_shift(large_coordinates(), 31);
+        assert!(is_expected_pair(p1, p2));
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    fn test_distinguishes_close_large_distances() {
+        // Squared distances 4e18 + 4 and 4e18 + 1 round to the same f32, so the
+        // closer pair, found second, would not replace the first one
+        let points: Vec<Point> = vec![
+            Point {
+                x: 4_000_000_000,
+                y: 3,
+            },
+            Point {
+                x: 2_000_000_000,
+                y: 1,
+            },
+            Point { x: 0, y: 0 },
+        ];
+
+        let (p1, p2, _) = closest_pair_brute_force(&points);
+        assert_eq!((p1.x, p2.x), (2_000_000_000, 0));
+    }
+}
+
+#[cfg(test)]
+mod metric_tests {
+    use super::*;
+
+    fn points() -> Vec<Point> {
+        vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 3 }, // 3√2 from the origin, but 6 in Manhattan distance
+            Point { x: 20, y: 0 },
+            Point { x: 25, y: 0 }, // 5 from (20, 0) under every metric
+        ]
+    }
+
+    fn contains(p1: Point, p2: Point, x1: u32, x2: u32) -> bool {
+        (p1.x == x1 && p2.x == x2) || (p1.x == x2 && p2.x == x1)
+    }
+
+    #[test]
+    fn test_manhattan_differs_from_euclidean() {
+        let (p1, p2, dist) = closest_pair_brute_force_metric(&points(), Metric::Euclidean);
+        assert!(contains(p1, p2, 0, 3));
+        assert!(approx_eq(dist, 3.0 * f32::sqrt(2.0), DISTANCE_EPSILON));
+
+        let (p1, p2, dist) = closest_pair_brute_force_metric(&points(), Metric::Manhattan);
+        assert!(contains(p1, p2, 20, 25));
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    fn test_optimized_manhattan_differs_from_euclidean() {
+        let (p1, p2, _) = closest_pair_optimized_metric(&points(), Metric::Euclidean);
+        assert!(contains(p1, p2, 0, 3));
+
+        let (p1, p2, dist) = closest_pair_optimized_metric(&points(), Metric::Manhattan);
+        assert!(contains(p1, p2, 20, 25));
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    fn test_chebyshev() {
+        let (p1, p2, dist) = closest_pair_optimized_metric(&points(), Metric::Chebyshev);
+        assert!(contains(p1, p2, 0, 3));
+        assert_eq!(dist, 3.0);
+    }
+
+    #[test]
+    fn test_euclidean_matches_default() {
+        let (_, _, dist) = closest_pair_optimized_metric(&points(), Metric::Euclidean);
+        let (_, _, expected) = closest_pair_optimized(&points());
+        assert_eq!(dist, expected);
+    }
+
+    #[test]
+    fn test_random_points() {
+        // Generate 2000 random points on a small grid, so ties and band crowding happen
+        let points = random_points(2000, 4999, 4);
+
+        for metric in [Metric::Euclidean, Metric::Manhattan, Metric::Chebyshev] {
+            let (_, _, dist) = closest_pair_optimized_metric(&points, metric);
+            let (_, _, bf_dist) = closest_pair_brute_force_metric(&points, metric);
+            assert_eq!(dist, bf_dist);
+        }
+    }
+}
+
+#[cfg(test)]
+mod strip_window_tests {
+    use super::*;
+
+    const METRICS: [Metric; 3] = [Metric::Euclidean, Metric::Manhattan, Metric::Chebyshev];
+
+    /// Checks every metric and a range of cutoffs against brute force.
+    fn assert_matches_brute_force(points: &[Point]) {
+        for metric in METRICS {
+            let (_, _, expected) = closest_pair_brute_force_metric(points, metric);
+            for cutoff in [3, 4, 5, 16] {
+                let (p1, p2, raw) = optimized_raw_with_cutoff(points, metric, cutoff);
+                assert_eq!(
+                    metric.finish::<u32>(raw),
+                    expected,
+                    "metric = {metric:?}, cutoff = {cutoff}"
+                );
+                assert_eq!(metric.raw_distance(&p1, &p2), raw);
+            }
+        }
+    }
+
+    /// A narrow lattice with spacing 2, so the band is packed with equidistant points.
+    fn lattice() -> Vec<Point> {
+        let mut points = Vec::new();
+        for y in (0..60).step_by(2) {
+            for x in (10..20).step_by(2) {
+                points.push(Point { x, y });
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_equidistant_lattice() {
+        assert_matches_brute_force(&lattice());
+    }
+
+    #[test]
+    fn test_single_coincident_pair() {
+        // Every point is 2 from its neighbours, except one duplicate anywhere in the lattice
+        let base = lattice();
+        for &point in &base {
+            let mut points = base.clone();
+            points.push(point);
+            assert_matches_brute_force(&points);
+        }
+    }
+
+    #[test]
+    fn test_single_closer_pair() {
+        // A point between two lattice points, 1 from each, with everything else equidistant
+        let base = lattice();
+        for &point in &base {
+            for offset in [
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 },
+            ] {
+                let mut points = base.clone();
+                points.push(Point {
+                    x: point.x + offset.x,
+                    y: point.y + offset.y,
+                });
+                assert_matches_brute_force(&points);
+            }
+        }
+    }
+
+    #[test]
+    fn test_coincident_columns() {
+        // Whole columns repeated on both sides of the split, with most points coincident
+        for copies in 1..5 {
+            let mut points = Vec::new();
+            for _ in 0..copies {
+                for y in 0..30 {
+                    points.push(Point { x: 7, y });
+                    points.push(Point { x: 8, y: 2 * y });
+                }
+            }
+            assert_matches_brute_force(&points);
+        }
+    }
+
+    #[test]
+    fn test_pair_far_apart_in_band() {
+        // Split at x = 100 into halves whose points are all at least 100 apart. In y order
+        // the band is (42, 0), (143, 0), (100, 83), (0, 94), (200, 97), (100, 99), so the
+        // closest pair, 16 apart, is three places apart with two equidistant points between
+        let points = vec![
+            Point { x: 42, y: 0 },
+            Point { x: 100, y: 83 },
+            Point { x: 0, y: 94 },
+            Point { x: 200, y: 97 },
+            Point { x: 143, y: 0 },
+            Point { x: 100, y: 99 },
+        ];
+        assert_matches_brute_force(&points);
+        assert_eq!(closest_pair_optimized_with_cutoff(&points, 3).2, 16.0);
+    }
+
+    #[test]
+    fn test_crowded_random_points() {
+        for seed in 0..20 {
+            assert_matches_brute_force(&random_points(300, 40, seed));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod closest_pair_brute_force_parallel_tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_small_set() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 0, y: 4 },
+            Point { x: 10, y: 10 },
+        ];
+
+        let (_, _, dist) = closest_pair_brute_force_parallel(&points);
+        assert_eq!(dist, 3.0);
+    }
+
+    #[test]
+    fn test_matches_serial_on_seeded_points() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut points = Vec::new();
+        for _ in 0..5000 {
+            points.push(Point {
+                x: rng.gen_range(0..100_000),
+                y: rng.gen_range(0..100_000),
+            });
+        }
+
+        let (p1, p2, dist) = closest_pair_brute_force_parallel(&points);
+        let (s1, s2, serial_dist) = closest_pair_brute_force(&points);
+
+        assert_eq!(dist, serial_dist);
+        assert_eq!((p1.x, p1.y, p2.x, p2.y), (s1.x, s1.y, s2.x, s2.y));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_single_point() {
+        closest_pair_brute_force_parallel(&[Point { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    #[should_panic(expected = "all distances might be infinite")]
+    fn test_infinite_distance() {
+        let points = vec![PointF64 { x: 0.0, y: 0.0 }, PointF64 { x: 1e200, y: 0.0 }];
+        closest_pair_brute_force_parallel(&points);
+    }
+
+    #[test]
+    fn test_skips_nan_distances() {
+        // Two points at the same infinite x are a NaN distance apart
+        let points = vec![
+            PointF64 {
+                x: f64::INFINITY,
+                y: 0.0,
+            },
+            PointF64 {
+                x: f64::INFINITY,
+                y: 0.0,
+            },
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 2.0, y: 0.0 },
+            PointF64 { x: 5.0, y: 0.0 },
+        ];
+        let (p1, p2, dist) = closest_pair_brute_force_parallel(&points);
+        assert_eq!((p1, p2, dist), (points[2], points[3], 2.0));
+    }
+
+    #[test]
+    fn test_optimized_matches_serial_on_seeded_points() {
+        // Large enough for several levels of the recursion to run in parallel
+        for seed in 0..3 {
+            let points = random_points(PARALLEL_THRESHOLD * 8 + 3, 1 << 24, seed);
+
+            let (p1, p2, dist) = closest_pair_optimized_parallel(&points);
+            let (s1, s2, serial_dist) = closest_pair_optimized(&points);
+
+            assert_eq!(dist, serial_dist);
+            assert_eq!((p1, p2), (s1, s2));
+        }
+    }
+
+    #[test]
+    fn test_optimized_parallel_small_and_duplicates() {
+        let points = vec![Point { x: 3, y: 4 }, Point { x: 0, y: 0 }];
+        let (_, _, dist) = closest_pair_optimized_parallel(&points);
+        assert_eq!(dist, 5.0);
+
+        let mut points = random_points(PARALLEL_THRESHOLD * 4, 1 << 30, 9);
+        points.push(points[PARALLEL_THRESHOLD]);
+        let (p1, p2, dist) = closest_pair_optimized_parallel(&points);
+        assert_eq!((p1, dist), (p2, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod float_coordinates_tests {
+    use super::*;
+
+    #[test]
+    fn test_small_set() {
+        let points = vec![
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 0.3, y: 0.4 },
+            PointF64 { x: -2.5, y: 1.0 },
+            PointF64 { x: 10.0, y: 10.0 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        assert!(approx_eq(dist, 0.5, DISTANCE_EPSILON));
+        assert!(p1.x.min(p2.x) == 0.0 && p1.x.max(p2.x) == 0.3);
+
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+    }
+
+    #[test]
+    fn test_fractional_band() {
+        // The closest pair straddles the dividing line less than one unit apart
+        let points = vec![
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 1.0, y: 5.0 },
+            PointF64 { x: 1.6, y: 2.0 },
+            PointF64 { x: 2.2, y: 2.1 },
+            PointF64 { x: 5.0, y: 9.0 },
+            PointF64 { x: 9.0, y: 0.0 },
+        ];
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+    }
+
+    #[test]
+    fn test_band_width_not_truncated() {
+        // The left half's closest pair is 1.5 apart, and the true closest pair
+        // crosses the dividing line at x = 10.0 from 1.1 units away. A band
+        // width truncated to an integer (1) would leave (8.9, 0.0) out
+        let points = vec![
+            PointF64 { x: 0.0, y: 100.0 },
+            PointF64 { x: 0.0, y: 101.5 },
+            PointF64 { x: 8.9, y: 0.0 },
+            PointF64 { x: 10.0, y: 0.0 },
+            PointF64 { x: 20.0, y: 50.0 },
+            PointF64 { x: 30.0, y: -50.0 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized(&points);
+        assert_eq!(p1.x.min(p2.x), 8.9);
+        assert_eq!(p1.x.max(p2.x), 10.0);
+        assert_eq!(dist, 1.1f64 as f32);
+    }
+
+    #[test]
+    fn test_band_below_one_unit() {
+        // With a cutoff of 3 the halves are split at x = 10.0, and the left half's
+        // closest pair is only 0.8 apart. A band width cast to an integer (0) would
+        // hold just the points on the dividing line and miss (9.5, 50.0)
+        let points = vec![
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 0.0, y: 0.8 },
+            PointF64 { x: 9.5, y: 50.0 },
+            PointF64 { x: 10.0, y: 50.0 },
+            PointF64 { x: 20.0, y: 0.0 },
+            PointF64 { x: 20.0, y: 5.0 },
+        ];
+
+        let (p1, p2, dist) = closest_pair_optimized_with_cutoff(&points, 3);
+        assert_eq!(p1.x.min(p2.x), 9.5);
+        assert_eq!(p1.x.max(p2.x), 10.0);
+        assert_eq!(dist, 0.5);
+    }
+
+    #[test]
+    fn test_random_points() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Generate 2000 random points spanning all four quadrants
+        let mut points = Vec::new();
+        for _ in 0..2000 {
+            points.push(PointF64 {
+                x: rng.gen_range(-1000.0..1000.0),
+                y: rng.gen_range(-1000.0..1000.0),
+            });
+        }
+
+        let (_, _, dist) = closest_pair_optimized(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+
+        assert_eq!(dist, bf_dist);
+    }
+
+    #[test]
+    fn test_infinite_distance() {
+        // 1e39 is past f32::MAX, and squaring 1e200 overflows even a f64
+        for far in [1e39, 1e200] {
+            let points = vec![PointF64 { x: 0.0, y: 0.0 }, PointF64 { x: far, y: 0.0 }];
+            assert_eq!(
+                try_closest_pair_brute_force(&points).unwrap_err(),
+                ClosestPairError::InfiniteDistance
+            );
+            assert_eq!(
+                try_closest_pair_brute_force_iter(points.iter().copied()).unwrap_err(),
+                ClosestPairError::InfiniteDistance
+            );
+        }
+
+        // As soon as one pair is close enough, its distance is returned
+        let points = vec![
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 1e200, y: 0.0 },
+            PointF64 { x: 1e200, y: 3e30 },
+        ];
+        assert_eq!(closest_pair_brute_force(&points).2, 3e30);
+    }
+
+    #[test]
+    #[should_panic(expected = "No closest pair found - all distances might be infinite")]
+    fn test_infinite_distance_panics() {
+        let points = vec![PointF64 { x: -1e39, y: 0.0 }, PointF64 { x: 0.0, y: 0.0 }];
+        closest_pair_brute_force(&points);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_indices_tests {
+    use super::*;
+
+    #[test]
+    fn test_small_set() {
+        let points = vec![
+            Point { x: 10, y: 10 },
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 4 },
+            Point { x: 3, y: 0 },
+        ];
+
+        let (i, j, dist) = closest_pair_indices(&points);
+        assert_eq!((i, j), (1, 3));
+        assert_eq!(dist, 3.0);
+    }
+
+    #[test]
+    fn test_duplicate_points() {
+        let points = vec![
+            Point { x: 10, y: 20 },
+            Point { x: 30, y: 40 },
+            Point { x: 10, y: 20 }, // Duplicate
+            Point { x: 50, y: 60 },
+        ];
+
+        let (i, j, dist) = closest_pair_indices(&points);
+        assert_eq!((i, j), (0, 2));
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_all_duplicates_are_distinct_indices() {
+        let points = vec![Point { x: 1, y: 1 }; 20];
+
+        let (i, j, dist) = closest_pair_indices(&points);
+        assert!(i < j && j < points.len());
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_random_points() {
+        let points = random_points(2000, 99_999, 5);
+
+        let (i, j, dist) = closest_pair_indices(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+
+        assert_ne!(i, j);
+        assert_eq!(dist, bf_dist);
+        assert_eq!(eucid_distance(&points[i], &points[j]), dist);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_labeled_tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    fn labeled(points: &[Point]) -> Vec<LabeledPoint<String>> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| LabeledPoint {
+                x: p.x,
+                y: p.y,
+                label: alloc::format!("point {i}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_string_labels() {
+        let points = vec![
+            LabeledPoint {
+                x: 0,
+                y: 0,
+                label: "Amsterdam".to_string(),
+            },
+            LabeledPoint {
+                x: 100,
+                y: 100,
+                label: "Berlin".to_string(),
+            },
+            LabeledPoint {
+                x: 51,
+                y: 50,
+                label: "Brussels".to_string(),
+            },
+            LabeledPoint {
+                x: 50,
+                y: 50,
+                label: "Paris".to_string(),
+            },
+        ];
+
+        let (p1, p2, distance) = closest_pair_labeled(&points);
+        assert_eq!(p1.label, "Brussels");
+        assert_eq!(p2.label, "Paris");
+        assert_eq!(distance, 1.0);
+    }
+
+    #[test]
+    fn test_duplicate_locations_keep_their_labels() {
+        let points = labeled(&[
+            Point { x: 5, y: 5 },
+            Point { x: 0, y: 0 },
+            Point { x: 5, y: 5 },
+        ]);
+        let (p1, p2, distance) = closest_pair_labeled(&points);
+        assert_eq!(
+            (p1.label.as_str(), p2.label.as_str()),
+            ("point 0", "point 2")
+        );
+        assert_eq!(distance, 0.0);
+    }
+
+    #[test]
+    fn test_matches_indices() {
+        let locations = random_points(1000, 1 << 16, 17);
+        let points = labeled(&locations);
+
+        let (i, j, distance) = closest_pair_indices(&locations);
+        let (p1, p2, labeled_distance) = closest_pair_labeled(&points);
+        assert_eq!(p1, points[i]);
+        assert_eq!(p2, points[j]);
+        assert_eq!(labeled_distance, distance);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_labeled::<String>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_labeled(&labeled(&[Point { x: 1, y: 1 }])).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_single_point() {
+        closest_pair_labeled(&[LabeledPoint {
+            x: 1,
+            y: 1,
+            label: (),
+        }]);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_positioned_tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct City {
+        name: &'static str,
+        lon: u32,
+        lat: u32,
+    }
+
+    impl Positioned for City {
+        fn x(&self) -> u32 {
+            self.lon
+        }
+
+        fn y(&self) -> u32 {
+            self.lat
+        }
+    }
+
+    #[test]
+    fn test_custom_struct() {
+        let cities = vec![
+            City {
+                name: "Lisbon",
+                lon: 0,
+                lat: 0,
+            },
+            City {
+                name: "Vienna",
+                lon: 2500,
+                lat: 900,
+            },
+            City {
+                name: "Madrid",
+                lon: 500,
+                lat: 100,
+            },
+            City {
+                name: "Bratislava",
+                lon: 2555,
+                lat: 900,
+            },
+        ];
+
+        let (c1, c2, distance) = closest_pair_positioned(&cities);
+        assert_eq!((c1.name, c2.name), ("Vienna", "Bratislava"));
+        assert_eq!(distance, 55.0);
+    }
+
+    #[test]
+    fn test_matches_points() {
+        let points = random_points(1000, 1 << 16, 18);
+        let (p1, p2, distance) = closest_pair_positioned(&points);
+        assert_eq!(eucid_distance(&p1, &p2), distance);
+        assert_eq!(distance, closest_pair_optimized(&points).2);
+    }
+
+    #[test]
+    fn test_weighted_points_ignore_weights() {
+        let points = vec![
+            WeightedPoint {
+                x: 0,
+                y: 0,
+                w: 100.0,
+            },
+            WeightedPoint {
+                x: 1,
+                y: 0,
+                w: 100.0,
+            },
+            WeightedPoint {
+                x: 50,
+                y: 0,
+                w: 0.0,
+            },
+        ];
+        let (p1, p2, distance) = closest_pair_positioned(&points);
+        assert_eq!((p1, p2, distance), (points[0], points[1], 1.0));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_positioned::<City>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        let city = City {
+            name: "Oslo",
+            lon: 1,
+            lat: 1,
+        };
+        assert_eq!(
+            try_closest_pair_positioned(&[city]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_to_centroid_tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetric_set() {
+        // A plus shape around (10, 10) with its centre, which is the centroid
+        let points = vec![
+            Point { x: 0, y: 10 },
+            Point { x: 20, y: 10 },
+            Point { x: 10, y: 10 },
+            Point { x: 10, y: 0 },
+            Point { x: 10, y: 20 },
+        ];
+        assert_eq!(closest_to_centroid(&points), (Point { x: 10, y: 10 }, 0.0));
+    }
+
+    #[test]
+    fn test_centroid_not_a_point() {
+        // A square around (5, 5) and one point just off centre
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 0 },
+            Point { x: 0, y: 10 },
+            Point { x: 10, y: 10 },
+            Point { x: 5, y: 10 },
+        ];
+        // The centroid is (5, 6)
+        assert_eq!(closest_to_centroid(&points), (Point { x: 5, y: 10 }, 4.0));
+    }
+
+    #[test]
+    fn test_ties_keep_first() {
+        // Every corner is the same distance from the centre
+        let points = vec![
+            Point { x: 10, y: 10 },
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 0 },
+            Point { x: 0, y: 10 },
+        ];
+        let (nearest, dist) = closest_to_centroid(&points);
+        assert_eq!(nearest, points[0]);
+        assert!(approx_eq(dist, 50.0f32.sqrt(), DISTANCE_EPSILON));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // Scaled by the number of points the centroid is a sum, so distances to it
+        // compare exactly in integers
+        let points = random_points(500, 1000, 6);
+        let n = points.len() as i128;
+        let sum_x: i128 = points.iter().map(|p| p.x as i128).sum();
+        let sum_y: i128 = points.iter().map(|p| p.y as i128).sum();
+        let scaled_sq =
+            |p: &Point| (n * p.x as i128 - sum_x).pow(2) + (n * p.y as i128 - sum_y).pow(2);
+        let best = points.iter().map(scaled_sq).min().unwrap();
+
+        let (nearest, dist) = closest_to_centroid(&points);
+        assert_eq!(scaled_sq(&nearest), best);
+        assert!(approx_eq(
+            dist,
+            ((best as f64).sqrt() / n as f64) as f32,
+            DISTANCE_EPSILON
+        ));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        // The centroid is (2^31 + 20, 0), which as a f32 would round to 2^31
+        let top = 1u32 << 31;
+        let points = vec![
+            Point { x: top + 60, y: 0 },
+            Point { x: top - 40, y: 0 },
+            Point { x: top + 40, y: 0 },
+        ];
+        assert_eq!(closest_to_centroid(&points), (points[2], 20.0));
+    }
+
+    #[test]
+    fn test_float_points() {
+        let points = vec![
+            PointF64 { x: -1.5, y: 0.0 },
+            PointF64 { x: 1.5, y: 0.0 },
+            PointF64 { x: 0.5, y: 0.0 },
+        ];
+        // The centroid is (1/6, 0)
+        let (nearest, dist) = closest_to_centroid(&points);
+        assert_eq!(nearest, points[2]);
+        assert!(approx_eq(dist, 1.0 / 3.0, DISTANCE_EPSILON));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find closest pair with empty vector")]
+    fn test_empty_panics() {
+        closest_to_centroid::<u32>(&[]);
+    }
+}
+
+#[cfg(test)]
+mod k_closest_pairs_tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_ascending() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 0, y: 4 },
+            Point { x: 10, y: 10 },
+        ];
+
+        let pairs = k_closest_pairs(&points, 3);
+        let dists: Vec<f32> = pairs.iter().map(|&(_, _, d)| d).collect();
+        assert_eq!(dists, vec![3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_k_larger_than_pair_count() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 5 },
+            Point { x: 7, y: 2 },
+            Point { x: 4, y: 4 },
+        ];
+
+        // 4 points only make 6 pairs
+        let pairs = k_closest_pairs(&points, 100);
+        assert_eq!(pairs.len(), 6);
+        assert!(pairs.windows(2).all(|w| w[0].2 <= w[1].2));
+
+        // Neither overflows nor tries to allocate room for k pairs
+        assert_eq!(k_closest_pairs(&points, usize::MAX), pairs);
+        assert_eq!(k_closest_pairs(&points, 1 << 40), pairs);
+    }
+
+    #[test]
+    fn test_k_zero_and_tiny_inputs() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+        assert!(k_closest_pairs(&points, 0).is_empty());
+        assert!(k_closest_pairs(&points[..1], 3).is_empty());
+        assert!(k_closest_pairs::<u32>(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_deterministic_ties() {
+        // All 4 sides of the unit square tie at distance 1
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 1 },
+            Point { x: 1, y: 1 },
+        ];
+
+        let pairs = k_closest_pairs(&points, 2);
+        let coords: Vec<(u32, u32, u32, u32)> =
+            pairs.iter().map(|(a, b, _)| (a.x, a.y, b.x, b.y)).collect();
+        // Lowest input positions win the tie: (0, 1) and then (0, 2)
+        assert_eq!(coords, vec![(0, 0, 1, 0), (0, 0, 0, 1)]);
+    }
+
+    #[test]
+    fn test_first_matches_closest_pair() {
+        let points = random_points(300, 9999, 6);
+
+        let pairs = k_closest_pairs(&points, 10);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(pairs.len(), 10);
+        assert_eq!(pairs[0].2, bf_dist);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_bichromatic_tests {
+    use super::*;
+
+    #[test]
+    fn test_ignores_same_set_pairs() {
+        // The two red points are right next to each other, but only cross pairs count
+        let red = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+        let blue = vec![Point { x: 100, y: 100 }, Point { x: 7, y: 8 }];
+
+        let (r, b, distance) = closest_pair_bichromatic(&red, &blue);
+        assert_eq!((r.x, r.y), (1, 0));
+        assert_eq!((b.x, b.y), (7, 8));
+        assert_eq!(distance, 10.0);
+    }
+
+    #[test]
+    fn test_shared_coordinates() {
+        let red = vec![Point { x: 5, y: 5 }, Point { x: 20, y: 1 }];
+        let blue = vec![Point { x: 9, y: 9 }, Point { x: 20, y: 1 }];
+
+        let (r, b, distance) = closest_pair_bichromatic(&red, &blue);
+        assert_eq!((r.x, r.y, b.x, b.y), (20, 1, 20, 1));
+        assert_eq!(distance, 0.0);
+    }
+
+    #[test]
+    fn test_single_point_sets() {
+        let red = vec![Point { x: 0, y: 0 }];
+        let blue = vec![Point { x: 3, y: 4 }];
+        assert_eq!(closest_pair_bichromatic(&red, &blue).2, 5.0);
+    }
+
+    #[test]
+    fn test_empty_sets() {
+        let points = vec![Point { x: 0, y: 0 }];
+        let empty: Vec<Point> = Vec::new();
+
+        assert_eq!(
+            try_closest_pair_bichromatic(&empty, &points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_bichromatic(&points, &empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find closest pair with empty vector")]
+    fn test_empty_set_panics() {
+        let points = vec![Point { x: 0, y: 0 }];
+        closest_pair_bichromatic(&points, &[]);
+    }
+}
+
+#[cfg(test)]
+mod farthest_pair_tests {
+    use super::*;
+
+    #[test]
+    fn test_collinear_points() {
+        let points = vec![
+            Point { x: 4, y: 4 },
+            Point { x: 1, y: 1 },
+            Point { x: 9, y: 9 },
+            Point { x: 0, y: 0 },
+            Point { x: 6, y: 6 },
+        ];
+
+        let (p1, p2, distance) = farthest_pair_brute_force(&points);
+        let mut xs = [p1.x, p2.x];
+        xs.sort();
+        assert_eq!(xs, [0, 9]);
+        assert_eq!(distance, (162.0f64).sqrt() as f32);
+
+        let (p1, p2, distance) = farthest_pair_optimized(points);
+        let mut xs = [p1.x, p2.x];
+        xs.sort();
+        assert_eq!(xs, [0, 9]);
+        assert_eq!(distance, (162.0f64).sqrt() as f32);
+    }
+
+    #[test]
+    fn test_square_diagonal() {
+        let points: Vec<Point> = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 0 },
+            Point { x: 10, y: 10 },
+            Point { x: 0, y: 10 },
+            Point { x: 5, y: 5 },
+        ];
+
+        let expected = (200.0f64).sqrt() as f32;
+        let (p1, p2, distance) = farthest_pair_brute_force(&points);
+        assert_eq!(distance, expected);
+        assert_eq!(p1.x.abs_diff(p2.x), 10);
+        assert_eq!(p1.y.abs_diff(p2.y), 10);
+
+        let (p1, p2, distance) = farthest_pair_optimized(points);
+        assert_eq!(distance, expected);
+        assert_eq!(p1.x.abs_diff(p2.x), 10);
+        assert_eq!(p1.y.abs_diff(p2.y), 10);
+    }
+
+    #[test]
+    fn test_identical_points() {
+        let points = vec![Point { x: 3, y: 3 }; 4];
+        assert_eq!(farthest_pair_brute_force(&points).2, 0.0);
+        assert_eq!(farthest_pair_optimized(points).2, 0.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_farthest_pair_brute_force(&empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_farthest_pair_optimized(vec![Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+
+    #[test]
+    fn test_optimized_matches_brute_force() {
+        for n in [2, 3, 10, 100, 1000] {
+            // A small grid produces plenty of duplicate, collinear and parallel hull edges
+            for max in [5, 1_000_000] {
+                let points: Vec<Point<i32>> = random_points(n, 2 * max, n as u64)
+                    .into_iter()
+                    .map(|p| Point {
+                        x: p.x as i32 - max as i32,
+                        y: p.y as i32 - max as i32,
+                    })
+                    .collect();
+
+                let (_, _, bf_dist) = farthest_pair_brute_force(&points);
+                let (_, _, opt_dist) = farthest_pair_optimized(points);
+                assert_eq!(bf_dist, opt_dist);
+            }
+        }
+    }
+
+    #[test]
+    fn test_float_coordinates() {
+        let points = vec![
+            Point { x: 0.5, y: 0.5 },
+            Point { x: -1.5, y: 0.5 },
+            Point { x: 0.5, y: 3.5 },
+            Point { x: 0.0, y: 1.0 },
+        ];
+
+        let (_, _, distance) = farthest_pair_optimized(points.clone());
+        assert_eq!(distance, farthest_pair_brute_force(&points).2);
+        assert_eq!(distance, (13.0f64).sqrt() as f32);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_dedup_tests {
+    use super::*;
+
+    fn rec_calls() -> usize {
+        REC_CALLS.with(|calls| calls.get())
+    }
+
+    #[test]
+    fn test_duplicates_skip_recursion() {
+        let mut points: Vec<Point> = (0..1000)
+            .map(|i| Point {
+                x: i * 10,
+                y: i * 7,
+            })
+            .collect();
+        points.push(Point { x: 5000, y: 3500 });
+
+        let before = rec_calls();
+        let (p1, p2, distance) = closest_pair_dedup(points);
+        assert_eq!(rec_calls(), before);
+
+        assert_eq!(p1, Point { x: 5000, y: 3500 });
+        assert_eq!(p1, p2);
+        assert_eq!(distance, 0.0);
+    }
+
+    #[test]
+    fn test_no_duplicates_falls_through() {
+        let points: Vec<Point> = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 10 },
+            Point { x: 5, y: 5 },
+            Point { x: 7, y: 7 },
+            Point { x: 30, y: 1 },
+        ];
+
+        let before = rec_calls();
+        let (_, _, distance) = closest_pair_dedup(points.clone());
+        assert!(rec_calls() > before);
+        assert_eq!(distance, closest_pair_optimized(&points).2);
+    }
+
+    #[test]
+    fn test_float_duplicates() {
+        let points = vec![
+            Point { x: 0.25, y: 1.5 },
+            Point { x: 3.0, y: 3.0 },
+            Point { x: 0.25, y: 1.5 },
+        ];
+        assert_eq!(closest_pair_dedup(points).2, 0.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_dedup(vec![Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod dedup_within_tests {
+    use super::*;
+
+    #[test]
+    fn test_noisy_cluster_collapses() {
+        // Three readings of the same spot, each off by 1, would be the closest
+        // pair. Collapsed, the closest pair is the two real points 5 apart
+        let points = vec![
+            Point { x: 100, y: 100 },
+            Point { x: 0, y: 0 },
+            Point { x: 101, y: 100 },
+            Point { x: 3, y: 4 },
+            Point { x: 100, y: 99 },
+            Point { x: 50, y: 80 },
+        ];
+        assert_eq!(closest_pair_brute_force(&points).2, 1.0);
+
+        let deduped = dedup_within(&points, 1);
+        assert_eq!(deduped, vec![points[0], points[1], points[3], points[5]]);
+        assert_eq!(
+            closest_pair_brute_force(&deduped),
+            (points[1], points[3], 5.0)
+        );
+    }
+
+    #[test]
+    fn test_radius_is_chebyshev() {
+        // 2 along both axes is within radius 2, however far it is in Euclidean terms
+        let points = vec![Point { x: 10, y: 10 }, Point { x: 12, y: 12 }];
+        assert_eq!(dedup_within(&points, 2), vec![points[0]]);
+        assert_eq!(dedup_within(&points, 1), points);
+    }
+
+    #[test]
+    fn test_zero_radius_removes_exact_duplicates() {
+        let points = vec![
+            Point { x: 1, y: 1 },
+            Point { x: 1, y: 2 },
+            Point { x: 1, y: 1 },
+            Point { x: 0, y: 0 },
+        ];
+        assert_eq!(
+            dedup_within(&points, 0),
+            vec![points[0], points[1], points[3]]
+        );
+    }
+
+    #[test]
+    fn test_kept_points_are_far_apart() {
+        let points = random_points(5000, 2000, 24);
+        let deduped = dedup_within(&points, 10);
+        assert!(deduped.len() < points.len());
+
+        for (i, a) in deduped.iter().enumerate() {
+            for b in &deduped[i + 1..] {
+                assert!(chebyshev_distance(a, b) > 10);
+            }
+        }
+        // Every dropped point is near a kept one
+        for p in &points {
+            assert!(deduped.iter().any(|k| chebyshev_distance(k, p) <= 10));
+        }
+    }
+
+    #[test]
+    fn test_edges() {
+        assert!(dedup_within(&[], 3).is_empty());
+
+        let corners = vec![
+            Point { x: 0, y: 0 },
+            Point {
+                x: u32::MAX,
+                y: u32::MAX,
+            },
+        ];
+        assert_eq!(dedup_within(&corners, u32::MAX - 1), corners);
+        assert_eq!(dedup_within(&corners, u32::MAX), vec![corners[0]]);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_distinct_tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_duplicates() {
+        let points = vec![
+            Point { x: 10, y: 10 },
+            Point { x: 50, y: 50 },
+            Point { x: 10, y: 10 },
+            Point { x: 50, y: 50 },
+            Point { x: 50, y: 50 },
+            Point { x: 53, y: 54 },
+            Point { x: 0, y: 0 },
+        ];
+        assert_eq!(closest_pair_optimized(&points).2, 0.0);
+
+        let (p1, p2, dist) = closest_pair_distinct(&points);
+        assert_eq!(dist, 5.0);
+        assert!(
+            (p1, p2) == (points[1], points[5]) || (p1, p2) == (points[5], points[1]),
+            "got {p1:?}, {p2:?}"
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force_over_distinct_pairs() {
+        for seed in 0..10 {
+            // A small grid, so most points have duplicates
+            let points = random_points(500, 30, seed);
+            let (p1, p2, dist) = closest_pair_distinct(&points);
+            assert_ne!(p1, p2);
+            assert_eq!(eucid_distance(&p1, &p2), dist);
+
+            let (_, _, expected) = closest_pair_by(&points, |a, b| match eucid_distance(a, b) {
+                0.0 => f32::INFINITY,
+                d => d,
+            });
+            assert_eq!(dist, expected);
+        }
+    }
+
+    #[test]
+    fn test_no_duplicates() {
+        let points = random_points(1000, 1 << 20, 8);
+        assert_eq!(
+            closest_pair_distinct(&points).2,
+            closest_pair_optimized(&points).2
+        );
+    }
+
+    #[test]
+    fn test_signed_zero() {
+        let points = vec![
+            PointF64 { x: -0.0, y: 5.0 },
+            PointF64 { x: 0.0, y: 3.0 },
+            PointF64 { x: 0.0, y: 5.0 },
+        ];
+        assert_eq!(closest_pair_distinct(&points).2, 2.0);
+    }
+
+    #[test]
+    fn test_all_duplicates() {
+        let points = vec![Point { x: 3, y: 3 }; 5];
+        assert_eq!(
+            try_closest_pair_distinct(&points).unwrap_err(),
+            ClosestPairError::NoDistinctPair
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two distinct points to find closest pair")]
+    fn test_all_duplicates_panics() {
+        closest_pair_distinct(&[Point { x: 3, y: 3 }, Point { x: 3, y: 3 }]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_distinct(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_distinct(&[Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_from_flat_tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_pairs() {
+        let coords = [5u32, 1, 100, 100, 8, 5, 0, 50];
+        let (p1, p2, dist) = closest_pair_from_flat(&coords);
+        assert_eq!(dist, 5.0);
+
+        let pair = [<[u32; 2]>::from(p1), p2.into()];
+        assert!(
+            pair == [[5, 1], [8, 5]] || pair == [[8, 5], [5, 1]],
+            "got {pair:?}"
+        );
+    }
+
+    #[test]
+    fn test_matches_points() {
+        let points = random_points(1000, 1 << 20, 14);
+        let coords: Vec<u32> = points.iter().flat_map(|&p| <[u32; 2]>::from(p)).collect();
+        assert_eq!(coords.len(), 2000);
+        assert_eq!(
+            closest_pair_from_flat(&coords),
+            closest_pair_optimized(&points)
+        );
+    }
+
+    #[test]
+    fn test_signed_coordinates() {
+        let (_, _, dist) = closest_pair_from_flat(&[-3i32, -4, 0, 0, 10, 10]);
+        assert_eq!(dist, 5.0);
+    }
+
+    #[test]
+    fn test_odd_length() {
+        assert_eq!(
+            try_closest_pair_from_flat(&[1u32, 2, 3, 4, 5]).unwrap_err(),
+            ClosestPairError::OddCoordinateCount(5)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Flat coordinates must come in x, y pairs, got 1 values")]
+    fn test_odd_length_panics() {
+        closest_pair_from_flat(&[1u32]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_from_flat::<u32>(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_from_flat(&[1u32, 2]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_3d_tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_cube_corners() {
+        let mut points = Vec::new();
+        for x in 0..2 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    points.push(Point3 { x, y, z });
+                }
+            }
+        }
+
+        let (_, _, distance) = closest_pair_brute_force_3d(&points);
+        assert_eq!(distance, 1.0);
+    }
+
+    #[test]
+    fn test_z_separates_pair() {
+        // (0, 0) and (1, 0) are closest in the xy-plane, but far apart along z
+        let points: Vec<Point3> = vec![
+            Point3 { x: 0, y: 0, z: 0 },
+            Point3 { x: 1, y: 0, z: 10 },
+            Point3 { x: 4, y: 0, z: 0 },
+        ];
+
+        let (p1, p2, distance) = closest_pair_brute_force_3d(&points);
+        assert_eq!((p1, p2), (points[0], points[2]));
+        assert_eq!(distance, 4.0);
+
+        let flat: Vec<Point> = points.iter().map(|p| Point { x: p.x, y: p.y }).collect();
+        assert_eq!(closest_pair_brute_force(&flat).2, 1.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: Vec<Point3> = Vec::new();
+        assert_eq!(
+            try_closest_pair_brute_force_3d(&empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_n_tests {
+    use super::*;
+
+    #[test]
+    fn test_2d_matches_point() {
+        let points = random_points(200, 10_000, 8);
+        let points_n: Vec<PointN<2>> = points.iter().map(|&p| p.into()).collect();
+
+        let (p1, p2, distance) = closest_pair_brute_force_n(&points_n);
+        let expected = closest_pair_brute_force(&points);
+        assert_eq!((p1.into(), p2.into(), distance), expected);
+    }
+
+    #[test]
+    fn test_3d_unit_cube_corners() {
+        let mut points = Vec::new();
+        for x in 0..2 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    points.push(PointN { coords: [x, y, z] });
+                }
+            }
+        }
+        assert_eq!(closest_pair_brute_force_n(&points).2, 1.0);
+    }
+
+    #[test]
+    fn test_4d_last_axis_separates_pair() {
+        // The first two points are closest in 3D, but far apart along the fourth axis
+        let points = vec![
+            PointN {
+                coords: [0, 0, 0, 0],
+            },
+            PointN {
+                coords: [1, 0, 0, 10],
+            },
+            PointN {
+                coords: [2, 2, 2, 2],
+            },
+        ];
+        let (p1, p2, distance) = closest_pair_brute_force_n(&points);
+        assert_eq!((p1, p2), (points[0], points[2]));
+        assert_eq!(distance, 4.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: Vec<PointN<4>> = Vec::new();
+        assert_eq!(
+            try_closest_pair_brute_force_n(&empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_geo_tests {
+    use super::*;
+
+    fn city(lat: f32, lon: f32) -> GeoPoint {
+        GeoPoint { lat, lon }
+    }
+
+    #[test]
+    fn test_world_cities() {
+        let cities = vec![
+            city(40.7128, -74.0060),  // New York
+            city(48.8566, 2.3522),    // Paris
+            city(35.6762, 139.6503),  // Tokyo
+            city(51.5074, -0.1278),   // London
+            city(-33.8688, 151.2093), // Sydney
+            city(50.8503, 4.3517),    // Brussels
+        ];
+
+        // Paris and Brussels are about 264 km apart, London and Paris about 344 km
+        let (p1, p2, distance) = closest_pair_geo(&cities);
+        assert_eq!((p1, p2), (cities[1], cities[5]));
+        assert!(approx_eq(distance, 264.0, 2.0));
+    }
+
+    #[test]
+    fn test_antimeridian() {
+        // Suva and Apia are about 1150 km apart across the antimeridian, but almost 360
+        // degrees of longitude apart as numbers
+        let points = vec![
+            city(-18.1416, 178.4419), // Suva
+            city(-13.8333, -171.75),  // Apia
+            city(-36.8485, 174.7633), // Auckland
+            city(21.3069, -157.8583), // Honolulu
+        ];
+        let (p1, p2, distance) = closest_pair_geo(&points);
+        assert_eq!((p1, p2), (points[0], points[1]));
+        assert!(approx_eq(distance, 1150.0, 50.0));
+    }
+
+    #[test]
+    fn test_near_pole() {
+        // Near the pole, degrees of longitude are short: the pair 90 degrees of
+        // longitude apart is closer than the pair one degree of latitude apart
+        let points = vec![
+            city(89.9, 0.0),
+            city(89.9, 90.0),
+            city(88.0, 0.0),
+            city(87.0, 0.0),
+        ];
+        let (p1, p2, distance) = closest_pair_geo(&points);
+        assert_eq!((p1, p2), (points[0], points[1]));
+        assert!(distance < 20.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_geo(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_geo(&[city(0.0, 0.0)]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod closest_pair_grid_tests {
+    use super::*;
+
+    #[test]
+    fn test_small_sets() {
+        let points = vec![Point { x: 5, y: 10 }, Point { x: 8, y: 14 }];
+        assert_eq!(closest_pair_grid(&points).2, 5.0);
+
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 0, y: 4 },
+        ];
+        assert_eq!(closest_pair_grid(&points).2, 3.0);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..8 {
+            let points = random_points(3000, 1 << 20, seed);
+            let (p1, p2, dist) = closest_pair_grid(&points);
+            let (_, _, bf_dist) = closest_pair_brute_force(&points);
+
+            assert_eq!(dist, bf_dist);
+            assert_eq!(eucid_distance(&p1, &p2), dist);
+        }
+    }
+
+    #[test]
+    fn test_clustered_points() {
+        // A tight cluster the sample is likely to miss, far from the other points
+        let mut points = random_points(2000, 1 << 30, 21);
+        points.extend((0..50).map(|i| Point {
+            x: 7 + i % 7,
+            y: 9 + i / 7,
+        }));
+
+        assert_eq!(closest_pair_grid(&points).2, 1.0);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut points = random_points(500, 1000, 3);
+        points.push(points[123]);
+
+        let (p1, p2, dist) = closest_pair_grid(&points);
+        assert_eq!(p1, p2);
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_signed_and_float() {
+        let points: Vec<Pointi> = random_points(1000, 20_000, 9)
+            .into_iter()
+            .map(|p| Pointi {
+                x: p.x as i32 - 10_000,
+                y: p.y as i32 - 10_000,
+            })
+            .collect();
+        assert_eq!(
+            closest_pair_grid(&points).2,
+            closest_pair_brute_force(&points).2
+        );
+
+        let points = vec![
+            PointF64 { x: -0.5, y: 0.25 },
+            PointF64 { x: 3.0, y: 3.0 },
+            PointF64 { x: -0.75, y: 0.25 },
+        ];
+        assert_eq!(closest_pair_grid(&points).2, 0.25);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod pairs_within_radius_tests {
+    use super::*;
+
+    fn lattice(side: u32) -> Vec<Point> {
+        let mut points = Vec::new();
+        for x in 0..side {
+            for y in 0..side {
+                points.push(Point { x, y });
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_unit_distance_lattice() {
+        // A 10x10 lattice has 9 horizontal and 9 vertical unit edges per row and column
+        let pairs = pairs_within_radius(&lattice(10), 1.0);
+        assert_eq!(pairs.len(), 2 * 10 * 9);
+        assert!(pairs.iter().all(|&(_, _, d)| d == 1.0));
+    }
+
+    #[test]
+    fn test_diagonals_lattice() {
+        // Just above √2 picks up both diagonals of every unit square as well
+        let pairs = pairs_within_radius(&lattice(10), 1.5);
+        assert_eq!(pairs.len(), 2 * 10 * 9 + 2 * 9 * 9);
+    }
+
+    #[test]
+    fn test_no_swapped_duplicates() {
+        let points = lattice(6);
+        let pairs = pairs_within_radius(&points, 2.0);
+
+        let index = |p: &Point| points.iter().position(|q| q == p).unwrap();
+        let mut seen = std::collections::HashSet::new();
+        for (p1, p2, _) in &pairs {
+            let (i, j) = (index(p1), index(p2));
+            assert!(i < j);
+            assert!(seen.insert((i, j)));
+        }
+    }
+
+    #[test]
+    fn test_zero_radius() {
+        let points: Vec<Point> = vec![
+            Point { x: 1, y: 1 },
+            Point { x: 2, y: 2 },
+            Point { x: 1, y: 1 },
+            Point { x: 1, y: 2 },
+            Point { x: 2, y: 2 },
+            Point { x: 1, y: 1 },
+        ];
+
+        let pairs = pairs_within_radius(&points, 0.0);
+        // 3 pairs among the copies of (1, 1), 1 among the copies of (2, 2)
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs.iter().all(|&(p1, p2, d)| p1 == p2 && d == 0.0));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let points = random_points(1500, 10_000, 13);
+        let r = 150.0;
+
+        let mut expected = Vec::new();
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                let d = eucid_distance(&points[i], &points[j]);
+                if d <= r {
+                    expected.push((points[i], points[j], d));
+                }
+            }
+        }
+
+        assert_eq!(pairs_within_radius(&points, r), expected);
+    }
+
+    #[test]
+    fn test_radius_far_below_coordinate_precision() {
+        // Near u64::MAX neighbouring integers round to the same f64, so a radius of
+        // 1 is far below what the cell keys can tell apart
+        let m = u64::MAX;
+        let points = vec![
+            PointU64 { x: m, y: m },
+            PointU64 { x: m - 1, y: m },
+            PointU64 { x: m - 3, y: m },
+            PointU64 { x: m - 4, y: m - 1 },
+        ];
+
+        assert_eq!(
+            pairs_within_radius(&points, 1.0),
+            vec![(points[0], points[1], 1.0)]
+        );
+        assert_eq!(pairs_within_radius(&points, 1.5).len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_radius() {
+        let points = lattice(3);
+        assert!(pairs_within_radius(&points, -1.0).is_empty());
+        assert!(pairs_within_radius(&points, f32::NAN).is_empty());
+        assert!(pairs_within_radius::<u32>(&[], 1.0).is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod connected_components_tests {
+    use super::*;
+
+    #[test]
+    fn test_two_blobs() {
+        // Two blobs of points 2 apart, far from each other, interleaved in the input
+        let mut points = Vec::new();
+        for i in 0..10u32 {
+            points.push(Point { x: 2 * i, y: 100 });
+            points.push(Point {
+                x: 10_000 + 2 * (i % 5),
+                y: 2 * (i / 5),
+            });
+        }
+
+        let clusters = connected_components(&points, 2.0);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(
+            clusters[0],
+            points.iter().copied().step_by(2).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            clusters[1],
+            points
+                .iter()
+                .copied()
+                .skip(1)
+                .step_by(2)
+                .collect::<Vec<_>>()
+        );
+
+        // Just below the spacing nothing is linked
+        assert_eq!(connected_components(&points, 1.9).len(), points.len());
+    }
+
+    #[test]
+    fn test_chain() {
+        // Each point is within r of the next only, yet they all join up
+        let points: Vec<Point> = (0..20).map(|i| Point { x: 3 * i, y: 4 * i }).collect();
+        assert_eq!(connected_components(&points, 5.0), vec![points.clone()]);
+    }
+
+    #[test]
+    fn test_isolated_points_are_singletons() {
+        let points = vec![
+            Pointi { x: -100, y: 0 },
+            Pointi { x: 0, y: 0 },
+            Pointi { x: 1, y: 1 },
+            Pointi { x: 100, y: 0 },
+        ];
+        assert_eq!(
+            connected_components(&points, 2.0),
+            vec![vec![points[0]], vec![points[1], points[2]], vec![points[3]]]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_and_edge_radii() {
+        let points = vec![
+            Point { x: 5, y: 5 },
+            Point { x: 6, y: 5 },
+            Point { x: 5, y: 5 },
+        ];
+        assert_eq!(
+            connected_components(&points, 0.0),
+            vec![vec![points[0], points[2]], vec![points[1]]]
+        );
+        assert_eq!(connected_components(&points, -1.0).len(), 3);
+        assert_eq!(connected_components(&points, f32::NAN).len(), 3);
+
+        let empty: Vec<Point> = Vec::new();
+        assert!(connected_components(&empty, 1.0).is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod exists_pair_closer_than_tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_under_threshold() {
+        let mut points: Vec<Point> = (0..20)
+            .flat_map(|x| {
+                (0..20).map(move |y| Point {
+                    x: x * 10,
+                    y: y * 10,
+                })
+            })
+            .collect();
+        points.push(Point { x: 93, y: 47 });
+
+        let (p1, p2, d) = exists_pair_closer_than(&points, 5.0).unwrap();
+        assert!(d < 5.0);
+        assert_eq!(eucid_distance(&p1, &p2), d);
+        assert!(p2 == Point { x: 93, y: 47 } || p1 == Point { x: 93, y: 47 });
+    }
+
+    #[test]
+    fn test_all_pairs_above_threshold() {
+        let points: Vec<Point> = (0..20)
+            .flat_map(|x| {
+                (0..20).map(move |y| Point {
+                    x: x * 10,
+                    y: y * 10,
+                })
+            })
+            .collect();
+        assert_eq!(exists_pair_closer_than(&points, 9.5), None);
+        assert!(exists_pair_closer_than(&points, 10.5).is_some());
+    }
+
+    #[test]
+    fn test_threshold_is_strict() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+        assert_eq!(exists_pair_closer_than(&points, 5.0), None);
+        assert_eq!(
+            exists_pair_closer_than(&points, 5.01),
+            Some((points[0], points[1], 5.0))
+        );
+    }
+
+    #[test]
+    fn test_matches_closest_distance() {
+        for seed in 0..200 {
+            let points = random_points(500, 10_000, seed);
+            let (_, _, min) = closest_pair_brute_force(&points);
+            // The closest pair's f32 distance is not strictly closer than itself
+            assert_eq!(exists_pair_closer_than(&points, min), None);
+
+            let above = f32::from_bits(min.to_bits() + 1);
+            let (_, _, d) = exists_pair_closer_than(&points, above).unwrap();
+            assert_eq!(d, min);
+        }
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        let points = vec![Point { x: 1, y: 1 }, Point { x: 1, y: 1 }];
+        assert!(exists_pair_closer_than(&points, 0.0).is_none());
+        assert!(exists_pair_closer_than(&points, -1.0).is_none());
+        assert!(exists_pair_closer_than(&points, f32::NAN).is_none());
+        assert!(exists_pair_closer_than::<u32>(&[], 1.0).is_none());
+        assert!(exists_pair_closer_than(&points[..1], 1.0).is_none());
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_weighted_tests {
+    use super::*;
+
+    fn wp(x: u32, y: u32, w: f32) -> WeightedPoint {
+        WeightedPoint { x, y, w }
+    }
+
+    #[test]
+    fn test_product_prefers_heavy_pairs() {
+        let points = vec![wp(0, 0, 1.0), wp(1, 0, 1.0), wp(10, 0, 4.0), wp(13, 4, 4.0)];
+        // (0,0)-(1,0) is the closest pair, but (10,0)-(13,4) scores 5 / 16
+        let (p1, p2, dist) = closest_pair_weighted(&points, WeightMode::Product);
+        assert_eq!((p1, p2), (points[2], points[3]));
+        assert_eq!(dist, 0.3125);
+    }
+
+    #[test]
+    fn test_surface_gap() {
+        let points = vec![
+            wp(0, 0, 1.0),
+            wp(3, 0, 1.0),
+            wp(50, 0, 20.0),
+            wp(95, 0, 20.0),
+        ];
+        // Gaps are 3 - 2 = 1 and 45 - 40 = 5
+        let (p1, p2, dist) = closest_pair_weighted(&points, WeightMode::Surface);
+        assert_eq!((p1, p2), (points[0], points[1]));
+        assert_eq!(dist, 1.0);
+    }
+
+    #[test]
+    fn test_surface_deepest_overlap_wins() {
+        let points = vec![
+            wp(0, 0, 3.0),
+            wp(4, 0, 3.0),
+            wp(100, 0, 10.0),
+            wp(110, 0, 10.0),
+            wp(200, 0, 0.0),
+            wp(200, 1, 0.0),
+        ];
+        // Overlaps of 2 and 10 both beat the gap of 1 between the weightless points
+        let (p1, p2, dist) = closest_pair_weighted(&points, WeightMode::Surface);
+        assert_eq!((p1, p2), (points[2], points[3]));
+        assert_eq!(dist, -10.0);
+    }
+
+    #[test]
+    fn test_nan_scores_are_skipped() {
+        // Coincident points with no weight score 0 / 0
+        let points = vec![wp(5, 5, 0.0), wp(5, 5, 0.0), wp(8, 9, 1.0), wp(8, 10, 2.0)];
+        let (p1, p2, dist) = closest_pair_weighted(&points, WeightMode::Product);
+        assert_eq!((p1, p2), (points[2], points[3]));
+        assert_eq!(dist, 0.5);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_weighted(&[], WeightMode::Surface).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_weighted(&[wp(1, 1, 1.0)], WeightMode::Surface).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+        let points = vec![wp(5, 5, 0.0), wp(5, 5, 0.0)];
+        assert_eq!(
+            try_closest_pair_weighted(&points, WeightMode::Product).unwrap_err(),
+            ClosestPairError::InfiniteDistance
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_optimized_sq_tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_f32_distance() {
+        for seed in 0..20 {
+            let points = random_points(500, u32::MAX, seed);
+            let (p1, p2, dist_sq) = closest_pair_optimized_sq(&points);
+            let (_, _, dist) = closest_pair_optimized(&points);
+            assert_eq!(eucid_distance_sq(&p1, &p2), dist_sq);
+            assert!(approx_eq((dist_sq as f64).sqrt() as f32, dist, dist * 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_exact_at_full_range() {
+        // 2^64 - 2^33 + 1 needs more than f64's 53 bits of mantissa
+        let points = vec![Point { x: 0, y: 7 }, Point { x: u32::MAX, y: 7 }];
+        let (_, _, dist_sq) = closest_pair_optimized_sq(&points);
+        assert_eq!(dist_sq, (u32::MAX as u128).pow(2));
+        assert_ne!(dist_sq as f64 as u128, dist_sq);
+    }
+
+    #[test]
+    fn test_tie_detection() {
+        // (0, 0) - (3, 4) and (20, 0) - (25, 0) are both 5 apart
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 4 },
+            Point { x: 20, y: 0 },
+            Point { x: 25, y: 0 },
+        ];
+        let (_, _, dist_sq) = closest_pair_optimized_sq(&points);
+        let ties = k_closest_pairs(&points, 6)
+            .into_iter()
+            .filter(|(p1, p2, _)| eucid_distance_sq(p1, p2) == dist_sq)
+            .count();
+        assert_eq!(ties, 2);
+    }
+
+    #[test]
+    fn test_float_coordinates() {
+        let points = vec![
+            PointF64 { x: 0.0, y: 0.0 },
+            PointF64 { x: 0.5, y: 0.0 },
+            PointF64 { x: 3.0, y: 3.0 },
+        ];
+        let (_, _, dist_sq) = closest_pair_optimized_sq(&points);
+        assert_eq!(dist_sq, 0.25);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_optimized_sq(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_tests {
+    use super::*;
+
+    fn rec_calls() -> usize {
+        REC_CALLS.with(|calls| calls.get())
+    }
+
+    #[test]
+    fn test_agrees_with_brute_force_around_threshold() {
+        let sizes = (2..16).chain(BRUTE_FORCE_THRESHOLD - 8..BRUTE_FORCE_THRESHOLD + 8);
+        for n in sizes {
+            for seed in 0..5 {
+                let points = random_points(n, 1000, seed);
+                let (p1, p2, dist) = closest_pair(&points);
+                let (_, _, bf_dist) = closest_pair_brute_force(&points);
+                assert_eq!(dist, bf_dist, "n = {n}, seed = {seed}");
+                assert_eq!(eucid_distance(&p1, &p2), dist);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let small = random_points(BRUTE_FORCE_THRESHOLD, 1000, 1);
+        let before = rec_calls();
+        closest_pair(&small);
+        assert_eq!(rec_calls(), before);
+
+        let large = random_points(BRUTE_FORCE_THRESHOLD + 1, 1000, 1);
+        closest_pair(&large);
+        assert!(rec_calls() > before);
+    }
+
+    #[test]
+    fn test_infinite_distance_either_side_of_threshold() {
+        // Points 1e200 apart, so every squared distance overflows a f64
+        for n in [BRUTE_FORCE_THRESHOLD, BRUTE_FORCE_THRESHOLD + 1] {
+            let points: Vec<PointF64> = (0..n)
+                .map(|i| PointF64 {
+                    x: i as f64 * 1e200,
+                    y: 0.0,
+                })
+                .collect();
+            assert_eq!(
+                try_closest_pair(&points).unwrap_err(),
+                ClosestPairError::InfiniteDistance,
+                "n = {n}"
+            );
+            let f64_result = std::panic::catch_unwind(|| closest_pair_f64(&points));
+            assert!(f64_result.is_err(), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair(&[Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod f64_distance_tests {
+    use super::*;
+
+    fn pair(dx: u32) -> Vec<Point> {
+        vec![Point { x: 7, y: 0 }, Point { x: 7 + dx, y: 0 }]
+    }
+
+    #[test]
+    fn test_f64_separates_distances_f32_merges() {
+        // 2^30 and 2^30 + 1 are the same f32
+        let (near, far) = (pair(1 << 30), pair((1 << 30) + 1));
+
+        assert_eq!(
+            closest_pair_optimized(&near).2,
+            closest_pair_optimized(&far).2
+        );
+        assert_eq!(closest_pair_optimized_f64(&near).2, 1073741824.0);
+        assert_eq!(closest_pair_optimized_f64(&far).2, 1073741825.0);
+
+        assert_eq!(
+            closest_pair_brute_force(&near).2,
+            closest_pair_brute_force(&far).2
+        );
+        assert_eq!(closest_pair_brute_force_f64(&near).2, 1073741824.0);
+        assert_eq!(closest_pair_brute_force_f64(&far).2, 1073741825.0);
+
+        assert_eq!(closest_pair_f64(&far).2, 1073741825.0);
+        assert_eq!(closest_pair_bit_shift_f64(far, 31).2, 1073741825.0);
+    }
+
+    #[test]
+    fn test_f64_agrees_with_f32() {
+        let points = random_points(1000, u32::MAX, 3);
+        let (p1, p2, dist) = closest_pair_optimized_f64(&points);
+        assert_eq!(dist as f32, closest_pair_optimized(&points).2);
+        assert_eq!(dist, eucid_distance_f64(&p1, &p2));
+        assert_eq!(dist, closest_pair_f64(&points).2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_single_point_panics() {
+        closest_pair_f64(&[Point { x: 1, y: 1 }]);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_brute_force_iter_tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_iterator() {
+        let n = 200u32;
+        let points = (0..n).map(|i| Point {
+            x: (i * 7919) % 1000,
+            y: (i * 104729) % 997,
+        });
+        let collected: Vec<Point> = points.clone().collect();
+
+        let (p1, p2, dist) = closest_pair_brute_force_iter(points);
+        assert_eq!((p1, p2, dist), closest_pair_brute_force(&collected));
+    }
+
+    #[test]
+    fn test_float_points() {
+        let points = (0..5).map(|i| PointF64 {
+            x: i as f64 * 1.5,
+            y: (i % 2) as f64,
+        });
+        let (_, _, dist) = closest_pair_brute_force_iter(points);
+        assert_eq!(dist, (3.25f64).sqrt() as f32);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            try_closest_pair_brute_force_iter(core::iter::empty::<Point>()).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_brute_force_iter(core::iter::once(Point { x: 3, y: 3 })).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod convex_hull_tests {
+    use super::*;
+
+    fn is_counter_clockwise(hull: &[Point]) -> bool {
+        let h = hull.len();
+        (0..h).all(|i| {
+            let (a, b, c) = (hull[i], hull[(i + 1) % h], hull[(i + 2) % h]);
+            u32::cross_sign(a, b, b, c) == Ordering::Greater
+        })
+    }
+
+    #[test]
+    fn test_square_with_interior_point() {
+        let points = vec![
+            Point { x: 10, y: 10 },
+            Point { x: 0, y: 10 },
+            Point { x: 5, y: 5 },
+            Point { x: 10, y: 0 },
+            Point { x: 0, y: 0 },
+        ];
+        let hull = convex_hull(&points);
+        assert_eq!(
+            hull,
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 10, y: 0 },
+                Point { x: 10, y: 10 },
+                Point { x: 0, y: 10 },
+            ]
+        );
+        assert!(is_counter_clockwise(&hull));
+    }
+
+    #[test]
+    fn test_drops_points_on_edges() {
+        let mut points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 6, y: 0 },
+            Point { x: 0, y: 6 },
+        ];
+        points.extend((1..6).map(|i| Point { x: i, y: 6 - i }));
+        points.extend((1..6).map(|i| Point { x: i, y: 0 }));
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 6, y: 0 },
+                Point { x: 0, y: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_collinear() {
+        let points: Vec<Point> = (0..10).rev().map(|i| Point { x: 3 * i, y: i }).collect();
+        assert_eq!(
+            convex_hull(&points),
+            vec![Point { x: 0, y: 0 }, Point { x: 27, y: 9 }]
+        );
+
+        let vertical: Vec<Point> = (0..5).map(|i| Point { x: 2, y: i }).collect();
+        assert_eq!(
+            convex_hull(&vertical),
+            vec![Point { x: 2, y: 0 }, Point { x: 2, y: 4 }]
+        );
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        let empty: Vec<Point> = Vec::new();
+        assert!(convex_hull(&empty).is_empty());
+
+        let p = Point { x: 3, y: 3 };
+        let q = Point { x: 1, y: 7 };
+        assert_eq!(convex_hull(&[p]), vec![p]);
+        assert_eq!(convex_hull(&[p, p, p]), vec![p]);
+        assert_eq!(convex_hull(&[p, q, p]), vec![q, p]);
+    }
+
+    #[test]
+    fn test_random_points() {
+        let points = random_points(2000, 1000, 11);
+        let hull = convex_hull(&points);
+        assert!(is_counter_clockwise(&hull));
+
+        // Every point is on or to the left of every edge
+        let h = hull.len();
+        for i in 0..h {
+            let (a, b) = (hull[i], hull[(i + 1) % h]);
+            assert!(points
+                .iter()
+                .all(|&p| u32::cross_sign(a, b, a, p) != Ordering::Less));
+        }
+    }
 }
 
 #[cfg(test)]
-mod closest_pair_optimized_tests {
+mod closest_pair_optimized_with_cutoff_tests {
     use super::*;
-    use std::f32;
 
     #[test]
-    fn test_small_set() {
-        // Basic test with known distances
+    fn test_cutoffs_agree() {
+        for seed in 0..5 {
+            let points = random_points(3000, 1 << 16, seed);
+            let (_, _, expected) = closest_pair_optimized(&points);
+            for cutoff in [3, 4, 5, 8, 16, 33, 64, 1000, 5000] {
+                let (p1, p2, dist) = closest_pair_optimized_with_cutoff(&points, cutoff);
+                assert_eq!(dist, expected, "seed = {seed}, cutoff = {cutoff}");
+                assert_eq!(eucid_distance(&p1, &p2), dist);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cutoff_reduces_recursion() {
+        let calls = |cutoff| {
+            let points = random_points(1024, 1000, 4);
+            let before = REC_CALLS.with(|calls| calls.get());
+            closest_pair_optimized_with_cutoff(&points, cutoff);
+            REC_CALLS.with(|calls| calls.get()) - before
+        };
+        // Halving 1024 down to 16 takes 2 * 64 - 1 calls
+        assert_eq!(calls(16), 127);
+        assert_eq!(calls(1024), 1);
+        assert!(calls(3) > calls(16));
+    }
+
+    #[test]
+    #[should_panic(expected = "Brute force cutoff must be at least 3, got 2")]
+    fn test_cutoff_too_small() {
+        closest_pair_optimized_with_cutoff(&[Point { x: 0, y: 0 }, Point { x: 1, y: 1 }], 2);
+    }
+}
+
+#[cfg(test)]
+mod mutual_nearest_neighbors_tests {
+    use super::*;
+
+    #[test]
+    fn test_single_mutual_pair_among_near_misses() {
+        // A chain with growing gaps: every point's nearest neighbor is the one
+        // before it, except (0, 0), whose nearest is (1, 0)
+        let points = vec![
+            Point { x: 10, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 24, y: 0 },
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 45, y: 0 },
+        ];
+        assert_eq!(
+            mutual_nearest_neighbors(&points),
+            vec![(points[1], points[3], 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_ties() {
+        // The center is equally close to all four arms, and each arm's only
+        // nearest neighbor is the center
+        let center = Point { x: 10, y: 10 };
+        let points = vec![
+            center,
+            Point { x: 13, y: 10 },
+            Point { x: 10, y: 13 },
+            Point { x: 7, y: 10 },
+            Point { x: 10, y: 7 },
+        ];
+        let pairs = mutual_nearest_neighbors(&points);
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs
+            .iter()
+            .all(|&(p1, _, dist)| p1 == center && dist == 3.0));
+
+        // Coincident points are all nearest neighbors of each other
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 0 },
+        ];
+        assert_eq!(mutual_nearest_neighbors(&points).len(), 3);
+    }
+
+    #[test]
+    fn test_matches_definition_on_random_points() {
+        let points = random_points(300, 200, 6);
+        let pairs = mutual_nearest_neighbors(&points);
+
+        let nearest = |p: &Point| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(_, q)| !core::ptr::eq(p, q))
+                .map(|(_, q)| eucid_distance_sq(p, q))
+                .min()
+                .unwrap()
+        };
+        let mut expected = Vec::new();
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                let d = eucid_distance_sq(&points[i], &points[j]);
+                if d == nearest(&points[i]) && d == nearest(&points[j]) {
+                    expected.push((points[i], points[j], eucid_distance(&points[i], &points[j])));
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_too_few_points() {
+        let empty: Vec<Point> = Vec::new();
+        assert!(mutual_nearest_neighbors(&empty).is_empty());
+        assert!(mutual_nearest_neighbors(&[Point { x: 1, y: 1 }]).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod nearest_neighbor_tests {
+    use super::*;
+
+    fn cloud() -> Vec<Point> {
+        vec![
+            Point { x: 10, y: 10 },
+            Point { x: 20, y: 12 },
+            Point { x: 15, y: 20 },
+            Point { x: 12, y: 15 },
+        ]
+    }
+
+    #[test]
+    fn test_query_inside() {
+        let points = cloud();
+        let (p, dist) = nearest_neighbor(&points, Point { x: 13, y: 13 });
+        assert_eq!((p, dist), (points[3], (5.0f64).sqrt() as f32));
+    }
+
+    #[test]
+    fn test_query_outside() {
+        let points = cloud();
+        let (p, dist) = nearest_neighbor(&points, Point { x: 1000, y: 0 });
+        assert_eq!(p, points[1]);
+        assert_eq!(dist, eucid_distance(&points[1], &Point { x: 1000, y: 0 }));
+
+        let (p, dist) = nearest_neighbor(&points, Point { x: 0, y: 0 });
+        assert_eq!((p, dist), (points[0], 200.0f64.sqrt() as f32));
+    }
+
+    #[test]
+    fn test_query_on_top() {
+        let points = cloud();
+        for &p in &points {
+            assert_eq!(nearest_neighbor(&points, p), (p, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_ties_return_first() {
+        let points = vec![Point { x: 0, y: 2 }, Point { x: 2, y: 0 }];
+        assert_eq!(nearest_neighbor(&points, Point { x: 0, y: 0 }).0, points[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find closest pair with empty vector")]
+    fn test_empty_panics() {
+        let points: Vec<Point> = Vec::new();
+        nearest_neighbor(&points, Point { x: 0, y: 0 });
+    }
+}
+
+#[cfg(test)]
+mod nearest_along_path_tests {
+    use super::*;
+
+    #[test]
+    fn test_straight_line() {
+        // Three posts above a horizontal path, which passes each of them in turn
+        let points = vec![
+            Point { x: 0, y: 3 },
+            Point { x: 10, y: 4 },
+            Point { x: 20, y: 3 },
+        ];
+        let path: Vec<Point> = (0..=5).map(|i| Point { x: 4 * i, y: 0 }).collect();
+
+        let expected = vec![
+            (points[0], 3.0),
+            (points[0], 5.0),
+            (points[1], (20.0f64).sqrt() as f32),
+            (points[1], (20.0f64).sqrt() as f32),
+            (points[2], 5.0),
+            (points[2], 3.0),
+        ];
+        assert_eq!(nearest_along_path(&points, &path), expected);
+    }
+
+    #[test]
+    fn test_matches_nearest_neighbor() {
+        let points = random_points(1000, 10_000, 21);
+        let path: Vec<Point> = (0..200)
+            .map(|i| Point {
+                x: 50 * i,
+                y: 25 * i,
+            })
+            .collect();
+
+        let found = nearest_along_path(&points, &path);
+        assert_eq!(found.len(), path.len());
+        for (&query, &(p, dist)) in path.iter().zip(&found) {
+            assert_eq!(dist, nearest_neighbor(&points, query).1);
+            assert_eq!(eucid_distance(&p, &query), dist);
+        }
+    }
+
+    #[test]
+    fn test_empty_path() {
+        let points = vec![Point { x: 1, y: 1 }];
+        assert!(nearest_along_path(&points, &[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find closest pair with empty vector")]
+    fn test_empty_points_panics() {
+        let points: Vec<Point> = Vec::new();
+        nearest_along_path(&points, &[Point { x: 0, y: 0 }]);
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_bit_shift_auto_tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_bits() {
+        assert_eq!(auto_bits(0), 1);
+        assert_eq!(auto_bits(1), 1);
+        assert_eq!(auto_bits(255), 8);
+        assert_eq!(auto_bits(256), 9);
+        assert_eq!(auto_bits(u32::MAX), 32);
+    }
+
+    #[test]
+    fn test_wide_coordinates_match_brute_force() {
+        let points = vec![
+            Point { x: 3, y: 1 },
+            Point { x: 70_000, y: 12 },
+            Point { x: 1 << 30, y: 5 },
+            Point { x: 70_004, y: 15 },
+            Point { x: 900, y: 1 << 31 },
+            Point { x: 250, y: 250 },
+        ];
+        let (p1, p2, dist) = closest_pair_bit_shift_auto(&points);
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        assert_eq!(dist, bf_dist);
+        assert_eq!(dist, 5.0);
+        assert_eq!(eucid_distance(&p1, &p2), dist);
+    }
+
+    #[test]
+    fn test_fixed_bits_would_truncate() {
+        let points = vec![
+            Point { x: 300, y: 0 },
+            Point { x: 44, y: 0 },
+            Point { x: 1000, y: 0 },
+        ];
+        // With 8 bits, 300 would be packed as 300 - 256 = 44, on top of (44, 0)
+        assert_eq!(pack_numbers(300, 0, 8), pack_numbers(44, 0, 8));
+        assert_eq!(
+            try_closest_pair_bit_shift(points.clone(), 8).unwrap_err(),
+            ClosestPairError::CoordinateOutOfRange {
+                value: 300,
+                bits: 8
+            }
+        );
+
+        let (_, _, dist) = closest_pair_bit_shift_auto(&points);
+        assert_eq!(dist, 256.0);
+    }
+
+    #[test]
+    fn test_all_zero() {
+        let points = vec![Point { x: 0, y: 0 }; 3];
+        assert_eq!(closest_pair_bit_shift_auto(&points).2, 0.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_bit_shift_auto(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptest_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Every algorithm takes the square root of an exact squared distance and
+    // rounds it to f32, so for integer points the results should match exactly.
+    // The tolerance only leaves room for float coordinates, where the squared
+    // distance itself is rounded and the order of operations can differ, and is
+    // kept at a few f32 ulps so a real miss can't hide behind it.
+    const TOLERANCE: f32 = 4.0 * f32::EPSILON;
+
+    fn close(a: f32, b: f32) -> bool {
+        approx_eq(a, b, TOLERANCE * a.abs().max(b.abs()).max(1.0))
+    }
+
+    /// Between 2 and `max_len` points with both coordinates in `0..max_coord`.
+    fn points(max_coord: u32, max_len: usize) -> impl Strategy<Value = Vec<Point>> {
+        prop::collection::vec((0..max_coord, 0..max_coord), 2..=max_len)
+            .prop_map(|coords| coords.into_iter().map(|(x, y)| Point { x, y }).collect())
+    }
+
+    /// A bit count together with points whose coordinates fit in it.
+    fn packable_points() -> impl Strategy<Value = (u8, Vec<Point>)> {
+        (1u8..=16).prop_flat_map(|bits| (Just(bits), points(1 << bits, 48)))
+    }
+
+    proptest! {
+        #[test]
+        fn brute_force_reports_its_pair(points in points(1000, 64)) {
+            let (p1, p2, dist) = closest_pair_brute_force(&points);
+            prop_assert_eq!(eucid_distance(&p1, &p2), dist);
+        }
+
+        #[test]
+        fn optimized_matches_brute_force(points in points(1000, 200)) {
+            let (p1, p2, dist) = closest_pair_optimized(&points);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            prop_assert!(close(dist, expected), "optimized = {}, brute force = {}", dist, expected);
+            prop_assert_eq!(eucid_distance(&p1, &p2), dist);
+        }
+
+        // Spans the whole u32 range, where squared distances need more than 64 bits
+        #[test]
+        fn optimized_matches_brute_force_wide(points in points(u32::MAX, 200)) {
+            let (_, _, dist) = closest_pair_optimized(&points);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            prop_assert!(close(dist, expected), "optimized = {}, brute force = {}", dist, expected);
+        }
+
+        #[test]
+        fn optimized_matches_brute_force_f64(
+            coords in prop::collection::vec((-1e6..1e6f64, -1e6..1e6f64), 2..=200)
+        ) {
+            let points: Vec<PointF64> = coords.into_iter().map(|(x, y)| PointF64 { x, y }).collect();
+            let (_, _, dist) = closest_pair_optimized(&points);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            prop_assert!(close(dist, expected), "optimized = {}, brute force = {}", dist, expected);
+        }
+
+        // The bit shift algorithm only compares each packed point with the next
+        // `bits` ones, so it is a heuristic: it can miss the closest pair, but
+        // never report a pair closer than the true one.
+        #[test]
+        fn bit_shift_is_an_upper_bound((bits, points) in packable_points()) {
+            let (p1, p2, dist) = closest_pair_bit_shift(points.clone(), bits);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            prop_assert!(
+                dist >= expected || close(dist, expected),
+                "bit shift = {}, brute force = {}", dist, expected
+            );
+            prop_assert_eq!(eucid_distance(&p1, &p2), dist);
+            prop_assert!(points.contains(&p1) && points.contains(&p2));
+        }
+
+        // When the window covers the whole input every pair gets compared
+        #[test]
+        fn bit_shift_exact_when_window_covers_input(
+            (bits, points) in (8u8..=16).prop_flat_map(|bits| (Just(bits), points(1 << bits, bits as usize + 1)))
+        ) {
+            let (_, _, dist) = closest_pair_bit_shift(points.clone(), bits);
+            let (_, _, expected) = closest_pair_brute_force(&points);
+            prop_assert!(close(dist, expected), "bit shift = {}, brute force = {}", dist, expected);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod closest_pair_excluding_tests {
+    use super::*;
+
+    #[test]
+    fn test_excluding_closest_pair_gives_second_closest() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 100, y: 0 },
+            Point { x: 1, y: 1 },
+            Point { x: 50, y: 50 },
+            Point { x: 103, y: 4 },
+        ];
+        let (i, j, dist) = closest_pair_excluding(&points, &HashSet::new());
+        assert_eq!(dist, closest_pair_optimized(&points).2);
+        assert_eq!(eucid_distance(&points[i], &points[j]), dist);
+
+        assert_eq!(
+            closest_pair_excluding(&points, &HashSet::from([0, 2])),
+            (1, 4, 5.0)
+        );
+    }
+
+    #[test]
+    fn test_greedy_pairing() {
+        let points = random_points(200, 10_000, 6);
+        let mut used = HashSet::new();
+        let mut last = 0.0;
+
+        // Each pair taken is at least as far apart as the one before
+        while points.len() - used.len() >= 2 {
+            let (i, j, dist) = closest_pair_excluding(&points, &used);
+            assert!(dist >= last);
+            last = dist;
+
+            let remaining: Vec<Point> = (0..points.len())
+                .filter(|i| !used.contains(i))
+                .map(|i| points[i])
+                .collect();
+            assert_eq!(dist, closest_pair_brute_force(&remaining).2);
+
+            assert!(i < j);
+            assert!(used.insert(i) && used.insert(j));
+        }
+        assert_eq!(used.len(), 200);
+    }
+
+    #[test]
+    fn test_duplicates_keep_their_positions() {
+        let points = vec![
+            Point { x: 2, y: 2 },
+            Point { x: 2, y: 2 },
+            Point { x: 9, y: 9 },
+            Point { x: 2, y: 2 },
+        ];
+        assert_eq!(
+            closest_pair_excluding(&points, &HashSet::from([0])),
+            (1, 3, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_indices_ignored() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+        assert_eq!(closest_pair_excluding(&points, &HashSet::from([7])).2, 5.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+        assert_eq!(
+            try_closest_pair_excluding(&points, &HashSet::from([0, 1])).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod closest_pair_with_blacklist_tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_pair_blacklisted() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 10 },
+            Point { x: 5, y: 5 },
+            Point { x: 6, y: 5 },
+            Point { x: 8, y: 8 },
+        ];
+        assert_eq!(
+            closest_pair_with_blacklist(&points, &HashSet::new()),
+            (points[2], points[3], 1.0)
+        );
+
+        // With (2, 3) forbidden, in either order, the next closest is (1, 4)
+        let expected = (points[1], points[4], (8.0f64).sqrt() as f32);
+        assert_eq!(
+            closest_pair_with_blacklist(&points, &HashSet::from([(2, 3)])),
+            expected
+        );
+        assert_eq!(
+            closest_pair_with_blacklist(&points, &HashSet::from([(3, 2)])),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force_without_forbidden_pairs() {
+        let points = random_points(300, 10_000, 23);
+        let (p1, p2, distance) = closest_pair_with_blacklist(&points, &HashSet::new());
+        assert_eq!(distance, closest_pair_brute_force(&points).2);
+
+        // Forbidding the closest pair leaves a pair at least as far apart
+        let i = points.iter().position(|&p| p == p1).unwrap();
+        let j = points.iter().position(|&p| p == p2).unwrap();
+        let (_, _, next) = closest_pair_with_blacklist(&points, &HashSet::from([(i, j)]));
+        assert!(next >= distance);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 1 },
+            Point { x: 5, y: 5 },
+        ];
+        let all = HashSet::from([(0, 1), (2, 0), (1, 2)]);
+        assert_eq!(
+            try_closest_pair_with_blacklist(&points, &all).unwrap_err(),
+            ClosestPairError::AllPairsForbidden
+        );
+
+        // Out of range pairs forbid nothing
+        let (_, _, distance) = closest_pair_with_blacklist(&points, &HashSet::from([(7, 9)]));
+        assert_eq!(distance, (2.0f64).sqrt() as f32);
+
+        let empty: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_with_blacklist(&empty, &HashSet::new()).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_in_region_tests {
+    use super::*;
+
+    #[test]
+    fn test_global_pair_outside_region() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 50, y: 50 },
+            Point { x: 53, y: 54 },
+            Point { x: 60, y: 70 },
+            Point { x: 200, y: 200 },
+        ];
+        assert_eq!(closest_pair_optimized(&points).2, 1.0);
+
+        let (p1, p2, dist) =
+            closest_pair_in_region(&points, Point { x: 40, y: 40 }, Point { x: 100, y: 100 });
+        assert_eq!(dist, 5.0);
+        assert!((p1, p2) == (points[2], points[3]) || (p1, p2) == (points[3], points[2]));
+    }
+
+    #[test]
+    fn test_edges_are_inside() {
+        let points = vec![
+            Point { x: 10, y: 10 },
+            Point { x: 20, y: 20 },
+            Point { x: 10, y: 21 },
+            Point { x: 9, y: 15 },
+        ];
+        let (p1, p2, dist) =
+            closest_pair_in_region(&points, Point { x: 10, y: 10 }, Point { x: 20, y: 20 });
+        assert_eq!(dist, eucid_distance(&points[0], &points[1]));
+        assert!((p1, p2) == (points[0], points[1]) || (p1, p2) == (points[1], points[0]));
+    }
+
+    #[test]
+    fn test_matches_filtered_brute_force() {
+        let points = random_points(2000, 1000, 8);
+        let (min, max) = (Point { x: 200, y: 300 }, Point { x: 600, y: 500 });
+        let inside: Vec<Point> = points
+            .iter()
+            .filter(|p| (200..=600).contains(&p.x) && (300..=500).contains(&p.y))
+            .copied()
+            .collect();
+
+        let (_, _, dist) = closest_pair_in_region(&points, min, max);
+        assert_eq!(dist, closest_pair_brute_force(&inside).2);
+    }
+
+    #[test]
+    fn test_signed_region() {
+        let points = vec![
+            Pointi { x: -5, y: -5 },
+            Pointi { x: -4, y: -5 },
+            Pointi { x: 3, y: 3 },
+            Pointi { x: 3, y: 5 },
+        ];
+        let (_, _, dist) =
+            closest_pair_in_region(&points, Pointi { x: 0, y: 0 }, Pointi { x: 9, y: 9 });
+        assert_eq!(dist, 2.0);
+    }
+
+    #[test]
+    fn test_too_few_points_in_region() {
         let points = vec![
             Point { x: 0, y: 0 },
-            Point { x: 3, y: 0 },
-            Point { x: 0, y: 4 },
-            Point { x: 10, y: 10 },
+            Point { x: 1, y: 1 },
+            Point { x: 9, y: 9 },
         ];
+        assert_eq!(
+            try_closest_pair_in_region(&points, Point { x: 2, y: 2 }, Point { x: 8, y: 8 })
+                .unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_closest_pair_in_region(&points, Point { x: 5, y: 5 }, Point { x: 9, y: 9 })
+                .unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+        // An inverted rectangle holds nothing
+        assert_eq!(
+            try_closest_pair_in_region(&points, Point { x: 9, y: 9 }, Point { x: 0, y: 0 })
+                .unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two points to find closest pair")]
+    fn test_single_point_in_region() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 9, y: 9 }];
+        closest_pair_in_region(&points, Point { x: 5, y: 5 }, Point { x: 9, y: 9 });
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_canonical_tests {
+    use super::*;
+
+    /// Smallest pair at the minimum distance, by checking every pair.
+    fn canonical_brute_force(points: &[Point]) -> (Point, Point) {
+        let (_, _, dist) = closest_pair_brute_force(points);
+        let mut pairs = Vec::new();
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                if eucid_distance(&points[i], &points[j]) == dist {
+                    let (a, b) = (points[i], points[j]);
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        pairs.into_iter().min().unwrap()
+    }
+
+    #[test]
+    fn test_algorithms_agree_on_ties() {
+        // A lattice has many pairs at the same distance
+        let points: Vec<Point> = (0..12)
+            .flat_map(|x| {
+                (0..12).map(move |y| Point {
+                    x: 3 * x + 7,
+                    y: 3 * y + 2,
+                })
+            })
+            .rev()
+            .collect();
+        let expected = canonical_brute_force(&points);
+        assert_eq!(expected, (Point { x: 7, y: 2 }, Point { x: 7, y: 5 }));
+
+        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+        let (_, _, opt_dist) = closest_pair_optimized(&points);
+        let (_, _, bit_dist) = closest_pair_bit_shift_auto(&points);
+        assert_eq!(bf_dist, 3.0);
+        assert_eq!(opt_dist, bf_dist);
+        assert_eq!(bit_dist, bf_dist);
 
-        let (p1, p2, dist) = closest_pair_optimized(points);
+        let (p1, p2, dist) = closest_pair_canonical(&points);
+        assert_eq!((p1, p2), expected);
         assert_eq!(dist, 3.0);
-        assert!(
-            (p1.x == 0 && p1.y == 0 && p2.x == 3 && p2.y == 0)
-                || (p2.x == 0 && p2.y == 0 && p1.x == 3 && p1.y == 0)
-        );
     }
 
     #[test]
-    fn test_single_pair() {
-        // Test with just two points
-        let points = vec![Point { x: 5, y: 10 }, Point { x: 8, y: 14 }];
+    fn test_independent_of_order() {
+        let mut points = random_points(300, 40, 13);
+        let expected = closest_pair_canonical(&points);
 
-        let (_, _, dist) = closest_pair_optimized(points);
-        assert!((dist - 5.0).abs() < 0.001); // Distance should be 5.0
+        points.reverse();
+        assert_eq!(closest_pair_canonical(&points), expected);
+        points.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(closest_pair_canonical(&points), expected);
     }
 
     #[test]
-    fn test_collinear_points() {
-        // Test with points in a straight line
+    fn test_matches_brute_force() {
+        for seed in 0..20 {
+            let points = random_points(200, 60, seed);
+            let (p1, p2, dist) = closest_pair_canonical(&points);
+            assert_eq!((p1, p2), canonical_brute_force(&points), "seed = {seed}");
+            assert_eq!(dist, closest_pair_brute_force(&points).2);
+        }
+    }
+
+    #[test]
+    fn test_vertical_line() {
+        let points: Vec<Point> = (0..500).rev().map(|y| Point { x: 4, y: 2 * y }).collect();
+        assert_eq!(
+            closest_pair_canonical(&points),
+            (Point { x: 4, y: 0 }, Point { x: 4, y: 2 }, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
         let points = vec![
-            Point { x: 1, y: 1 },
-            Point { x: 3, y: 3 },
-            Point { x: 5, y: 5 },
-            Point { x: 7, y: 7 },
             Point { x: 9, y: 9 },
+            Point { x: 2, y: 8 },
+            Point { x: 9, y: 9 },
+            Point { x: 2, y: 8 },
         ];
+        assert_eq!(
+            closest_pair_canonical(&points),
+            (Point { x: 2, y: 8 }, Point { x: 2, y: 8 }, 0.0)
+        );
+    }
 
-        let (_, _, dist) = closest_pair_optimized(points);
-        assert!((dist - 2.0 * f32::sqrt(2.0)).abs() < 0.001); // Distance should be 2√2
+    #[test]
+    fn test_float_points() {
+        let points = vec![
+            PointF64 { x: 0.5, y: 0.5 },
+            PointF64 { x: -0.5, y: 0.5 },
+            PointF64 { x: 0.5, y: -0.5 },
+        ];
+        let (p1, p2, dist) = closest_pair_canonical(&points);
+        assert_eq!((p1, p2), (points[1], points[0]));
+        assert_eq!(dist, 1.0);
     }
 
     #[test]
-    fn test_grid_points() {
-        // Test with points arranged in a grid
-        let mut points = Vec::new();
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_canonical(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
 
-        // Create a 5x5 grid with points at integer coordinates
-        for x in 0..5 {
-            for y in 0..5 {
-                points.push(Point { x, y });
-            }
-        }
+#[cfg(test)]
+mod min_distance_multiplicity_tests {
+    use super::*;
 
-        let (_, _, dist) = closest_pair_optimized(points);
-        assert_eq!(dist, 1.0); // Minimum distance in a grid is 1.0
+    #[test]
+    fn test_grid() {
+        // An n x n grid has n(n - 1) horizontal and as many vertical unit pairs
+        let points: Vec<Point> = (0..10)
+            .flat_map(|x| (0..10).map(move |y| Point { x, y }))
+            .collect();
+        assert_eq!(min_distance_multiplicity(&points), (1.0, 180));
     }
 
     #[test]
-    fn test_duplicate_points() {
-        // Test with duplicate points (should give distance 0)
+    fn test_unique_pair() {
+        let points = random_points(300, 1 << 20, 14);
+        let (dist, count) = min_distance_multiplicity(&points);
+        assert_eq!(dist, closest_pair_brute_force(&points).2);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_ties_counted_exactly() {
+        // Squared distances 2^48 + 1 and 2^48 round to the same f32 distance
         let points = vec![
-            Point { x: 10, y: 20 },
-            Point { x: 30, y: 40 },
-            Point { x: 10, y: 20 }, // Duplicate
-            Point { x: 50, y: 60 },
+            Point { x: 0, y: 0 },
+            Point { x: 1 << 24, y: 1 },
+            Point { x: 1 << 25, y: 0 },
+            Point { x: 3 << 24, y: 0 },
         ];
+        assert_eq!(
+            eucid_distance(&points[0], &points[1]),
+            eucid_distance(&points[2], &points[3])
+        );
+        assert_eq!(min_distance_multiplicity(&points), ((1u64 << 24) as f32, 1));
+    }
 
-        let (_, _, dist) = closest_pair_optimized(points);
-        assert_eq!(dist, 0.0);
+    #[test]
+    fn test_duplicates() {
+        // Three copies of a point make three pairs at distance 0
+        let points = vec![Point { x: 2, y: 2 }; 3];
+        assert_eq!(min_distance_multiplicity(&points), (0.0, 3));
     }
 
     #[test]
-    fn test_large_range() {
-        // Test with points spread over a large range
+    fn test_errors() {
+        let points = vec![Pointi { x: -1, y: 1 }];
+        assert_eq!(
+            try_min_distance_multiplicity(&points).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_periodic_tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_wins() {
+        // Without wrapping (10, 10) and (13, 14) are closest, 5 apart
         let points = vec![
-            Point { x: 0, y: 0 },
-            Point { x: 10000, y: 10000 },
-            Point { x: 20000, y: 20000 },
-            Point { x: 20005, y: 20005 }, // Closest to the previous point
+            Point { x: 1, y: 50 },
+            Point { x: 10, y: 10 },
+            Point { x: 13, y: 14 },
+            Point { x: 98, y: 51 },
         ];
+        assert_eq!(closest_pair_brute_force(&points).2, 5.0);
 
-        let (_, _, dist) = closest_pair_optimized(points);
-        assert!((dist - 5.0 * f32::sqrt(2.0)).abs() < 0.001); // Should be 5√2
+        let (p1, p2, dist) = closest_pair_periodic(&points, 100, 100);
+        assert_eq!((p1, p2), (points[0], points[3]));
+        assert_eq!(dist, (10.0f64).sqrt() as f32);
     }
 
     #[test]
-    fn test_random_points() {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let bits = 31;
+    fn test_corners() {
+        // Opposite corners of the space are neighbours across both edges
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 31, y: 63 },
+            Point { x: 16, y: 30 },
+        ];
+        assert_eq!(
+            closest_pair_periodic(&points, 32, 64).2,
+            (2.0f64).sqrt() as f32
+        );
+    }
 
-        // Generate 50000 random points
-        let mut points = Vec::new();
-        for _ in 0..50000 {
-            points.push(Point {
-                x: rng.gen_range(0..(u32::pow(2, bits))),
-                y: rng.gen_range(0..(u32::pow(2, bits))),
-            });
-        }
+    #[test]
+    fn test_large_period_matches_plain_distance() {
+        let points = random_points(300, 1000, 15);
+        let (_, _, dist) = closest_pair_periodic(&points, u32::MAX, u32::MAX);
+        assert_eq!(dist, closest_pair_brute_force(&points).2);
+    }
 
-        // Run closest pair algorithm
-        let (_, _, dist) = closest_pair_optimized(points.clone());
+    #[test]
+    fn test_coordinates_outside_period() {
+        // x = 105 is the same place as x = 5
+        let points = vec![
+            Point { x: 105, y: 0 },
+            Point { x: 7, y: 0 },
+            Point { x: 50, y: 0 },
+        ];
+        assert_eq!(closest_pair_periodic(&points, 100, 100).2, 2.0);
+    }
 
-        // Compare with brute force result for validation
-        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_periodic(&points, 10, 10).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
 
-        // Distances should match, points acn be different
-        assert!(dist == bf_dist);
+    #[test]
+    fn test_zero_period() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+        assert_eq!(
+            try_closest_pair_periodic(&points, 0, 10).unwrap_err(),
+            ClosestPairError::InvalidPeriod {
+                width: 0,
+                height: 10
+            }
+        );
+        assert_eq!(
+            try_closest_pair_periodic(&points, 10, 0).unwrap_err(),
+            ClosestPairError::InvalidPeriod {
+                width: 10,
+                height: 0
+            }
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn test_empty_vector() {
-        // This should panic because we need at least 2 points
-        let points: Vec<Point> = Vec::new();
-        closest_pair_optimized(points);
+    #[should_panic(expected = "Periodic width and height must be positive, got 0 x 10")]
+    fn test_zero_width_panics() {
+        closest_pair_periodic(&[Point { x: 0, y: 0 }, Point { x: 1, y: 1 }], 0, 10);
     }
 }
 
 #[cfg(test)]
-mod closest_pair_bit_shift_tests {
+mod closest_pair_by_tests {
     use super::*;
-    use std::f32;
 
     #[test]
-    fn test_small_set() {
-        // Basic test with known distances
+    fn test_manhattan_closure() {
+        // Euclidean prefers (0, 0)-(3, 3), Manhattan prefers (20, 0)-(25, 0)
         let points = vec![
             Point { x: 0, y: 0 },
-            Point { x: 3, y: 0 },
-            Point { x: 0, y: 4 },
-            Point { x: 10, y: 10 },
+            Point { x: 3, y: 3 },
+            Point { x: 20, y: 0 },
+            Point { x: 25, y: 0 },
         ];
+        let manhattan = |a: &Point, b: &Point| manhattan_distance(a, b) as f32;
 
-        let (p1, p2, dist) = closest_pair_bit_shift(points, 8);
-        assert_eq!(dist, 3.0);
-        assert!(
-            (p1.x == 0 && p1.y == 0 && p2.x == 3 && p2.y == 0)
-                || (p2.x == 0 && p2.y == 0 && p1.x == 3 && p1.y == 0)
+        let (p1, p2, dist) = closest_pair_by(&points, manhattan);
+        assert_eq!((p1, p2, dist), (points[2], points[3], 5.0));
+        assert_eq!(
+            closest_pair_brute_force_metric(&points, Metric::Manhattan),
+            (p1, p2, dist)
         );
     }
 
     #[test]
-    fn test_single_pair() {
-        // Test with just two points
-        let points = vec![Point { x: 5, y: 10 }, Point { x: 8, y: 14 }];
-
-        let (_, _, dist) = closest_pair_bit_shift(points, 8);
-        assert!((dist - 5.0).abs() < 0.001); // Distance should be 5.0
+    fn test_matches_euclidean() {
+        let points = random_points(300, 1 << 20, 16);
+        let (_, _, dist) = closest_pair_by(&points, eucid_distance);
+        assert_eq!(dist, closest_pair_brute_force(&points).2);
     }
 
     #[test]
-    fn test_collinear_points() {
-        // Test with points in a straight line
+    fn test_captured_data() {
+        // A distance that depends on data outside the points: travel costs per row
+        let costs = [1.0, 10.0, 1.0];
         let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 2, y: 0 },
+            Point { x: 0, y: 1 },
             Point { x: 1, y: 1 },
-            Point { x: 3, y: 3 },
-            Point { x: 5, y: 5 },
-            Point { x: 7, y: 7 },
-            Point { x: 9, y: 9 },
+            Point { x: 5, y: 2 },
+            Point { x: 8, y: 2 },
         ];
+        let cost = |a: &Point, b: &Point| {
+            if a.y != b.y {
+                return f32::INFINITY;
+            }
+            a.x.abs_diff(b.x) as f32 * costs[a.y as usize]
+        };
 
-        let (_, _, dist) = closest_pair_bit_shift(points, 8);
-        assert!((dist - 2.0 * f32::sqrt(2.0)).abs() < 0.001); // Distance should be 2√2
+        let (p1, p2, dist) = closest_pair_by(&points, cost);
+        assert_eq!((p1, p2, dist), (points[0], points[1], 2.0));
     }
 
     #[test]
-    fn test_grid_points() {
-        // Test with points arranged in a grid
-        let mut points = Vec::new();
-
-        // Create a 5x5 grid with points at integer coordinates
-        for x in 0..5 {
-            for y in 0..5 {
-                points.push(Point { x, y });
+    fn test_nan_never_wins() {
+        let points = vec![
+            Pointi { x: 0, y: 0 },
+            Pointi { x: 1, y: 0 },
+            Pointi { x: 9, y: 0 },
+        ];
+        let dist = |a: &Pointi, b: &Pointi| {
+            if a.x == 0 && b.x == 1 {
+                f32::NAN
+            } else {
+                a.x.abs_diff(b.x) as f32
             }
-        }
+        };
+        assert_eq!(closest_pair_by(&points, dist), (points[1], points[2], 8.0));
+    }
 
-        let (_, _, dist) = closest_pair_bit_shift(points, 8);
-        assert_eq!(dist, 1.0); // Minimum distance in a grid is 1.0
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_closest_pair_by(&points, eucid_distance).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }];
+        assert_eq!(
+            try_closest_pair_by(&points, |_, _| f32::INFINITY).unwrap_err(),
+            ClosestPairError::InfiniteDistance
+        );
     }
+}
+
+#[cfg(test)]
+mod min_distance_between_sets_tests {
+    use super::*;
 
     #[test]
-    fn test_duplicate_points() {
-        // Test with duplicate points (should give distance 0)
-        let points = vec![
-            Point { x: 10, y: 20 },
-            Point { x: 30, y: 40 },
-            Point { x: 10, y: 20 }, // Duplicate
-            Point { x: 50, y: 60 },
-        ];
+    fn test_disjoint_sets() {
+        // Two columns 3 apart, with the points of each 7 apart
+        let left: Vec<Point> = (0..10).map(|i| Point { x: 0, y: 7 * i }).collect();
+        let right: Vec<Point> = (0..10).map(|i| Point { x: 3, y: 7 * i + 2 }).collect();
+        let expected = (13.0f64).sqrt() as f32;
+        assert_eq!(min_distance_between_sets(&left, &right), expected);
+        assert_eq!(min_distance_between_sets(&right, &left), expected);
+    }
 
-        let (_, _, dist) = closest_pair_bit_shift(points, 8);
-        assert_eq!(dist, 0.0);
+    #[test]
+    fn test_overlapping_sets() {
+        let a = random_points(200, 1000, 17);
+        let mut b = random_points(200, 1000, 18);
+        assert!(min_distance_between_sets(&a, &b) > 0.0);
+
+        b.push(a[100]);
+        assert_eq!(min_distance_between_sets(&a, &b), 0.0);
     }
 
     #[test]
-    fn test_large_range() {
-        // Test with points spread over a large range
-        let points = vec![
-            Point { x: 0, y: 0 },
-            Point { x: 10000, y: 10000 },
-            Point { x: 20000, y: 20000 },
-            Point { x: 20005, y: 20005 }, // Closest to the previous point
-        ];
+    fn test_ignores_pairs_within_a_set() {
+        let a = vec![Pointi { x: 0, y: 0 }, Pointi { x: 0, y: 1 }];
+        let b = vec![Pointi { x: -6, y: 8 }, Pointi { x: -6, y: 9 }];
+        assert_eq!(
+            min_distance_between_sets(&a, &b),
+            (36.0f64 + 49.0).sqrt() as f32
+        );
+    }
 
-        let (_, _, dist) = closest_pair_bit_shift(points, 16);
-        assert!((dist - 5.0 * f32::sqrt(2.0)).abs() < 0.001); // Should be 5√2
+    #[test]
+    fn test_errors() {
+        let empty: Vec<Point> = Vec::new();
+        let points = vec![Point { x: 1, y: 1 }];
+        assert_eq!(
+            try_min_distance_between_sets(&points, &empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            try_min_distance_between_sets(&empty, &empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
     }
+}
+
+#[cfg(test)]
+mod farthest_pair_bichromatic_tests {
+    use super::*;
 
     #[test]
-    fn test_random_points() {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let bits = 31;
-        // Generate 50000 random points
-        let mut points = Vec::new();
-        for _ in 0..50000 {
-            points.push(Point {
-                x: rng.gen_range(0..(u32::pow(2, bits))),
-                y: rng.gen_range(0..(u32::pow(2, bits))),
-            });
-        }
+    fn test_ignores_pairs_within_a_set() {
+        // The two points of `a` are the farthest apart overall
+        let a = vec![Point { x: 0, y: 0 }, Point { x: 100, y: 0 }];
+        let b = vec![Point { x: 50, y: 3 }, Point { x: 40, y: 3 }];
+        let (p, q, distance) = farthest_pair_bichromatic(&a, &b);
+        assert_eq!((p, q), (a[1], b[1]));
+        assert_eq!(distance, eucid_distance(&a[1], &b[1]));
+    }
 
-        // Run closest pair algorithm
-        let (_, _, dist) = closest_pair_bit_shift(points.clone(), 32);
+    #[test]
+    fn test_matches_brute_force_on_union() {
+        let a = random_points(50, 1000, 19);
+        let b = random_points(60, 1000, 20);
+        let expected = a
+            .iter()
+            .flat_map(|p| b.iter().map(move |q| eucid_distance(p, q)))
+            .fold(0.0f32, f32::max);
+        assert_eq!(farthest_pair_bichromatic(&a, &b).2, expected);
+        assert_eq!(farthest_pair_bichromatic(&b, &a).2, expected);
+    }
 
-        // Compare with brute force result for validation
-        let (_, _, bf_dist) = closest_pair_brute_force(&points);
+    #[test]
+    fn test_errors() {
+        let empty: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_farthest_pair_bichromatic(&empty, &empty).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
 
-        // Distances should match, points acn be different
-        assert!(dist == bf_dist);
+#[cfg(test)]
+mod cluster_distance_tests {
+    use super::*;
+
+    fn clusters() -> (Vec<Point>, Vec<Point>) {
+        // Pairwise distances: (0,0)-(3,4) = 5, (0,0)-(6,8) = 10,
+        // (0,4)-(3,4) = 3, (0,4)-(6,8) = √52
+        let a = vec![Point { x: 0, y: 0 }, Point { x: 0, y: 4 }];
+        let b = vec![Point { x: 3, y: 4 }, Point { x: 6, y: 8 }];
+        (a, b)
     }
 
     #[test]
-    #[should_panic]
-    fn test_empty_vector() {
-        // This should panic because we need at least 2 points
-        let points: Vec<Point> = Vec::new();
-        closest_pair_bit_shift(points, 8);
+    fn test_single() {
+        let (a, b) = clusters();
+        assert_eq!(cluster_distance(&a, &b, Linkage::Single), 3.0);
+    }
+
+    #[test]
+    fn test_complete() {
+        let (a, b) = clusters();
+        assert_eq!(cluster_distance(&a, &b, Linkage::Complete), 10.0);
+    }
+
+    #[test]
+    fn test_average() {
+        let (a, b) = clusters();
+        let expected = ((5.0 + 10.0 + 3.0 + 52.0f64.sqrt()) / 4.0) as f32;
+        assert_eq!(cluster_distance(&a, &b, Linkage::Average), expected);
+        assert_eq!(cluster_distance(&b, &a, Linkage::Average), expected);
+    }
+
+    #[test]
+    fn test_linkages_are_ordered() {
+        let a = random_points(30, 500, 21);
+        let b = random_points(40, 500, 22);
+        let single = cluster_distance(&a, &b, Linkage::Single);
+        let average = cluster_distance(&a, &b, Linkage::Average);
+        let complete = cluster_distance(&a, &b, Linkage::Complete);
+        assert!(single <= average && average <= complete);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: Vec<Point> = Vec::new();
+        let points = vec![Point { x: 1, y: 1 }];
+        for linkage in [Linkage::Single, Linkage::Complete, Linkage::Average] {
+            assert_eq!(
+                try_cluster_distance(&points, &empty, linkage).unwrap_err(),
+                ClosestPairError::EmptyInput
+            );
+        }
     }
 }
@@ -0,0 +1,268 @@
+use crate::algorithms::*;
+use crate::error::ClosestPairError;
+use crate::utils::*;
+
+/// Settings for the divide-and-conquer closest pair search, built up one option at a time.
+///
+/// The free functions like [`closest_pair_optimized`] cover the common cases.
+/// This builder is for combining options that have no function of their own,
+/// such as a metric with a custom brute force cutoff. Start from
+/// [`ClosestPairConfig::new`], which measures Euclidean distances, uses
+/// [`DEFAULT_BRUTE_FORCE_CUTOFF`] and runs on a single thread.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{Metric, Point};
+/// use closest_pair_rs::config::ClosestPairConfig;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 3 },
+///     Point { x: 20, y: 0 },
+///     Point { x: 25, y: 0 }
+/// ];
+///
+/// let (_, _, distance) = ClosestPairConfig::new().solve(&points);
+/// assert_eq!(distance, (18.0f64).sqrt() as f32);
+///
+/// let (_, _, distance) = ClosestPairConfig::new()
+///     .metric(Metric::Manhattan)
+///     .brute_force_cutoff(8)
+///     .solve(&points);
+/// assert_eq!(distance, 5.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClosestPairConfig {
+    metric: Metric,
+    cutoff: usize,
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+    #[cfg(feature = "rayon")]
+    threads: Option<usize>,
+}
+
+impl ClosestPairConfig {
+    /// Creates a config with the default settings.
+    pub fn new() -> Self {
+        ClosestPairConfig {
+            metric: Metric::Euclidean,
+            cutoff: DEFAULT_BRUTE_FORCE_CUTOFF,
+            #[cfg(feature = "rayon")]
+            parallel: false,
+            #[cfg(feature = "rayon")]
+            threads: None,
+        }
+    }
+
+    /// Sets the metric distances are measured with.
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Sets the largest subproblem solved by brute force instead of being split.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cutoff` is less than 3, see [`closest_pair_optimized_with_cutoff`].
+    pub fn brute_force_cutoff(mut self, cutoff: usize) -> Self {
+        assert!(
+            cutoff >= 3,
+            "Brute force cutoff must be at least 3, got {cutoff}"
+        );
+        self.cutoff = cutoff;
+        self
+    }
+
+    /// Sets whether the recursion is spread across the rayon thread pool, like
+    /// [`closest_pair_optimized_parallel`] does. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Runs the recursion in parallel on a thread pool of its own with `threads`
+    /// threads, rather than on the current rayon pool. Requires the `rayon` feature.
+    ///
+    /// Implies [`ClosestPairConfig::parallel`]. The pool is built on every call to
+    /// [`ClosestPairConfig::solve`], so for many small inputs it's cheaper to build
+    /// one pool and call `solve` inside its `install`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is 0.
+    #[cfg(feature = "rayon")]
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "Thread count must be at least 1, got 0");
+        self.parallel = true;
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Finds the closest pair of points with these settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Vector of points to analyze
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// * The first point of the closest pair
+    /// * The second point of the closest pair
+    /// * The distance between these points as a f32
+    ///
+    /// # Panics
+    ///
+    /// * When the input vector is empty
+    /// * When there's only one point in the vector
+    /// * When a thread pool was asked for and couldn't be built
+    pub fn solve<T: Coordinate>(&self, points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+        self.try_solve(points).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Finds the closest pair of points with these settings, without panicking.
+    ///
+    /// Same as [`ClosestPairConfig::solve`], but reports invalid input as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Vector of points to analyze
+    ///
+    /// # Returns
+    ///
+    /// The closest pair and their distance, or a [`ClosestPairError`] when:
+    /// * The input vector is empty
+    /// * There's only one point in the vector
+    ///
+    /// # Panics
+    ///
+    /// * When a thread pool was asked for and couldn't be built
+    pub fn try_solve<T: Coordinate>(
+        &self,
+        points: &[Point<T>],
+    ) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+        check_input(points)?;
+
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            let solve = || optimized_raw_parallel(points, self.metric, self.cutoff);
+            let (p1, p2, dist_raw) = match self.threads {
+                Some(threads) => rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap_or_else(|err| panic!("Failed to build thread pool: {err}"))
+                    .install(solve),
+                None => solve(),
+            };
+            return Ok((p1, p2, self.metric.finish::<T>(dist_raw)));
+        }
+
+        let (p1, p2, dist_raw) = optimized_raw_with_cutoff(points, self.metric, self.cutoff);
+        Ok((p1, p2, self.metric.finish::<T>(dist_raw)))
+    }
+}
+
+impl Default for ClosestPairConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_config_tests {
+    use super::*;
+    use crate::algorithms::REC_CALLS;
+
+    fn rec_calls() -> usize {
+        REC_CALLS.with(|calls| calls.get())
+    }
+
+    #[test]
+    fn test_defaults_match_optimized() {
+        let points = random_points(2000, 1 << 20, 5);
+        assert_eq!(ClosestPairConfig::default(), ClosestPairConfig::new());
+        assert_eq!(
+            ClosestPairConfig::new().solve(&points),
+            closest_pair_optimized(&points)
+        );
+    }
+
+    #[test]
+    fn test_metric_changes_result() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 3 },
+            Point { x: 20, y: 0 },
+            Point { x: 24, y: 0 },
+        ];
+        let config = ClosestPairConfig::new();
+
+        let (p1, p2, dist) = config.solve(&points);
+        assert_eq!((p1, p2, dist), (points[2], points[3], 4.0));
+
+        let (p1, p2, dist) = config.metric(Metric::Chebyshev).solve(&points);
+        assert_eq!((p1, p2, dist), (points[0], points[1], 3.0));
+    }
+
+    #[test]
+    fn test_cutoff_changes_recursion() {
+        let points = random_points(1024, 1000, 2);
+        let calls = |config: ClosestPairConfig| {
+            let before = rec_calls();
+            let result = config.solve(&points);
+            (rec_calls() - before, result)
+        };
+
+        let (default_calls, default_result) = calls(ClosestPairConfig::new());
+        let (brute_calls, brute_result) = calls(ClosestPairConfig::new().brute_force_cutoff(1024));
+        assert_eq!(brute_calls, 1);
+        assert!(default_calls > brute_calls);
+        assert_eq!(default_result.2, brute_result.2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_matches_serial() {
+        let points = random_points(100_000, 1 << 24, 8);
+        let serial = ClosestPairConfig::new().metric(Metric::Manhattan);
+        let parallel = serial.parallel(true);
+        assert_ne!(serial, parallel);
+        assert_eq!(parallel.solve(&points), serial.solve(&points));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_thread_counts_agree() {
+        let points = random_points(200_000, 1 << 24, 9);
+        let one = ClosestPairConfig::new().threads(1);
+        let four = ClosestPairConfig::new().threads(4);
+        assert_ne!(one, four);
+        assert_eq!(one.solve(&points), four.solve(&points));
+        assert_eq!(four.solve(&points), ClosestPairConfig::new().solve(&points));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[should_panic(expected = "Thread count must be at least 1, got 0")]
+    fn test_zero_threads() {
+        ClosestPairConfig::new().threads(0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            ClosestPairConfig::new().try_solve(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Brute force cutoff must be at least 3, got 1")]
+    fn test_cutoff_too_small() {
+        ClosestPairConfig::new().brute_force_cutoff(1);
+    }
+}
@@ -0,0 +1,80 @@
+use core::fmt;
+
+/// Errors that can occur while searching for the closest pair of points.
+///
+/// Returned by the `try_*` family of functions, which are the non-panicking
+/// counterparts of the closest pair algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosestPairError {
+    /// The input contained no points at all.
+    EmptyInput,
+    /// The input contained a single point, so no pair can be formed.
+    SinglePoint,
+    /// Every point coincided with another, so no pair of distinct points exists.
+    NoDistinctPair,
+    /// A flat coordinate slice had an odd length, so its last `x` has no `y`.
+    OddCoordinateCount(usize),
+    /// Every candidate pair had an infinite distance.
+    ///
+    /// Integer coordinates are never that far apart, so this only comes up with
+    /// floating point coordinates, or with weights that make every score infinite.
+    InfiniteDistance,
+    /// The number of bits to pack each coordinate into was outside `1..=32`.
+    InvalidBits(u8),
+    /// A coordinate was too large to pack into the requested number of bits.
+    CoordinateOutOfRange { value: u32, bits: u8 },
+    /// A histogram bucket width was zero, negative or NaN.
+    InvalidBucketWidth,
+    /// Every pair of points was on the list of pairs that may not be matched.
+    AllPairsForbidden,
+    /// A periodic space had a width or height of 0.
+    InvalidPeriod { width: u32, height: u32 },
+}
+
+impl fmt::Display for ClosestPairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClosestPairError::EmptyInput => {
+                write!(f, "Cannot find closest pair with empty vector")
+            }
+            ClosestPairError::SinglePoint => {
+                write!(f, "Need at least two points to find closest pair")
+            }
+            ClosestPairError::NoDistinctPair => {
+                write!(f, "Need at least two distinct points to find closest pair")
+            }
+            ClosestPairError::OddCoordinateCount(len) => {
+                write!(
+                    f,
+                    "Flat coordinates must come in x, y pairs, got {len} values"
+                )
+            }
+            ClosestPairError::InfiniteDistance => {
+                write!(f, "No closest pair found - all distances might be infinite")
+            }
+            ClosestPairError::InvalidBits(bits) => {
+                write!(
+                    f,
+                    "Bits per coordinate must be between 1 and 32, got {bits}"
+                )
+            }
+            ClosestPairError::CoordinateOutOfRange { value, bits } => {
+                write!(f, "Coordinate {value} does not fit in {bits} bits")
+            }
+            ClosestPairError::InvalidBucketWidth => {
+                write!(f, "Bucket width must be a positive number")
+            }
+            ClosestPairError::AllPairsForbidden => {
+                write!(f, "Every pair of points is forbidden")
+            }
+            ClosestPairError::InvalidPeriod { width, height } => {
+                write!(
+                    f,
+                    "Periodic width and height must be positive, got {width} x {height}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for ClosestPairError {}
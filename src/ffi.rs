@@ -0,0 +1,171 @@
+//! C bindings, behind the `ffi` feature.
+//!
+//! To build a shared library a C program can link against, compile the crate as
+//! a `cdylib` with the feature enabled:
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//!
+//! The matching C declarations are:
+//!
+//! ```c
+//! typedef struct {
+//!     uint32_t x1, y1, x2, y2;
+//!     float distance;
+//! } ClosestPairFFI;
+//!
+//! int32_t closest_pair_ffi(const uint32_t *ptr, size_t len, ClosestPairFFI *out);
+//! ```
+
+use crate::algorithms::try_closest_pair_from_flat;
+use crate::error::ClosestPairError;
+use core::slice;
+
+/// The search succeeded and the result was written.
+pub const CLOSEST_PAIR_OK: i32 = 0;
+/// `out` was null, or `ptr` was null with a non-zero `len`.
+pub const CLOSEST_PAIR_ERR_NULL: i32 = -1;
+/// There were no points.
+pub const CLOSEST_PAIR_ERR_EMPTY: i32 = -2;
+/// There was only one point.
+pub const CLOSEST_PAIR_ERR_SINGLE_POINT: i32 = -3;
+/// `len` was odd, so the last `x` had no `y`.
+pub const CLOSEST_PAIR_ERR_ODD_LENGTH: i32 = -4;
+
+/// The closest pair of points and their distance, laid out for C.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClosestPairFFI {
+    pub x1: u32,
+    pub y1: u32,
+    pub x2: u32,
+    pub y2: u32,
+    pub distance: f32,
+}
+
+/// Finds the closest pair of points in a flat buffer of interleaved coordinates.
+///
+/// The C entry point to [`try_closest_pair_from_flat`]: `ptr` holds `len` values,
+/// `x0, y0, x1, y1, ...`, and the closest pair is written to `out`.
+///
+/// # Safety
+///
+/// * `ptr` must point to `len` initialized `u32`s that stay valid and unmodified
+///   for the duration of the call. It may only be null when `len` is 0.
+/// * `out` must point to memory valid for writing one [`ClosestPairFFI`].
+/// * Neither buffer is kept after the call returns.
+///
+/// # Returns
+///
+/// [`CLOSEST_PAIR_OK`] when the result was written, or one of the negative
+/// `CLOSEST_PAIR_ERR_*` codes, in which case `out` is left untouched.
+#[no_mangle]
+pub unsafe extern "C" fn closest_pair_ffi(
+    ptr: *const u32,
+    len: usize,
+    out: *mut ClosestPairFFI,
+) -> i32 {
+    if out.is_null() || (ptr.is_null() && len > 0) {
+        return CLOSEST_PAIR_ERR_NULL;
+    }
+    let coords = if len == 0 {
+        &[]
+    } else {
+        // SAFETY: the caller guarantees `ptr` points to `len` valid values
+        unsafe { slice::from_raw_parts(ptr, len) }
+    };
+
+    match try_closest_pair_from_flat(coords) {
+        Ok((p1, p2, distance)) => {
+            let result = ClosestPairFFI {
+                x1: p1.x,
+                y1: p1.y,
+                x2: p2.x,
+                y2: p2.y,
+                distance,
+            };
+            // SAFETY: the caller guarantees `out` is valid for writes
+            unsafe { out.write(result) };
+            CLOSEST_PAIR_OK
+        }
+        Err(ClosestPairError::EmptyInput) => CLOSEST_PAIR_ERR_EMPTY,
+        Err(ClosestPairError::SinglePoint) => CLOSEST_PAIR_ERR_SINGLE_POINT,
+        Err(ClosestPairError::OddCoordinateCount(_)) => CLOSEST_PAIR_ERR_ODD_LENGTH,
+        Err(err) => unreachable!("unexpected error for integer points: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod ffi_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_optimized;
+    use crate::utils::*;
+    use alloc::vec::Vec;
+    use core::ptr;
+
+    fn call(coords: &[u32]) -> (i32, ClosestPairFFI) {
+        let mut out = ClosestPairFFI::default();
+        let code = unsafe { closest_pair_ffi(coords.as_ptr(), coords.len(), &mut out) };
+        (code, out)
+    }
+
+    #[test]
+    fn test_flat_buffer() {
+        let (code, out) = call(&[0, 0, 10, 10, 3, 4]);
+        assert_eq!(code, CLOSEST_PAIR_OK);
+        assert_eq!(out.distance, 5.0);
+        let pair = [(out.x1, out.y1), (out.x2, out.y2)];
+        assert!(pair == [(0, 0), (3, 4)] || pair == [(3, 4), (0, 0)]);
+    }
+
+    #[test]
+    fn test_matches_points() {
+        let points = random_points(1000, 1 << 20, 15);
+        let coords: Vec<u32> = points.iter().flat_map(|&p| <[u32; 2]>::from(p)).collect();
+        let (code, out) = call(&coords);
+        assert_eq!(code, CLOSEST_PAIR_OK);
+
+        let (p1, p2, distance) = closest_pair_optimized(&points);
+        assert_eq!(
+            out,
+            ClosestPairFFI {
+                x1: p1.x,
+                y1: p1.y,
+                x2: p2.x,
+                y2: p2.y,
+                distance,
+            }
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(call(&[]).0, CLOSEST_PAIR_ERR_EMPTY);
+        assert_eq!(call(&[1, 2]).0, CLOSEST_PAIR_ERR_SINGLE_POINT);
+        assert_eq!(call(&[1, 2, 3]).0, CLOSEST_PAIR_ERR_ODD_LENGTH);
+
+        // Errors leave the output alone
+        let (_, out) = call(&[1, 2, 3]);
+        assert_eq!(out, ClosestPairFFI::default());
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let mut out = ClosestPairFFI::default();
+        unsafe {
+            assert_eq!(
+                closest_pair_ffi(ptr::null(), 0, &mut out),
+                CLOSEST_PAIR_ERR_EMPTY
+            );
+            assert_eq!(
+                closest_pair_ffi(ptr::null(), 4, &mut out),
+                CLOSEST_PAIR_ERR_NULL
+            );
+            assert_eq!(
+                closest_pair_ffi([0, 0, 1, 1].as_ptr(), 4, ptr::null_mut()),
+                CLOSEST_PAIR_ERR_NULL
+            );
+        }
+    }
+}
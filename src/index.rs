@@ -0,0 +1,178 @@
+use crate::utils::*;
+use alloc::vec::Vec;
+
+/// Answers nearest neighbor queries against a fixed set of points.
+///
+/// The points are sorted by x once when the index is built, in O(n log n) time.
+/// A query starts at the query's x position and walks outwards in both
+/// directions, stopping on each side as soon as the horizontal distance alone is
+/// larger than the nearest distance found so far. For points spread out over
+/// the plane only a small slice of them is visited, although a set where every
+/// point has the same x still needs a full scan.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::index::NearestNeighborIndex;
+///
+/// let index = NearestNeighborIndex::new(&[
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 20, y: 0 }
+/// ]);
+/// assert_eq!(index.nearest(Point { x: 7, y: 6 }), Some((Point { x: 10, y: 10 }, 5.0)));
+/// assert_eq!(index.nearest(Point { x: 20, y: 0 }), Some((Point { x: 20, y: 0 }, 0.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NearestNeighborIndex<T: Coordinate = u32> {
+    /// The points, sorted by x.
+    points: Vec<Point<T>>,
+}
+
+impl<T: Coordinate> NearestNeighborIndex<T> {
+    /// Builds an index over a copy of `points`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The points to answer queries against
+    pub fn new(points: &[Point<T>]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.x.compare(&b.x));
+        NearestNeighborIndex { points }
+    }
+
+    /// Finds the indexed point closest to `query`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The point to search around, which doesn't need to be indexed
+    ///
+    /// # Returns
+    ///
+    /// The nearest point and its distance to `query` as a f32, or `None` when the
+    /// index is empty. If several points are equally near, any one of them may
+    /// be returned.
+    pub fn nearest(&self, query: Point<T>) -> Option<(Point<T>, f32)> {
+        let start = self.points.partition_point(|p| p.x < query.x);
+        let mut best: Option<(Point<T>, T::Distance)> = None;
+
+        // Whether a point `dx` away along x can't beat the best found so far
+        let out_of_reach = |dx: T::Distance, best: &Option<(Point<T>, T::Distance)>| {
+            best.is_some_and(|(_, dist_sq)| Metric::Euclidean.raw_axis_distance(dx) > dist_sq)
+        };
+
+        let (mut left, mut right) = (start, start);
+        loop {
+            let mut moved = false;
+
+            if right < self.points.len() {
+                let p = self.points[right];
+                if out_of_reach(p.x.axis_distance(query.x), &best) {
+                    right = self.points.len();
+                } else {
+                    let dist_sq = eucid_distance_sq(&p, &query);
+                    if best.is_none_or(|(_, d)| dist_sq < d) {
+                        best = Some((p, dist_sq));
+                    }
+                    right += 1;
+                    moved = true;
+                }
+            }
+
+            if left > 0 {
+                let p = self.points[left - 1];
+                if out_of_reach(p.x.axis_distance(query.x), &best) {
+                    left = 0;
+                } else {
+                    let dist_sq = eucid_distance_sq(&p, &query);
+                    if best.is_none_or(|(_, d)| dist_sq < d) {
+                        best = Some((p, dist_sq));
+                    }
+                    left -= 1;
+                    moved = true;
+                }
+            }
+
+            if !moved {
+                break;
+            }
+        }
+
+        best.map(|(p, dist_sq)| (p, Metric::Euclidean.finish::<T>(dist_sq)))
+    }
+
+    /// Number of indexed points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the index holds no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod nearest_neighbor_index_tests {
+    use super::*;
+    use crate::algorithms::nearest_neighbor;
+
+    #[test]
+    fn test_matches_linear_scan() {
+        let points = random_points(2000, 10_000, 1);
+        let index = NearestNeighborIndex::new(&points);
+        assert_eq!(index.len(), 2000);
+
+        // Queries inside the cloud and far outside it
+        let mut queries = random_points(500, 10_000, 2);
+        queries.extend(random_points(100, 1_000_000, 3));
+        for query in queries {
+            let (p, dist) = index.nearest(query).unwrap();
+            let (_, expected) = nearest_neighbor(&points, query);
+            assert_eq!(dist, expected, "query = {query}");
+            assert_eq!(eucid_distance(&p, &query), dist);
+        }
+    }
+
+    #[test]
+    fn test_query_on_a_point() {
+        let points = random_points(500, 1000, 4);
+        let index = NearestNeighborIndex::new(&points);
+        for &p in &points {
+            assert_eq!(index.nearest(p), Some((p, 0.0)));
+        }
+    }
+
+    #[test]
+    fn test_same_x() {
+        let points: Vec<Point> = (0..50).map(|y| Point { x: 5, y: 3 * y }).collect();
+        let index = NearestNeighborIndex::new(&points);
+        assert_eq!(
+            index.nearest(Point { x: 9, y: 100 }),
+            Some((Point { x: 5, y: 99 }, (17.0f64).sqrt() as f32))
+        );
+    }
+
+    #[test]
+    fn test_signed_and_float_points() {
+        let index = NearestNeighborIndex::new(&[Pointi { x: -5, y: -5 }, Pointi { x: 5, y: 5 }]);
+        assert_eq!(
+            index.nearest(Pointi { x: -1, y: -2 }),
+            Some((Pointi { x: -5, y: -5 }, 5.0))
+        );
+
+        let index = NearestNeighborIndex::new(&[PointF64 { x: 0.5, y: 0.0 }]);
+        assert_eq!(
+            index.nearest(PointF64 { x: 0.0, y: 0.0 }),
+            Some((PointF64 { x: 0.5, y: 0.0 }, 0.5))
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let index: NearestNeighborIndex = NearestNeighborIndex::new(&[]);
+        assert!(index.is_empty());
+        assert_eq!(index.nearest(Point { x: 1, y: 1 }), None);
+    }
+}
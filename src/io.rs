@@ -0,0 +1,184 @@
+use crate::utils::*;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// Reads points from CSV data with one `x,y` pair per line.
+///
+/// Whitespace around each value is ignored, and so are blank lines. A line
+/// without a comma is split on whitespace instead, so `x y` pairs are read too.
+/// The first non-blank line may be a header such as `x,y`: it is skipped when
+/// none of its fields is a number.
+///
+/// # Arguments
+///
+/// * `reader` - Source of the CSV data, such as a file or stdin
+///
+/// # Returns
+///
+/// The points in the order they appear, or an error when reading fails or a
+/// line isn't two non-negative integers separated by a comma or whitespace. Malformed lines
+/// are reported as [`io::ErrorKind::InvalidData`], naming the line number.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::io::read_points_csv;
+///
+/// let csv = "x,y\n0,0\n\n3, 4\n";
+/// let points = read_points_csv(csv.as_bytes()).unwrap();
+/// assert_eq!(points, vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }]);
+///
+/// let points = read_points_csv("0 0\n3 4\n".as_bytes()).unwrap();
+/// assert_eq!(points, vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }]);
+/// ```
+pub fn read_points_csv(reader: impl Read) -> io::Result<Vec<Point>> {
+    let mut points = Vec::new();
+    let mut first = true;
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match parse_point(line) {
+            Some(point) => points.push(point),
+            None if first && is_header(line) => {}
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Line {}: expected `x,y`, got `{line}`", i + 1),
+                ))
+            }
+        }
+        first = false;
+    }
+
+    Ok(points)
+}
+
+/// Writes points as CSV data with one `x,y` pair per line and no header.
+///
+/// Every point is a separate write, so wrap unbuffered writers such as files in
+/// a [`std::io::BufWriter`].
+///
+/// # Arguments
+///
+/// * `writer` - Destination of the CSV data
+/// * `points` - The points to write
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::io::write_points_csv;
+///
+/// let mut csv = Vec::new();
+/// write_points_csv(&mut csv, &[Point { x: 0, y: 0 }, Point { x: 3, y: 4 }]).unwrap();
+/// assert_eq!(csv, b"0,0\n3,4\n");
+/// ```
+pub fn write_points_csv(mut writer: impl Write, points: &[Point]) -> io::Result<()> {
+    for p in points {
+        writeln!(writer, "{},{}", p.x, p.y)?;
+    }
+    Ok(())
+}
+
+/// Parses a trimmed, non-blank `x,y` or `x y` line.
+fn parse_point(line: &str) -> Option<Point> {
+    match fields(line)[..] {
+        [x, y] => Some(Point {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether a line that isn't a point looks like a header rather than bad data.
+fn is_header(line: &str) -> bool {
+    fields(line)
+        .iter()
+        .all(|field| field.parse::<f64>().is_err())
+}
+
+/// The trimmed fields of a line, separated by commas if it has any and by whitespace otherwise.
+fn fields(line: &str) -> Vec<&str> {
+    if line.contains(',') {
+        line.split(',').map(str::trim).collect()
+    } else {
+        line.split_whitespace().collect()
+    }
+}
+
+#[cfg(test)]
+mod csv_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let points = random_points(1000, u32::MAX, 3);
+        let mut csv = Vec::new();
+        write_points_csv(&mut csv, &points).unwrap();
+        assert_eq!(read_points_csv(csv.as_slice()).unwrap(), points);
+    }
+
+    #[test]
+    fn test_header_and_blank_lines() {
+        let csv = "\n  x , y \r\n1,2\r\n\n   \n 3 ,4\n";
+        assert_eq!(
+            read_points_csv(csv.as_bytes()).unwrap(),
+            vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+        );
+        assert_eq!(read_points_csv("".as_bytes()).unwrap(), vec![]);
+        assert_eq!(read_points_csv("x,y\n".as_bytes()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_whitespace_separated() {
+        let input = "x y\n1 2\n  3\t 4  \n5,6\n";
+        assert_eq!(
+            read_points_csv(input.as_bytes()).unwrap(),
+            vec![
+                Point { x: 1, y: 2 },
+                Point { x: 3, y: 4 },
+                Point { x: 5, y: 6 }
+            ]
+        );
+        assert_eq!(
+            read_points_csv("1 2 3\n".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "Line 1: expected `x,y`, got `1 2 3`"
+        );
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        let error = |csv: &str| read_points_csv(csv.as_bytes()).unwrap_err();
+
+        // A header is only allowed on the first line
+        let err = error("1,2\nx,y\n");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Line 2: expected `x,y`, got `x,y`");
+
+        // A first line with a number in it is bad data, not a header
+        assert_eq!(
+            error("1,abc\n").to_string(),
+            "Line 1: expected `x,y`, got `1,abc`"
+        );
+        assert_eq!(
+            error("1,2\n\n3\n").to_string(),
+            "Line 3: expected `x,y`, got `3`"
+        );
+        assert_eq!(
+            error("1,2\n-3,4\n").to_string(),
+            "Line 2: expected `x,y`, got `-3,4`"
+        );
+        assert_eq!(
+            error("1,2,3\n").to_string(),
+            "Line 1: expected `x,y`, got `1,2,3`"
+        );
+    }
+}
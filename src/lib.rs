@@ -1,5 +1,41 @@
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
+
 pub mod algorithms;
+pub mod config;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod index;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod result;
+pub mod solver;
+#[cfg(feature = "std")]
+pub mod streaming;
+#[cfg(feature = "std")]
+pub mod tracker;
 pub mod utils;
+pub mod viz;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use algorithms::*;
+pub use config::*;
+pub use error::*;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
+pub use index::*;
+#[cfg(feature = "std")]
+pub use io::*;
+pub use result::*;
+pub use solver::*;
+#[cfg(feature = "std")]
+pub use streaming::*;
+#[cfg(feature = "std")]
+pub use tracker::*;
 pub use utils::*;
+pub use viz::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
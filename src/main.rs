@@ -0,0 +1,78 @@
+//! Reads points from stdin, one `x y` or `x,y` pair per line, and prints their closest pair.
+
+use closest_pair_rs::algorithms::*;
+use closest_pair_rs::io::read_points_csv;
+use std::process::ExitCode;
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: closest-pair [--brute | --optimized | --bit-shift] [--time]
+
+Reads points from stdin, one `x y` or `x,y` pair per line, and prints the
+closest pair followed by their distance.
+
+Options:
+    --brute       Compare every pair of points
+    --optimized   Use the divide-and-conquer algorithm (default)
+    --bit-shift   Use the bit shift heuristic, which may miss the closest pair
+    --time        Print how long the search took to stderr
+    -h, --help    Print this help";
+
+#[derive(Clone, Copy)]
+enum Algorithm {
+    Brute,
+    Optimized,
+    BitShift,
+}
+
+fn main() -> ExitCode {
+    let mut algorithm = Algorithm::Optimized;
+    let mut time = false;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--brute" => algorithm = Algorithm::Brute,
+            "--optimized" => algorithm = Algorithm::Optimized,
+            "--bit-shift" => algorithm = Algorithm::BitShift,
+            "--time" => time = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("error: unknown argument `{arg}`\n\n{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let points = match read_points_csv(std::io::stdin().lock()) {
+        Ok(points) => points,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let start = Instant::now();
+    let result = match algorithm {
+        Algorithm::Brute => try_closest_pair_brute_force(&points),
+        Algorithm::Optimized => try_closest_pair_optimized(&points),
+        Algorithm::BitShift => try_closest_pair_bit_shift_auto(&points),
+    };
+    let elapsed = start.elapsed();
+
+    match result {
+        Ok((p1, p2, dist)) => {
+            println!("{p1} {p2} {dist}");
+            if time {
+                eprintln!("Took {elapsed:?}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::utils::*;
+use core::time::Duration;
+
+/// The closest pair of points and their distance, with named accessors.
+///
+/// The search functions return a bare `(Point, Point, f32)` tuple. Converting it
+/// into a `ClosestPairResult` makes call sites say which value is the distance,
+/// and converting back lets code that destructures tuples migrate gradually.
+/// [`closest_pair_optimized_result`](crate::algorithms::closest_pair_optimized_result)
+/// returns one directly.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::result::ClosestPairResult;
+/// use closest_pair_rs::algorithms::closest_pair_optimized;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// let result = ClosestPairResult::from(closest_pair_optimized(&points));
+/// assert_eq!(result.distance(), 5.0);
+///
+/// let (a, b, distance) = result.into();
+/// assert_eq!(distance, 5.0);
+/// assert!((a, b) == (points[0], points[2]) || (a, b) == (points[2], points[0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClosestPairResult<T = u32> {
+    a: Point<T>,
+    b: Point<T>,
+    distance: f32,
+}
+
+impl<T: Coordinate> ClosestPairResult<T> {
+    /// Creates a result from the two points of the pair and their distance.
+    pub fn new(a: Point<T>, b: Point<T>, distance: f32) -> Self {
+        ClosestPairResult { a, b, distance }
+    }
+
+    /// The first point of the closest pair.
+    pub fn a(&self) -> Point<T> {
+        self.a
+    }
+
+    /// The second point of the closest pair.
+    pub fn b(&self) -> Point<T> {
+        self.b
+    }
+
+    /// The distance between the two points.
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Both points of the closest pair, without the distance.
+    pub fn pair(&self) -> (Point<T>, Point<T>) {
+        (self.a, self.b)
+    }
+
+    /// Whether the distance is within [`DISTANCE_EPSILON`] of `other`, see [`approx_eq`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use closest_pair_rs::utils::Point;
+    /// use closest_pair_rs::result::ClosestPairResult;
+    ///
+    /// let result = ClosestPairResult::new(Point { x: 0, y: 0 }, Point { x: 1, y: 1 }, 2.0f32.sqrt());
+    /// assert!(result.distance_approx_eq(1.4142));
+    /// ```
+    pub fn distance_approx_eq(&self, other: f32) -> bool {
+        approx_eq(self.distance, other, DISTANCE_EPSILON)
+    }
+}
+
+impl<T: Coordinate> From<(Point<T>, Point<T>, f32)> for ClosestPairResult<T> {
+    fn from((a, b, distance): (Point<T>, Point<T>, f32)) -> Self {
+        ClosestPairResult::new(a, b, distance)
+    }
+}
+
+impl<T: Coordinate> From<ClosestPairResult<T>> for (Point<T>, Point<T>, f32) {
+    fn from(result: ClosestPairResult<T>) -> Self {
+        (result.a, result.b, result.distance)
+    }
+}
+
+/// Where the divide-and-conquer search found the closest pair.
+///
+/// Returned by `closest_pair_optimized_explained`, and relative to the first
+/// split of the points, sorted by x, into a left and a right half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Origin {
+    /// Both points are in the left half, found by the recursion on it.
+    Left,
+    /// Both points are in the right half, found by the recursion on it.
+    Right,
+    /// One point in each half, found in the band around the dividing line.
+    Strip,
+}
+
+/// How long each phase of the divide-and-conquer search took.
+///
+/// Returned by `closest_pair_optimized_timed`, to see whether sorting or the
+/// recursion dominates on a given input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timings {
+    /// Copying the points and sorting them by x.
+    pub sort: Duration,
+    /// The recursion, including merging by y and checking the band at each level.
+    pub recursion: Duration,
+}
+
+impl Timings {
+    /// The time taken by all phases together.
+    pub fn total(&self) -> Duration {
+        self.sort + self.recursion
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_result_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_brute_force;
+
+    #[test]
+    fn test_accessors() {
+        let a = Point { x: 1, y: 2 };
+        let b = Point { x: 4, y: 6 };
+        let result = ClosestPairResult::new(a, b, 5.0);
+
+        assert_eq!(result.a(), a);
+        assert_eq!(result.b(), b);
+        assert_eq!(result.distance(), 5.0);
+        assert_eq!(result.pair(), (a, b));
+    }
+
+    #[test]
+    fn test_distance_approx_eq() {
+        let result = ClosestPairResult::new(Point { x: 0, y: 0 }, Point { x: 3, y: 4 }, 5.0);
+        assert!(result.distance_approx_eq(5.0));
+        assert!(result.distance_approx_eq(5.0009));
+        assert!(result.distance_approx_eq(4.9991));
+        assert!(!result.distance_approx_eq(5.0011));
+        assert!(!result.distance_approx_eq(4.9989));
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let points = random_points(100, 1000, 9);
+        let tuple = closest_pair_brute_force(&points);
+
+        let result = ClosestPairResult::from(tuple);
+        assert_eq!((result.a(), result.b(), result.distance()), tuple);
+
+        let back: (Point, Point, f32) = result.into();
+        assert_eq!(back, tuple);
+    }
+
+    #[test]
+    fn test_signed_points() {
+        let result: ClosestPairResult<i32> =
+            closest_pair_brute_force(&[Pointi { x: -1, y: -1 }, Pointi { x: 2, y: 3 }]).into();
+        assert_eq!(
+            result.pair(),
+            (Pointi { x: -1, y: -1 }, Pointi { x: 2, y: 3 })
+        );
+        assert_eq!(result.distance(), 5.0);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::algorithms::*;
+use crate::error::ClosestPairError;
+use crate::utils::*;
+use alloc::vec::Vec;
+
+/// Runs the divide-and-conquer closest pair search repeatedly without reallocating.
+///
+/// [`closest_pair_optimized`] allocates two buffers as long as the input on every
+/// call: one to sort the points in and one the recursion merges through. A solver
+/// owns those buffers and only clears and refills them, so in a hot loop, such as
+/// once per simulation frame, memory is only allocated when the input grows past
+/// the largest one seen so far.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::solver::ClosestPairSolver;
+///
+/// let mut solver = ClosestPairSolver::new();
+/// let mut points = vec![
+///     Point { x: 0, y: 100 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 13, y: 14 }
+/// ];
+/// for step in 0..3 {
+///     let (_, _, distance) = solver.solve(&points);
+///     assert_eq!(distance, 5.0 * (step + 1) as f32);
+///     points[2].x += 3;
+///     points[2].y += 4;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClosestPairSolver<T = u32> {
+    /// The points sorted by x on the way into the recursion, and by y on the way out.
+    sorted: Vec<Point<T>>,
+    /// Scratch space for the recursion, as long as `sorted`.
+    scratch: Vec<Point<T>>,
+}
+
+impl<T: Coordinate> ClosestPairSolver<T> {
+    /// Creates a solver with empty buffers, which grow on the first call.
+    pub fn new() -> Self {
+        ClosestPairSolver {
+            sorted: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Creates a solver with buffers for `capacity` points, so inputs up to that
+    /// size never allocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ClosestPairSolver {
+            sorted: Vec::with_capacity(capacity),
+            scratch: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of points the buffers hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.sorted.capacity().min(self.scratch.capacity())
+    }
+
+    /// Finds the closest pair of points, reusing the solver's buffers.
+    ///
+    /// Finds the same distance as [`closest_pair_optimized`]. When several pairs
+    /// share it, the pair returned may differ.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Vector of points to analyze
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// * The first point of the closest pair
+    /// * The second point of the closest pair
+    /// * The distance between these points as a f32
+    ///
+    /// # Panics
+    ///
+    /// * When the input vector is empty
+    /// * When there's only one point in the vector
+    pub fn solve(&mut self, points: &[Point<T>]) -> (Point<T>, Point<T>, f32) {
+        self.try_solve(points).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Finds the closest pair of points reusing the solver's buffers, without panicking.
+    ///
+    /// Same as [`ClosestPairSolver::solve`], but reports invalid input as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Vector of points to analyze
+    ///
+    /// # Returns
+    ///
+    /// The closest pair and their distance, or a [`ClosestPairError`] when:
+    /// * The input vector is empty
+    /// * There's only one point in the vector
+    pub fn try_solve(
+        &mut self,
+        points: &[Point<T>],
+    ) -> Result<(Point<T>, Point<T>, f32), ClosestPairError> {
+        check_input(points)?;
+
+        self.sorted.clear();
+        self.sorted.extend_from_slice(points);
+        // Unstable, as the stable sort allocates a buffer of its own
+        self.sorted.sort_unstable_by(|a, b| a.x.compare(&b.x));
+
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&self.sorted);
+
+        let (p1, p2, dist_sq) = rec(
+            &mut self.sorted,
+            &mut self.scratch,
+            Metric::Euclidean,
+            DEFAULT_BRUTE_FORCE_CUTOFF,
+        );
+        Ok((p1, p2, Metric::Euclidean.finish::<T>(dist_sq)))
+    }
+}
+
+impl<T: Coordinate> Default for ClosestPairSolver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_solver_tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_optimized() {
+        let mut solver = ClosestPairSolver::new();
+        for seed in 0..10 {
+            let points = random_points(500 + 300 * seed as usize, 1 << 20, seed);
+            let (p1, p2, dist) = solver.solve(&points);
+            assert_eq!(dist, closest_pair_optimized(&points).2);
+            assert_eq!(eucid_distance(&p1, &p2), dist);
+        }
+    }
+
+    #[test]
+    fn test_reuses_buffers() {
+        let mut solver = ClosestPairSolver::with_capacity(1000);
+        assert!(solver.capacity() >= 1000);
+
+        let sorted = solver.sorted.as_ptr();
+        let scratch = solver.scratch.as_ptr();
+        for seed in 0..5 {
+            // Smaller and larger inputs, up to the capacity
+            solver.solve(&random_points(1000 - 200 * seed as usize, 1000, seed));
+            assert_eq!(solver.sorted.as_ptr(), sorted);
+            assert_eq!(solver.scratch.as_ptr(), scratch);
+        }
+    }
+
+    #[test]
+    fn test_grows() {
+        let mut solver = ClosestPairSolver::new();
+        assert_eq!(solver.capacity(), 0);
+        solver.solve(&random_points(100, 1000, 1));
+        assert!(solver.capacity() >= 100);
+    }
+
+    #[test]
+    fn test_float_points() {
+        let mut solver = ClosestPairSolver::new();
+        let points = vec![
+            PointF64 { x: 0.5, y: 0.0 },
+            PointF64 { x: -1.0, y: 0.0 },
+            PointF64 { x: 3.0, y: 0.0 },
+        ];
+        assert_eq!(solver.solve(&points).2, 1.5);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut solver: ClosestPairSolver = ClosestPairSolver::new();
+        assert_eq!(
+            solver.try_solve(&[]).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+        assert_eq!(
+            solver.try_solve(&[Point { x: 1, y: 1 }]).unwrap_err(),
+            ClosestPairError::SinglePoint
+        );
+    }
+}
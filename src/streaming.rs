@@ -0,0 +1,256 @@
+use crate::tracker::ClosestPairTracker;
+use crate::utils::*;
+use std::collections::BTreeMap;
+
+/// Estimates the closest pair of an unbounded stream of points in bounded memory.
+///
+/// Points are snapped to a grid of square cells and each cell keeps only the first
+/// point that landed in it, its representative. A later point in an occupied cell
+/// is compared with the representative and dropped. The representatives go into a
+/// [`ClosestPairTracker`], which keeps the exact closest pair among them. When more
+/// than about `1 / epsilon²` cells are occupied the cells double in size, and the
+/// representatives that now share a cell are compared and all but one dropped.
+///
+/// Every point seen shares a cell with a kept representative, so with cells of
+/// side `c` the estimate is at most the true closest distance plus `2√2 c`, and
+/// it's always the exact distance of a real pair of stream points, so never below
+/// the true distance. Cells start 1 wide, where only equal points share a cell, so
+/// the estimate is exact until the first coarsening. After that, for points spread
+/// over a square of side `L`, `c` stays around `2 epsilon L`: a smaller `epsilon`
+/// keeps the error down at the cost of `1 / epsilon²` stored points.
+///
+/// No bounded memory estimator can promise a relative error on every stream, as
+/// telling whether a stream repeats a point needs memory for every distinct point.
+/// Streams whose closest pairs are spread all over, such as samples of a lattice,
+/// usually get within a small factor, since each representative meets many near
+/// points before they're dropped.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::streaming::StreamingClosestPair;
+///
+/// let mut stream = StreamingClosestPair::new(0.5);
+/// assert!(stream.estimate().is_none());
+///
+/// for p in [Point { x: 0, y: 0 }, Point { x: 10, y: 0 }, Point { x: 3, y: 4 }] {
+///     stream.push(p);
+/// }
+/// assert_eq!(stream.estimate().unwrap().2, 5.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamingClosestPair {
+    /// Most cells allowed to be occupied before they're coarsened.
+    capacity: usize,
+    /// Cells are `2^level` wide.
+    level: u32,
+    /// Representative of each occupied cell.
+    cells: BTreeMap<(u64, u64), Point>,
+    representatives: ClosestPairTracker,
+    /// Closest pair among the comparisons with dropped points, with its squared distance.
+    dropped_best: Option<(Point, Point, u128)>,
+}
+
+impl StreamingClosestPair {
+    /// Creates an empty estimator.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - Size of the cells relative to the spread of the points once
+    ///   they need coarsening. Up to `1 / epsilon²` points are stored
+    ///
+    /// # Panics
+    ///
+    /// * When `epsilon` is zero, negative or NaN
+    pub fn new(epsilon: f32) -> Self {
+        assert!(
+            epsilon > 0.0,
+            "Epsilon must be a positive number, got {epsilon}"
+        );
+        let capacity = (1.0 / (epsilon as f64 * epsilon as f64)).ceil().max(1.0) as usize;
+
+        StreamingClosestPair {
+            capacity,
+            level: 0,
+            cells: BTreeMap::new(),
+            representatives: ClosestPairTracker::new(),
+            dropped_best: None,
+        }
+    }
+
+    /// Adds the next point of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point to add
+    pub fn push(&mut self, p: Point) {
+        let key = self.cell_of(&p);
+        if let Some(&rep) = self.cells.get(&key) {
+            self.compare(rep, p);
+            return;
+        }
+
+        self.cells.insert(key, p);
+        self.representatives.insert(p);
+        if self.cells.len() > self.capacity {
+            self.coarsen();
+        }
+    }
+
+    /// Returns the estimated closest pair of the points pushed so far.
+    ///
+    /// # Returns
+    ///
+    /// Two points of the stream and the distance between them as a f32, which is
+    /// at least the true closest distance and at most `2√2` cell sizes more. `None`
+    /// until at least two points are pushed.
+    pub fn estimate(&self) -> Option<(Point, Point, f32)> {
+        let kept = self
+            .representatives
+            .current()
+            .map(|(p1, p2, _)| (p1, p2, eucid_distance_sq(&p1, &p2)));
+
+        let best = match (kept, self.dropped_best) {
+            (Some(a), Some(b)) => Some(if b.2 < a.2 { b } else { a }),
+            (a, b) => a.or(b),
+        };
+        best.map(|(p1, p2, dist_sq)| (p1, p2, Metric::Euclidean.finish::<u32>(dist_sq)))
+    }
+
+    /// Current side length of a grid cell.
+    pub fn cell_size(&self) -> f64 {
+        (1u64 << self.level) as f64
+    }
+
+    /// Number of points currently stored, at most about `1 / epsilon²`.
+    pub fn stored(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Doubles the cells until few enough are occupied, keeping one representative
+    /// per cell.
+    fn coarsen(&mut self) {
+        while self.cells.len() > self.capacity {
+            self.level += 1;
+
+            let old = std::mem::take(&mut self.cells);
+            for (_, rep) in old {
+                let key = self.cell_of(&rep);
+                match self.cells.get(&key) {
+                    Some(&kept) => self.compare(kept, rep),
+                    None => {
+                        self.cells.insert(key, rep);
+                    }
+                }
+            }
+        }
+
+        self.representatives = ClosestPairTracker::new();
+        for &rep in self.cells.values() {
+            self.representatives.insert(rep);
+        }
+    }
+
+    /// Records the pair if it's closer than any dropped pair so far.
+    fn compare(&mut self, p1: Point, p2: Point) {
+        let dist_sq = eucid_distance_sq(&p1, &p2);
+        if self.dropped_best.is_none_or(|(_, _, d)| dist_sq < d) {
+            self.dropped_best = Some((p1, p2, dist_sq));
+        }
+    }
+
+    /// Grid cell that holds `p`.
+    fn cell_of(&self, p: &Point) -> (u64, u64) {
+        ((p.x as u64) >> self.level, (p.y as u64) >> self.level)
+    }
+}
+
+#[cfg(test)]
+mod streaming_closest_pair_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_optimized;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_exact_before_coarsening() {
+        let points = random_points(100, 10_000, 5);
+        let mut stream = StreamingClosestPair::new(0.05);
+        for &p in &points {
+            stream.push(p);
+        }
+
+        assert_eq!(stream.cell_size(), 1.0);
+        assert_eq!(
+            stream.estimate().unwrap().2,
+            closest_pair_optimized(&points).2
+        );
+    }
+
+    #[test]
+    fn test_duplicate_point() {
+        let mut stream = StreamingClosestPair::new(0.5);
+        stream.push(Point { x: 7, y: 7 });
+        assert!(stream.estimate().is_none());
+
+        stream.push(Point { x: 7, y: 7 });
+        assert_eq!(stream.stored(), 1);
+        assert_eq!(
+            stream.estimate(),
+            Some((Point { x: 7, y: 7 }, Point { x: 7, y: 7 }, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_within_bound_on_long_stream() {
+        let points = random_points(50_000, 1_000_000, 6);
+        let mut stream = StreamingClosestPair::new(0.05);
+
+        for (i, &p) in points.iter().enumerate() {
+            stream.push(p);
+            assert!(stream.stored() <= 400);
+
+            if i % 10_000 == 9_999 {
+                let (p1, p2, estimate) = stream.estimate().unwrap();
+                let (_, _, exact) = closest_pair_optimized(&points[..=i]);
+                assert_eq!(eucid_distance(&p1, &p2), estimate);
+                assert!(exact <= estimate);
+                let slack = 2.0 * 2.0f64.sqrt() * stream.cell_size();
+                assert!((estimate as f64) <= exact as f64 + slack);
+            }
+        }
+        assert!(stream.cell_size() > 1.0);
+    }
+
+    #[test]
+    fn test_within_factor_on_lattice() {
+        // Every site of a 300x300 lattice 50 apart, in random order, so the
+        // closest distance is 50 while far more points than fit are pushed
+        let mut points: Vec<Point> = (0..300)
+            .flat_map(|x| {
+                (0..300).map(move |y| Point {
+                    x: 50 * x,
+                    y: 50 * y,
+                })
+            })
+            .collect();
+        points.shuffle(&mut StdRng::seed_from_u64(7));
+
+        let mut stream = StreamingClosestPair::new(0.1);
+        for &p in &points {
+            stream.push(p);
+        }
+
+        assert!(stream.stored() <= 100);
+        let (_, _, estimate) = stream.estimate().unwrap();
+        assert!((50.0..=100.0).contains(&estimate), "estimate = {estimate}");
+    }
+
+    #[test]
+    #[should_panic(expected = "Epsilon must be a positive number")]
+    fn test_zero_epsilon_panics() {
+        StreamingClosestPair::new(0.0);
+    }
+}
@@ -0,0 +1,459 @@
+use crate::algorithms::{Candidate, CellGrid};
+use crate::utils::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps track of the closest pair of points while points are inserted and removed.
+///
+/// Every point records a neighbour, the nearest other point when it was last
+/// searched for, and the closest of these recorded pairs is the closest pair. That
+/// holds as long as, for any two points, at least one has recorded a neighbour no
+/// farther than the other point. An inserted point searches for its nearest
+/// neighbour, which covers its pairs with every point already held. A removed point
+/// only invalidates the records of the points that recorded it, so those search
+/// again, and every other record still holds. The records sit in a heap ordered by
+/// distance, so the closest one is always on top.
+///
+/// Searches go through a grid of square cells sized for about one point per cell
+/// over the bounding box of the points, rebuilt with every record searched again
+/// whenever the number of points held has doubled or halved since the last build.
+/// For points spread evenly over an area a search takes O(1) expected time, so an
+/// insert takes O(log n) expected amortized time for the heap, and so does a
+/// removal, times the number of points that recorded the removed one: at most a
+/// handful in practice, as a point's nearest neighbour can only be the nearest
+/// neighbour of a few others. Clustered points crowd into a few cells, where
+/// searches slow down towards O(n). Removed points stay in memory until the next
+/// rebuild.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::tracker::ClosestPairTracker;
+///
+/// let mut tracker = ClosestPairTracker::new();
+/// tracker.insert(Point { x: 0, y: 0 });
+/// assert!(tracker.current().is_none());
+///
+/// tracker.insert(Point { x: 10, y: 0 });
+/// assert_eq!(tracker.current().unwrap().2, 10.0);
+///
+/// tracker.insert(Point { x: 3, y: 4 });
+/// assert_eq!(tracker.current().unwrap().2, 5.0);
+///
+/// assert!(tracker.remove(Point { x: 3, y: 4 }));
+/// assert_eq!(tracker.current().unwrap().2, 10.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClosestPairTracker<T: Coordinate = u32> {
+    points: Vec<Point<T>>,
+    /// Whether the point at the same index in `points` was removed.
+    removed: Vec<bool>,
+    /// Each point's recorded neighbour with their squared distance, `None` when
+    /// there was no other point to record.
+    neighbor: Vec<Option<(usize, T::Distance)>>,
+    /// The points that recorded each point as their neighbour, including some
+    /// that have recorded another one since.
+    recorded_by: Vec<Vec<usize>>,
+    /// Recorded pairs, closest first, including some no longer recorded.
+    pairs: BinaryHeap<Reverse<Candidate<T::Distance>>>,
+    /// Indices into `points` of the closest pair, with their squared distance.
+    best: Option<(usize, usize, T::Distance)>,
+    grid: CellGrid,
+    /// Number of points not removed.
+    held: usize,
+    /// Number of points held when the grid was last built.
+    built_for: usize,
+}
+
+impl<T: Coordinate> ClosestPairTracker<T> {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        ClosestPairTracker {
+            points: Vec::new(),
+            removed: Vec::new(),
+            neighbor: Vec::new(),
+            recorded_by: Vec::new(),
+            pairs: BinaryHeap::new(),
+            best: None,
+            grid: CellGrid::new(1.0, 0),
+            held: 0,
+            built_for: 0,
+        }
+    }
+
+    /// Adds a point, updating the closest pair if the new point is part of a closer one.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point to add
+    pub fn insert(&mut self, p: Point<T>) {
+        let idx = self.points.len();
+        self.points.push(p);
+        self.removed.push(false);
+        self.neighbor.push(None);
+        self.recorded_by.push(Vec::new());
+        self.held += 1;
+
+        if self.held > 2 * self.built_for {
+            self.rebuild();
+        } else {
+            self.search(idx);
+            self.grid.insert(self.grid.key(&p), idx);
+        }
+        self.update_best();
+    }
+
+    /// Removes a point, updating the closest pair if it was part of it.
+    ///
+    /// When the point was inserted more than once only one copy is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point to remove
+    ///
+    /// # Returns
+    ///
+    /// `true` if the point was found and removed, `false` if it isn't in the tracker.
+    pub fn remove(&mut self, p: Point<T>) -> bool {
+        let key = self.grid.key(&p);
+        let Some(idx) = self.grid.find_in(key, &mut |j| self.points[j] == p) else {
+            return false;
+        };
+
+        self.grid.remove(key, idx);
+        self.removed[idx] = true;
+        self.neighbor[idx] = None;
+        self.held -= 1;
+
+        if self.held < self.built_for / 2 {
+            self.rebuild();
+        } else {
+            for i in std::mem::take(&mut self.recorded_by[idx]) {
+                if self.neighbor[i].is_some_and(|(j, _)| j == idx) {
+                    self.search(i);
+                }
+            }
+        }
+        self.update_best();
+        true
+    }
+
+    /// Returns the closest pair of the points in the tracker.
+    ///
+    /// # Returns
+    ///
+    /// The earlier inserted point of the closest pair, the later one, and the
+    /// distance between them as a f32. `None` while fewer than two points are held.
+    pub fn current(&self) -> Option<(Point<T>, Point<T>, f32)> {
+        self.best.map(|(i, j, dist_sq)| {
+            (
+                self.points[i],
+                self.points[j],
+                Metric::Euclidean.finish::<T>(dist_sq),
+            )
+        })
+    }
+
+    /// Records the nearest neighbour of the point at `idx` among the points in the grid.
+    fn search(&mut self, idx: usize) {
+        let p = self.points[idx];
+        let found = self.grid.nearest(&p, |j| {
+            (j != idx).then(|| eucid_distance_sq(&self.points[j], &p))
+        });
+
+        self.neighbor[idx] = found;
+        if let Some((j, dist)) = found {
+            self.recorded_by[j].push(idx);
+            self.pairs.push(Reverse(Candidate {
+                dist,
+                i: idx.min(j),
+                j: idx.max(j),
+            }));
+        }
+    }
+
+    /// Drops the stale pairs off the top of the heap and takes the closest pair from it.
+    fn update_best(&mut self) {
+        let recorded = |i: usize, j: usize, dist| self.neighbor[i] == Some((j, dist));
+
+        self.best = None;
+        while let Some(Reverse(top)) = self.pairs.peek() {
+            let (i, j, dist) = (top.i, top.j, top.dist);
+            if !self.removed[i]
+                && !self.removed[j]
+                && (recorded(i, j, dist) || recorded(j, i, dist))
+            {
+                self.best = Some((i, j, dist));
+                return;
+            }
+            self.pairs.pop();
+        }
+    }
+
+    /// Drops the removed points, sizes the grid for the rest and searches every
+    /// neighbour again.
+    fn rebuild(&mut self) {
+        let removed = std::mem::take(&mut self.removed);
+        let mut flags = removed.iter();
+        self.points.retain(|_| !flags.next().unwrap());
+
+        let n = self.points.len();
+        self.removed = vec![false; n];
+        self.neighbor = vec![None; n];
+        self.recorded_by = vec![Vec::new(); n];
+        self.pairs.clear();
+        self.held = n;
+        self.built_for = n;
+
+        // Cells of about one point each over the bounding box, or along it when the
+        // points are all on one line
+        let (mut lo, mut hi) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        for p in &self.points {
+            for (axis, v) in [p.x.to_f64(), p.y.to_f64()].into_iter().enumerate() {
+                lo[axis] = lo[axis].min(v);
+                hi[axis] = hi[axis].max(v);
+            }
+        }
+        let (width, height) = (hi[0] - lo[0], hi[1] - lo[1]);
+        let mut cell = (width * height / n as f64).sqrt();
+        if !(cell > 0.0 && cell.is_finite()) {
+            cell = width.max(height) / n as f64;
+        }
+        if !(cell > 0.0 && cell.is_finite()) {
+            cell = 1.0;
+        }
+
+        self.grid = CellGrid::new(cell, n);
+        for idx in 0..n {
+            self.search(idx);
+            self.grid.insert(self.grid.key(&self.points[idx]), idx);
+        }
+    }
+}
+
+impl<T: Coordinate> Default for ClosestPairTracker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod closest_pair_tracker_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_brute_force;
+
+    #[test]
+    fn test_fewer_than_two_points() {
+        let mut tracker = ClosestPairTracker::new();
+        assert!(tracker.current().is_none());
+
+        tracker.insert(Point { x: 5, y: 5 });
+        assert!(tracker.current().is_none());
+    }
+
+    #[test]
+    fn test_closest_pair_changes() {
+        let mut tracker = ClosestPairTracker::new();
+        let mut distances = Vec::new();
+
+        // Each insert lands closer to an existing point than the last closest pair
+        let points: Vec<Point> = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1000, y: 1000 },
+            Point { x: 0, y: 500 },
+            Point { x: 900, y: 1000 },
+            Point { x: 5000, y: 5000 },
+            Point { x: 3, y: 504 },
+            Point { x: 4, y: 504 },
+        ];
+        for &p in &points {
+            tracker.insert(p);
+            if let Some((_, _, dist)) = tracker.current() {
+                distances.push(dist);
+            }
+        }
+
+        let expected = [
+            (2_000_000.0f64).sqrt() as f32,
+            500.0,
+            100.0,
+            100.0,
+            5.0,
+            1.0,
+        ];
+        assert_eq!(distances, expected);
+
+        let (p1, p2, _) = tracker.current().unwrap();
+        assert_eq!((p1, p2), (points[5], points[6]));
+    }
+
+    #[test]
+    fn test_duplicate_point() {
+        let mut tracker = ClosestPairTracker::new();
+        tracker.insert(Point { x: 7, y: 7 });
+        tracker.insert(Point { x: 9, y: 9 });
+        tracker.insert(Point { x: 7, y: 7 });
+        tracker.insert(Point { x: 8, y: 8 });
+
+        let (p1, p2, dist) = tracker.current().unwrap();
+        assert_eq!(p1, p2);
+        assert_eq!(dist, 0.0);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let points = random_points(500, 100_000, 11);
+        let mut tracker = ClosestPairTracker::new();
+
+        for (i, &p) in points.iter().enumerate() {
+            tracker.insert(p);
+            if i >= 1 {
+                let (_, _, bf_dist) = closest_pair_brute_force(&points[..=i]);
+                assert_eq!(tracker.current().unwrap().2, bf_dist);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_and_float() {
+        let mut tracker = ClosestPairTracker::new();
+        for p in [
+            Pointi { x: -5, y: -5 },
+            Pointi { x: 5, y: 5 },
+            Pointi { x: -1, y: 0 },
+            Pointi { x: 1, y: 0 },
+        ] {
+            tracker.insert(p);
+        }
+        assert_eq!(tracker.current().unwrap().2, 2.0);
+
+        let mut tracker = ClosestPairTracker::new();
+        for p in [
+            PointF64 { x: -0.5, y: 0.25 },
+            PointF64 { x: 3.0, y: 3.0 },
+            PointF64 { x: -0.75, y: 0.25 },
+        ] {
+            tracker.insert(p);
+        }
+        assert_eq!(tracker.current().unwrap().2, 0.25);
+    }
+
+    #[test]
+    fn test_remove_missing_point() {
+        let mut tracker = ClosestPairTracker::new();
+        assert!(!tracker.remove(Point { x: 1, y: 1 }));
+
+        tracker.insert(Point { x: 1, y: 1 });
+        tracker.insert(Point { x: 4, y: 5 });
+        assert!(!tracker.remove(Point { x: 2, y: 2 }));
+        assert_eq!(tracker.current().unwrap().2, 5.0);
+
+        assert!(tracker.remove(Point { x: 4, y: 5 }));
+        assert!(tracker.current().is_none());
+        assert!(tracker.remove(Point { x: 1, y: 1 }));
+        assert!(!tracker.remove(Point { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn test_remove_duplicate() {
+        let mut tracker = ClosestPairTracker::new();
+        tracker.insert(Point { x: 7, y: 7 });
+        tracker.insert(Point { x: 7, y: 7 });
+        tracker.insert(Point { x: 10, y: 11 });
+        assert_eq!(tracker.current().unwrap().2, 0.0);
+
+        assert!(tracker.remove(Point { x: 7, y: 7 }));
+        assert_eq!(tracker.current().unwrap().2, 5.0);
+    }
+
+    #[test]
+    fn test_insert_and_remove_match_brute_force() {
+        let points = random_points(400, 100_000, 12);
+        let mut tracker = ClosestPairTracker::new();
+        let mut held: Vec<Point> = Vec::new();
+
+        for (i, &p) in points.iter().enumerate() {
+            tracker.insert(p);
+            held.push(p);
+
+            // Every third step also takes out a point, alternating between the
+            // closest pair and the oldest point still held
+            if i % 3 == 2 {
+                let gone = if i % 2 == 0 {
+                    tracker.current().unwrap().0
+                } else {
+                    held[0]
+                };
+                assert!(tracker.remove(gone));
+                let pos = held.iter().position(|&q| q == gone).unwrap();
+                held.remove(pos);
+            }
+
+            match tracker.current() {
+                Some((_, _, dist)) => {
+                    let (_, _, bf_dist) = closest_pair_brute_force(&held);
+                    assert_eq!(dist, bf_dist);
+                }
+                None => assert!(held.len() < 2),
+            }
+        }
+
+        // Greedy matching: keep taking out the closest pair
+        while let Some((p1, p2, dist)) = tracker.current() {
+            let (_, _, bf_dist) = closest_pair_brute_force(&held);
+            assert_eq!(dist, bf_dist);
+            for gone in [p1, p2] {
+                assert!(tracker.remove(gone));
+                let pos = held.iter().position(|&q| q == gone).unwrap();
+                held.remove(pos);
+            }
+        }
+        assert!(held.len() < 2);
+    }
+
+    #[test]
+    fn test_crowded_points_match_brute_force() {
+        // Few distinct spots, so most points have copies and cells hold many points
+        let points = random_points(300, 12, 13);
+        let mut tracker = ClosestPairTracker::new();
+        let mut held: Vec<Point> = Vec::new();
+
+        for (i, &p) in points.iter().enumerate() {
+            tracker.insert(p);
+            held.push(p);
+            if i % 2 == 1 {
+                let gone = held[i % held.len()];
+                assert!(tracker.remove(gone));
+                let pos = held.iter().position(|&q| q == gone).unwrap();
+                held.remove(pos);
+            }
+
+            match tracker.current() {
+                Some((_, _, dist)) => assert_eq!(dist, closest_pair_brute_force(&held).2),
+                None => assert!(held.len() < 2),
+            }
+        }
+    }
+
+    #[test]
+    fn test_greedy_matching_on_many_points() {
+        // Taking out the closest pair can only leave pairs as far apart or farther
+        let points = random_points(20_000, 1_000_000, 14);
+        let mut tracker = ClosestPairTracker::new();
+        for &p in &points {
+            tracker.insert(p);
+        }
+
+        let mut last = 0.0;
+        let mut matched = 0;
+        while let Some((p1, p2, dist)) = tracker.current() {
+            assert!(dist >= last);
+            assert_eq!(eucid_distance(&p1, &p2), dist);
+            last = dist;
+            assert!(tracker.remove(p1) && tracker.remove(p2));
+            matched += 1;
+        }
+        assert_eq!(matched, 10_000);
+    }
+}
@@ -1,11 +1,665 @@
-/// A 2D point with unsigned integer coordinates.
+use crate::error::ClosestPairError;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Mul};
+
+/// A 2D point.
+///
+/// The coordinate type defaults to `u32`. Signed integers such as `i32` can be used
+/// for points that lie in any quadrant of the Cartesian plane, and floating point
+/// types such as `f64` for real-valued data like GPS positions.
+///
+/// Points compare, sort and hash like the tuple `(x, y)`: ordering looks at `x`
+/// first and only falls back to `y` when the `x` coordinates are equal. Integer
+/// points are therefore `Eq`, `Ord` and `Hash`, and can be deduplicated or stored
+/// in sets and maps. Floating point points only get `PartialEq` and `PartialOrd`.
+/// The default point is the origin.
+///
+/// With the `serde` feature enabled, points serialize as `{"x": 3, "y": 4}`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+///
+/// let mut points = vec![Point { x: 2, y: 0 }, Point { x: 1, y: 5 }, Point { x: 1, y: 3 }];
+/// points.sort();
+/// assert_eq!(points, vec![Point { x: 1, y: 3 }, Point { x: 1, y: 5 }, Point { x: 2, y: 0 }]);
+/// ```
 // #[repr(C)]
-#[derive(Debug, Clone, Copy)]
-pub struct Point {
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point<T = u32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Formats the point as `(x, y)`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+///
+/// assert_eq!(Point { x: 3, y: 4 }.to_string(), "(3, 4)");
+/// ```
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Converts `[x, y]` into a point.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+///
+/// assert_eq!(Point::from([3, 4]), Point { x: 3, y: 4 });
+/// ```
+impl<T> From<[T; 2]> for Point<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Point { x, y }
+    }
+}
+
+/// Converts a point into `[x, y]`, such as for handing it over FFI.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+///
+/// let coords: [u32; 2] = Point { x: 3, y: 4 }.into();
+/// assert_eq!(coords, [3, 4]);
+/// ```
+impl<T> From<Point<T>> for [T; 2] {
+    fn from(p: Point<T>) -> Self {
+        [p.x, p.y]
+    }
+}
+
+/// Distances to another point, as methods delegating to the free functions.
+impl<T: Coordinate> Point<T> {
+    /// The Euclidean distance to `other`, see [`eucid_distance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use closest_pair_rs::utils::Point;
+    ///
+    /// let a: Point = Point { x: 0, y: 0 };
+    /// assert_eq!(a.euclidean_to(&Point { x: 3, y: 4 }), 5.0);
+    /// ```
+    #[inline]
+    pub fn euclidean_to(&self, other: &Point<T>) -> f32 {
+        eucid_distance(self, other)
+    }
+
+    /// The exact squared Euclidean distance to `other`, see [`eucid_distance_sq`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use closest_pair_rs::utils::Point;
+    ///
+    /// let a: Point = Point { x: 0, y: 0 };
+    /// assert_eq!(a.squared_euclidean_to(&Point { x: 3, y: 4 }), 25);
+    /// ```
+    #[inline]
+    pub fn squared_euclidean_to(&self, other: &Point<T>) -> T::Distance {
+        eucid_distance_sq(self, other)
+    }
+
+    /// The Manhattan distance to `other`, see [`manhattan_distance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use closest_pair_rs::utils::Point;
+    ///
+    /// let a: Point = Point { x: 0, y: 0 };
+    /// assert_eq!(a.manhattan_to(&Point { x: 3, y: 4 }), 7);
+    /// ```
+    #[inline]
+    pub fn manhattan_to(&self, other: &Point<T>) -> T::Distance {
+        manhattan_distance(self, other)
+    }
+
+    /// The Chebyshev distance to `other`, see [`chebyshev_distance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use closest_pair_rs::utils::Point;
+    ///
+    /// let a: Point = Point { x: 0, y: 0 };
+    /// assert_eq!(a.chebyshev_to(&Point { x: 3, y: 4 }), 4);
+    /// ```
+    #[inline]
+    pub fn chebyshev_to(&self, other: &Point<T>) -> T::Distance {
+        chebyshev_distance(self, other)
+    }
+}
+
+/// A 2D point with unsigned `u32` coordinates, the same as plain [`Point`].
+pub type PointU32 = Point<u32>;
+
+/// A 2D point with unsigned `u64` coordinates, for ranges beyond `u32::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = PointU64 { x: 1 << 40, y: 0 };
+/// let p2 = PointU64 { x: (1 << 40) + 3, y: 4 };
+/// assert_eq!(eucid_distance_sq(&p1, &p2), WideDistance::from(25));
+/// assert_eq!(eucid_distance(&p1, &p2), 5.0);
+/// ```
+pub type PointU64 = Point<u64>;
+
+/// A 2D point with signed `i32` coordinates.
+pub type Pointi = Point<i32>;
+
+/// A 2D point with `f64` coordinates.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::{approx_eq, PointF64, DISTANCE_EPSILON};
+/// use closest_pair_rs::algorithms::closest_pair_optimized;
+///
+/// let points = vec![
+///     PointF64 { x: 52.52, y: 13.40 },
+///     PointF64 { x: 48.85, y: 2.35 },
+///     PointF64 { x: 52.37, y: 4.90 },
+///     PointF64 { x: 50.85, y: 4.35 }
+/// ];
+/// // Amsterdam and Brussels
+/// let (_, _, distance) = closest_pair_optimized(&points);
+/// assert!(approx_eq(distance, 1.6165, DISTANCE_EPSILON));
+/// ```
+pub type PointF64 = Point<f64>;
+
+/// A 3D point.
+///
+/// Works with the same coordinate types as [`Point`], and defaults to `u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point3<T = u32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// Formats the point as `(x, y, z)`.
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// A point in `D` dimensions.
+///
+/// Works with the same coordinate types as [`Point`], and defaults to `u32`. Only
+/// brute force runs on these, as the divide and conquer algorithms split the plane
+/// and are 2D only. Convert to and from [`Point`] and [`Point3`] with `From`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<const D: usize, T = u32> {
+    pub coords: [T; D],
+}
+
+impl<T: Copy> PointN<2, T> {
+    /// The first coordinate.
+    #[inline]
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    /// The second coordinate.
+    #[inline]
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+}
+
+impl<T> From<Point<T>> for PointN<2, T> {
+    fn from(p: Point<T>) -> Self {
+        PointN { coords: [p.x, p.y] }
+    }
+}
+
+impl<T: Copy> From<PointN<2, T>> for Point<T> {
+    fn from(p: PointN<2, T>) -> Self {
+        Point { x: p.x(), y: p.y() }
+    }
+}
+
+impl<T> From<Point3<T>> for PointN<3, T> {
+    fn from(p: Point3<T>) -> Self {
+        PointN {
+            coords: [p.x, p.y, p.z],
+        }
+    }
+}
+
+/// Formats the point as `(c0, c1, ...)`.
+impl<const D: usize, T: fmt::Display> fmt::Display for PointN<D, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, c) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{c}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A position on the Earth's surface, in degrees.
+///
+/// Latitude runs from -90 (south pole) to 90 (north pole) and longitude from
+/// -180 to 180, east of Greenwich being positive. Used by `closest_pair_geo`,
+/// which measures great-circle distances with [`haversine_distance`].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeoPoint {
+    pub lat: f32,
+    pub lon: f32,
+}
+
+/// A 2D point carrying a weight, such as a mass or a radius.
+///
+/// Used by `closest_pair_weighted`, where a [`WeightMode`] decides how the
+/// weights change the distance between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightedPoint {
     pub x: u32,
     pub y: u32,
+    pub w: f32,
+}
+
+impl WeightedPoint {
+    /// The location of the point, without its weight.
+    #[inline]
+    pub fn point(&self) -> Point {
+        Point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// A 2D point carrying a label, such as an ID or any other payload.
+///
+/// Used by `closest_pair_labeled`, which measures distances between the
+/// locations only and hands the labels back with the closest pair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabeledPoint<L> {
+    pub x: u32,
+    pub y: u32,
+    pub label: L,
+}
+
+impl<L> LabeledPoint<L> {
+    /// The location of the point, without its label.
+    #[inline]
+    pub fn point(&self) -> Point {
+        Point {
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// Anything with a 2D location in `u32` coordinates.
+///
+/// Lets `closest_pair_positioned` run on your own types, such as a struct with
+/// a name and a position, without converting them to [`Point`] first.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Positioned;
+///
+/// struct Sensor {
+///     id: u16,
+///     col: u32,
+///     row: u32,
+/// }
+///
+/// impl Positioned for Sensor {
+///     fn x(&self) -> u32 {
+///         self.col
+///     }
+///
+///     fn y(&self) -> u32 {
+///         self.row
+///     }
+/// }
+///
+/// let sensor = Sensor { id: 7, col: 3, row: 4 };
+/// assert_eq!((sensor.x(), sensor.y()), (3, 4));
+/// ```
+pub trait Positioned {
+    /// The x coordinate of the item.
+    fn x(&self) -> u32;
+
+    /// The y coordinate of the item.
+    fn y(&self) -> u32;
+}
+
+impl Positioned for Point {
+    #[inline]
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[inline]
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+impl Positioned for WeightedPoint {
+    #[inline]
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[inline]
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+impl<L> Positioned for LabeledPoint<L> {
+    #[inline]
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[inline]
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+/// Numeric types that can be used as point coordinates.
+///
+/// Implemented for `u8`, `u16`, `u32`, `u64`, `i8`, `i16`, `i32`, `i64`, `f32` and `f64`.
+/// Floating point coordinates must not be NaN.
+pub trait Coordinate: Copy + PartialOrd + Send + Sync + fmt::Debug {
+    /// Type that distances (and squared distances) between coordinates are measured in.
+    ///
+    /// Integer coordinates of up to 16 bits use `u64` and 32-bit ones `u128`, so
+    /// distances are exact and never overflow, even between opposite corners of the
+    /// coordinate range. `u64` and `i64` use [`WideDistance`], which holds any sum
+    /// of their squared differences exactly. Floating point coordinates use `f64`.
+    type Distance: Copy
+        + PartialOrd
+        + Add<Output = Self::Distance>
+        + Mul<Output = Self::Distance>
+        + Send
+        + Sync
+        + fmt::Debug;
+
+    /// Absolute difference between two coordinates.
+    fn axis_distance(self, other: Self) -> Self::Distance;
+
+    /// Converts a distance into an `f64`.
+    fn distance_to_f64(distance: Self::Distance) -> f64;
+
+    /// Converts the coordinate into an `f64`.
+    fn to_f64(self) -> f64;
+
+    /// Total order used when sorting points by coordinate.
+    fn compare(&self, other: &Self) -> Ordering;
+
+    /// Sign of the cross product of the vectors `a -> b` and `c -> d`.
+    ///
+    /// `Greater` means `c -> d` turns counter-clockwise from `a -> b`, `Less`
+    /// clockwise, and `Equal` that the vectors are parallel.
+    fn cross_sign(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Ordering;
+}
+
+macro_rules! impl_integer_coordinate {
+    ($d:ty: $($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                type Distance = $d;
+
+                #[inline]
+                fn axis_distance(self, other: Self) -> $d {
+                    // Widen to i64, so the difference never overflows
+                    (self as i64).abs_diff(other as i64) as $d
+                }
+
+                #[inline]
+                fn distance_to_f64(distance: $d) -> f64 {
+                    distance as f64
+                }
+
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn compare(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+
+                #[inline]
+                fn cross_sign(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Ordering {
+                    // Differences fit in 33 bits and products in 66, so i128 is exact
+                    let (ux, uy) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+                    let (vx, vy) = (d.x as i128 - c.x as i128, d.y as i128 - c.y as i128);
+                    (ux * vy).cmp(&(uy * vx))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                type Distance = f64;
+
+                #[inline]
+                fn axis_distance(self, other: Self) -> f64 {
+                    (self as f64 - other as f64).abs()
+                }
+
+                #[inline]
+                fn distance_to_f64(distance: f64) -> f64 {
+                    distance
+                }
+
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn compare(&self, other: &Self) -> Ordering {
+                    self.total_cmp(other)
+                }
+
+                #[inline]
+                fn cross_sign(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Ordering {
+                    let (ux, uy) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+                    let (vx, vy) = (d.x as f64 - c.x as f64, d.y as f64 - c.y as f64);
+                    (ux * vy).total_cmp(&(uy * vx))
+                }
+            }
+        )*
+    };
+}
+
+/// Distance type of `u64` and `i64` coordinates, an unsigned integer of 256 bits.
+///
+/// A squared difference of 64-bit coordinates takes up to 128 bits, and the sum of
+/// two needs one more, so a `u128` alone would overflow between far apart points.
+/// The bits past 128 are kept in a second `u128`. Values compare like the numbers
+/// they hold, and convert from a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = PointU64 { x: 0, y: 0 };
+/// let p2 = PointU64 { x: u64::MAX, y: u64::MAX };
+/// let side = WideDistance::from((u64::MAX as u128).pow(2));
+/// assert_eq!(eucid_distance_sq(&p1, &p2), side + side);
+/// assert!(eucid_distance_sq(&p1, &p2) > WideDistance::from(u128::MAX));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WideDistance {
+    // Field order makes the derived ordering compare `hi` first
+    hi: u128,
+    lo: u128,
+}
+
+impl WideDistance {
+    /// Converts the distance into an `f64`, rounding it to the nearest one.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        // 2^128, exact in an f64
+        const HI_UNIT: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+        self.hi as f64 * HI_UNIT + self.lo as f64
+    }
+}
+
+impl From<u128> for WideDistance {
+    #[inline]
+    fn from(lo: u128) -> Self {
+        WideDistance { hi: 0, lo }
+    }
+}
+
+impl Add for WideDistance {
+    type Output = WideDistance;
+
+    #[inline]
+    fn add(self, other: WideDistance) -> WideDistance {
+        let (lo, carry) = self.lo.overflowing_add(other.lo);
+        WideDistance {
+            hi: self.hi + other.hi + carry as u128,
+            lo,
+        }
+    }
+}
+
+impl Mul for WideDistance {
+    type Output = WideDistance;
+
+    /// Panics when the product doesn't fit in 256 bits.
+    #[inline]
+    fn mul(self, other: WideDistance) -> WideDistance {
+        let (hi, lo) = widening_mul(self.lo, other.lo);
+        let cross = match (self.hi, other.hi) {
+            (0, 0) => Some(0),
+            (0, h) => h.checked_mul(self.lo),
+            (h, 0) => h.checked_mul(other.lo),
+            _ => None,
+        };
+        let hi = cross
+            .and_then(|c| c.checked_add(hi))
+            .expect("attempt to multiply with overflow");
+        WideDistance { hi, lo }
+    }
+}
+
+/// Full product of two `u128`s, as its high and low halves.
+#[inline]
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    let low_half = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & low_half);
+    let (b1, b0) = (b >> 64, b & low_half);
+
+    // a * b = a1 b1 2^128 + (a1 b0 + a0 b1) 2^64 + a0 b0, where the middle sum
+    // can carry into a 129th bit
+    let (mid, mid_carry) = (a1 * b0).overflowing_add(a0 * b1);
+    let (lo, lo_carry) = (a0 * b0).overflowing_add(mid << 64);
+    let hi = a1 * b1 + (mid >> 64) + ((mid_carry as u128) << 64) + lo_carry as u128;
+    (hi, lo)
+}
+
+macro_rules! impl_wide_integer_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                type Distance = WideDistance;
+
+                #[inline]
+                fn axis_distance(self, other: Self) -> WideDistance {
+                    WideDistance::from(self.abs_diff(other) as u128)
+                }
+
+                #[inline]
+                fn distance_to_f64(distance: WideDistance) -> f64 {
+                    distance.to_f64()
+                }
+
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn compare(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+
+                #[inline]
+                fn cross_sign(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Ordering {
+                    // Differences fit in 65 bits, but products need 130, so compare
+                    // them as a sign and a u128 magnitude
+                    let (ux, uy) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+                    let (vx, vy) = (d.x as i128 - c.x as i128, d.y as i128 - c.y as i128);
+                    compare_products((ux, vy), (uy, vx))
+                }
+            }
+        )*
+    };
 }
 
+/// Compares `a.0 * a.1` with `b.0 * b.1`, for factors whose magnitude fits in a `u64`.
+fn compare_products(a: (i128, i128), b: (i128, i128)) -> Ordering {
+    // A product as its sign and magnitude, with zero counted as non-negative
+    let split = |(x, y): (i128, i128)| {
+        (
+            (x < 0) != (y < 0) && x != 0 && y != 0,
+            x.unsigned_abs() * y.unsigned_abs(),
+        )
+    };
+
+    match (split(a), split(b)) {
+        ((false, a), (false, b)) => a.cmp(&b),
+        ((true, a), (true, b)) => b.cmp(&a),
+        ((false, _), (true, _)) => Ordering::Greater,
+        ((true, _), (false, _)) => Ordering::Less,
+    }
+}
+
+// Up to 16 bits, two squared differences sum to less than 2^34. At 32 bits each
+// squared difference alone can reach 2^64, so the sum needs a u128
+impl_integer_coordinate!(u64: u8, u16, i8, i16);
+impl_integer_coordinate!(u128: u32, i32);
+impl_wide_integer_coordinate!(u64, i64);
+impl_float_coordinate!(f32, f64);
+
 /// Packs two positive numbers into a single number using bit manipulation.
 ///
 /// This function takes two positive integers and combines them into a single value
@@ -16,12 +670,18 @@ pub struct Point {
 ///
 /// * `num1` - First positive integer to pack
 /// * `num2` - Second positive integer to pack
-/// * `bits` - Number of bits to use for each number
+/// * `bits` - Number of bits to use for each number, from 1 to 32. Higher bits
+///   of the numbers are dropped, use [`try_pack_numbers`] to catch them instead
 ///
 /// # Returns
 ///
 /// A u64 containing both numbers packed together
 ///
+/// # Panics
+///
+/// Panics if `bits` is 0 or more than 32, as two numbers of more than 32 bits
+/// don't fit in a u64.
+///
 /// # Examples
 ///
 /// ```
@@ -31,7 +691,7 @@ pub struct Point {
 /// assert_eq!(unpack_numbers(packed, 16), (123, 456));
 /// ```
 pub fn pack_numbers(num1: u32, num2: u32, bits: u8) -> u64 {
-    let mask = (1u64 << bits) - 1;
+    let mask = low_bits_mask(bits);
 
     // handling of negative numbers
     // commented as this breaks the algorithm ;-;
@@ -54,6 +714,93 @@ pub fn pack_numbers(num1: u32, num2: u32, bits: u8) -> u64 {
     ((num1 as u64 & mask) << bits) | (num2 as u64 & mask)
 }
 
+/// Packs two positive numbers into a single number, without truncating them.
+///
+/// Same as [`pack_numbers`], but reports numbers that don't fit in `bits` bits
+/// as an error instead of dropping their higher bits.
+///
+/// # Arguments
+///
+/// * `num1` - First positive integer to pack
+/// * `num2` - Second positive integer to pack
+/// * `bits` - Number of bits to use for each number, from 1 to 32
+///
+/// # Returns
+///
+/// A u64 containing both numbers packed together, or a [`ClosestPairError`] when:
+/// * `bits` is 0 or more than 32
+/// * A number is larger than `2^bits - 1`
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+/// use closest_pair_rs::error::ClosestPairError;
+///
+/// assert_eq!(try_pack_numbers(255, 3, 8), Ok(pack_numbers(255, 3, 8)));
+/// assert_eq!(
+///     try_pack_numbers(3, 256, 8),
+///     Err(ClosestPairError::CoordinateOutOfRange { value: 256, bits: 8 })
+/// );
+/// ```
+pub fn try_pack_numbers(num1: u32, num2: u32, bits: u8) -> Result<u64, ClosestPairError> {
+    if !(1..=32).contains(&bits) {
+        return Err(ClosestPairError::InvalidBits(bits));
+    }
+    let mask = low_bits_mask(bits);
+    if let Some(value) = [num1, num2].into_iter().find(|&n| n as u64 > mask) {
+        return Err(ClosestPairError::CoordinateOutOfRange { value, bits });
+    }
+
+    Ok(pack_numbers(num1, num2, bits))
+}
+
+/// Checks that every coordinate fits in `bits` bits, so packing won't truncate it.
+///
+/// # Arguments
+///
+/// * `points` - The points to check
+/// * `bits` - Number of bits each coordinate gets when packing
+///
+/// # Returns
+///
+/// `Ok(())` when every coordinate is at most `2^bits - 1`, or the indices into
+/// `points`, in order, of the points whose x or y is larger.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = vec![
+///     Point { x: 255, y: 0 },
+///     Point { x: 256, y: 0 },
+///     Point { x: 3, y: 1000 }
+/// ];
+/// assert_eq!(validate_fits(&points, 8), Err(vec![1, 2]));
+/// assert_eq!(validate_fits(&points, 10), Ok(()));
+/// ```
+pub fn validate_fits(points: &[Point], bits: u8) -> Result<(), Vec<usize>> {
+    let max_coord = if bits >= 32 {
+        u32::MAX
+    } else {
+        (1u32 << bits) - 1
+    };
+
+    let offenders: Vec<usize> = points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.x.max(p.y) > max_coord)
+        .map(|(i, _)| i)
+        .collect();
+
+    if offenders.is_empty() {
+        Ok(())
+    } else {
+        Err(offenders)
+    }
+}
+
 /// Calculates the Euclidean distance between two points.
 ///
 /// # Arguments
@@ -63,7 +810,9 @@ pub fn pack_numbers(num1: u32, num2: u32, bits: u8) -> u64 {
 ///
 /// # Returns
 ///
-/// The Euclidean distance between p1 and p2 as a f32 value.
+/// The Euclidean distance between p1 and p2 as a f32 value. The square root is
+/// taken in `f64` and only narrowed to `f32` at the end, so large coordinates
+/// don't lose precision along the way.
 ///
 /// # Examples
 ///
@@ -74,205 +823,2506 @@ pub fn pack_numbers(num1: u32, num2: u32, bits: u8) -> u64 {
 /// let p2 = Point { x: 3, y: 4 };
 /// assert_eq!(eucid_distance(&p1, &p2), 5.0);
 /// ```
+///
+/// Points are taken by reference, like every other distance function in this
+/// module. Passing them by value does not compile:
+///
+/// ```compile_fail,E0308
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// eucid_distance(p1, p2);
+/// ```
 #[inline]
-pub fn eucid_distance(p1: &Point, p2: &Point) -> f32 {
-    let dx = p1.x as i64 - p2.x as i64;
-    let dy = p1.y as i64 - p2.y as i64;
-    ((dx * dx + dy * dy) as f32).sqrt()
+pub fn eucid_distance<T: Coordinate>(p1: &Point<T>, p2: &Point<T>) -> f32 {
+    Metric::Euclidean.finish::<T>(eucid_distance_sq(p1, p2))
 }
 
-/// Unpacks a single number into two positive numbers.
+/// Calculates the Euclidean distance between two points in double precision.
 ///
-/// This function extracts two positive integers that were previously combined
-/// using the `pack_numbers` function, with each number using the specified
-/// number of bits.
+/// Same as [`eucid_distance`], but without narrowing the result to `f32`, whose 24
+/// bits of mantissa round distances above `2^24` to a multiple of a power of two.
 ///
 /// # Arguments
 ///
-/// * `packed` - The combined number to unpack
-/// * `bits` - Number of bits used for each original number
+/// * `p1` - The first point
+/// * `p2` - The second point
 ///
 /// # Returns
 ///
-/// A tuple containing the two extracted positive integers (num1, num2)
+/// The Euclidean distance between p1 and p2 as a f64 value.
 ///
 /// # Examples
 ///
 /// ```
 /// use closest_pair_rs::utils::*;
 ///
-/// let packed = pack_numbers(42, 127, 8);
-/// let (a, b) = unpack_numbers(packed, 8);
-/// assert_eq!(a, 42);
-/// assert_eq!(b, 127);
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: (1 << 30) + 1, y: 0 };
+/// assert_eq!(eucid_distance_f64(&p1, &p2), 1073741825.0);
+/// assert_eq!(eucid_distance(&p1, &p2), 1073741824.0);
 /// ```
-pub fn unpack_numbers(packed: u64, bits: u8) -> (u32, u32) {
-    let mask = (1 << bits) - 1;
-    // let sign_bit = 1 << (bits - 1);
+#[inline]
+pub fn eucid_distance_f64<T: Coordinate>(p1: &Point<T>, p2: &Point<T>) -> f64 {
+    Metric::Euclidean.finish_f64::<T>(eucid_distance_sq(p1, p2))
+}
+
+/// Calculates the squared Euclidean distance between two points.
+///
+/// Comparing squared distances gives the same ordering as comparing distances,
+/// so the algorithms use this in their inner loops and only take the square root
+/// of the final result. For integer coordinates of up to 32 bits the math is done
+/// in a type wide enough that no pair of coordinates can overflow it.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The squared Euclidean distance between p1 and p2, as the coordinate type's
+/// [`Coordinate::Distance`]: a u128 for `u32` coordinates and a f64 for floating
+/// point ones.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// assert_eq!(eucid_distance_sq(&p1, &p2), 25u128);
+/// ```
+#[inline]
+pub fn eucid_distance_sq<T: Coordinate>(p1: &Point<T>, p2: &Point<T>) -> T::Distance {
+    sum_of_squares([p1.x, p1.y], [p2.x, p2.y])
+}
+
+/// Sum of the squared differences along each axis, shared by the 2D, 3D and N-D distances.
+#[inline]
+fn sum_of_squares<T: Coordinate, const N: usize>(a: [T; N], b: [T; N]) -> T::Distance {
+    let square = |i: usize| {
+        let d = a[i].axis_distance(b[i]);
+        d * d
+    };
+    (1..N).fold(square(0), |sum, i| sum + square(i))
+}
+
+/// Calculates the Euclidean distance between two 3D points.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The Euclidean distance between p1 and p2 as a f32 value.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point3 { x: 0, y: 0, z: 0 };
+/// let p2 = Point3 { x: 2, y: 3, z: 6 };
+/// assert_eq!(eucid_distance_3d(&p1, &p2), 7.0);
+/// ```
+#[inline]
+pub fn eucid_distance_3d<T: Coordinate>(p1: &Point3<T>, p2: &Point3<T>) -> f32 {
+    Metric::Euclidean.finish::<T>(eucid_distance_sq_3d(p1, p2))
+}
+
+/// Calculates the squared Euclidean distance between two 3D points.
+///
+/// For integer coordinates of up to 32 bits the math is done in a type wide enough
+/// that no pair of coordinates can overflow it.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The squared Euclidean distance between p1 and p2, as the coordinate type's
+/// [`Coordinate::Distance`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point3 { x: 0, y: 0, z: 0 };
+/// let p2 = Point3 { x: 2, y: 3, z: 6 };
+/// assert_eq!(eucid_distance_sq_3d(&p1, &p2), 49u128);
+/// ```
+#[inline]
+pub fn eucid_distance_sq_3d<T: Coordinate>(p1: &Point3<T>, p2: &Point3<T>) -> T::Distance {
+    sum_of_squares([p1.x, p1.y, p1.z], [p2.x, p2.y, p2.z])
+}
+
+/// Calculates the Euclidean distance between two points in `D` dimensions.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The Euclidean distance between p1 and p2 as a f32 value.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = PointN { coords: [0, 0, 0, 0] };
+/// let p2 = PointN { coords: [1, 1, 1, 1] };
+/// assert_eq!(eucid_distance_n(&p1, &p2), 2.0);
+/// ```
+#[inline]
+pub fn eucid_distance_n<const D: usize, T: Coordinate>(
+    p1: &PointN<D, T>,
+    p2: &PointN<D, T>,
+) -> f32 {
+    Metric::Euclidean.finish::<T>(eucid_distance_sq_n(p1, p2))
+}
+
+/// Calculates the squared Euclidean distance between two points in `D` dimensions.
+///
+/// For integer coordinates of up to 32 bits the math is done in a type wide enough
+/// that no pair of coordinates can overflow it.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The squared Euclidean distance between p1 and p2, as the coordinate type's
+/// [`Coordinate::Distance`]. Points with no dimensions don't compile.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = PointN { coords: [0, 0, 0, 0] };
+/// let p2 = PointN { coords: [1, 2, 3, 4] };
+/// assert_eq!(eucid_distance_sq_n(&p1, &p2), 30u128);
+/// ```
+#[inline]
+pub fn eucid_distance_sq_n<const D: usize, T: Coordinate>(
+    p1: &PointN<D, T>,
+    p2: &PointN<D, T>,
+) -> T::Distance {
+    const { assert!(D > 0, "Points need at least one dimension") };
+    sum_of_squares(p1.coords, p2.coords)
+}
+
+/// Mean radius of the Earth in kilometers, as used by [`haversine_distance`].
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Calculates the great-circle distance between two positions on the Earth.
+///
+/// Uses the haversine formula on a sphere of radius [`EARTH_RADIUS_KM`], which is
+/// within about 0.5% of the true distance on the ellipsoid. Longitudes only enter
+/// through the sine of half their difference, so positions on either side of the
+/// antimeridian, such as longitudes 179 and -179, come out close together.
+///
+/// # Arguments
+///
+/// * `p1` - The first position
+/// * `p2` - The second position
+///
+/// # Returns
+///
+/// The distance between p1 and p2 in kilometers as a f32 value.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// // A quarter of the way around the equator
+/// let p1 = GeoPoint { lat: 0.0, lon: 0.0 };
+/// let p2 = GeoPoint { lat: 0.0, lon: 90.0 };
+/// assert!(approx_eq(haversine_distance(&p1, &p2), 10007.557, 0.01));
+/// ```
+pub fn haversine_distance(p1: &GeoPoint, p2: &GeoPoint) -> f32 {
+    haversine_central_angle(haversine(p1, p2)) as f32
+}
+
+/// The haversine of the central angle between two positions, `sin²(θ / 2)`.
+///
+/// Grows with the distance, so pairs can be compared on it without taking the
+/// arcsine. Trigonometry comes from `libm`, so this works without `std`.
+#[inline]
+pub(crate) fn haversine(p1: &GeoPoint, p2: &GeoPoint) -> f64 {
+    let (lat1, lat2) = ((p1.lat as f64).to_radians(), (p2.lat as f64).to_radians());
+    let half_dlat = (lat2 - lat1) / 2.0;
+    let half_dlon = (p2.lon as f64 - p1.lon as f64).to_radians() / 2.0;
+
+    let sin_dlat = libm::sin(half_dlat);
+    let sin_dlon = libm::sin(half_dlon);
+    sin_dlat * sin_dlat + libm::cos(lat1) * libm::cos(lat2) * sin_dlon * sin_dlon
+}
+
+/// Turns the value of [`haversine`] into a distance in kilometers.
+#[inline]
+pub(crate) fn haversine_central_angle(h: f64) -> f64 {
+    // Rounding can push `h` just past 1 for antipodal positions
+    2.0 * EARTH_RADIUS_KM * libm::asin(sqrt(h.min(1.0)))
+}
+
+/// Calculates the Manhattan (L1, taxicab) distance between two points.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The sum of the absolute coordinate differences, as the coordinate type's
+/// [`Coordinate::Distance`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// assert_eq!(manhattan_distance(&p1, &p2), 7u128);
+/// ```
+#[inline]
+pub fn manhattan_distance<T: Coordinate>(p1: &Point<T>, p2: &Point<T>) -> T::Distance {
+    let dx = p1.x.axis_distance(p2.x);
+    let dy = p1.y.axis_distance(p2.y);
+    dx + dy
+}
+
+/// Calculates the Chebyshev (L∞, chessboard) distance between two points.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+///
+/// # Returns
+///
+/// The largest absolute coordinate difference, as the coordinate type's
+/// [`Coordinate::Distance`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// assert_eq!(chebyshev_distance(&p1, &p2), 4u128);
+/// ```
+#[inline]
+pub fn chebyshev_distance<T: Coordinate>(p1: &Point<T>, p2: &Point<T>) -> T::Distance {
+    let dx = p1.x.axis_distance(p2.x);
+    let dy = p1.y.axis_distance(p2.y);
+    if dx < dy {
+        dy
+    } else {
+        dx
+    }
+}
+
+/// Whether two points are the same up to noise of `radius` in each coordinate.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+/// * `radius` - Largest difference allowed along each axis
+///
+/// # Returns
+///
+/// `true` when the [`chebyshev_distance`] between the points is at most `radius`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 10, y: 10 };
+/// assert!(points_equal_within(&p1, &Point { x: 11, y: 9 }, 1));
+/// assert!(!points_equal_within(&p1, &Point { x: 12, y: 10 }, 1));
+/// ```
+#[inline]
+pub fn points_equal_within(p1: &Point, p2: &Point, radius: u32) -> bool {
+    chebyshev_distance(p1, p2) <= radius as u128
+}
+
+/// Distance metrics supported by the metric-aware algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Straight-line (L2) distance.
+    Euclidean,
+    /// Taxicab (L1) distance, the sum of the coordinate differences.
+    Manhattan,
+    /// Chessboard (L∞) distance, the largest coordinate difference.
+    Chebyshev,
+}
+
+impl Metric {
+    /// Distance in a cheap (and for integers exact) form that orders pairs the same
+    /// way as the real distance. This is the squared distance for [`Metric::Euclidean`].
+    #[inline]
+    pub(crate) fn raw_distance<T: Coordinate>(self, p1: &Point<T>, p2: &Point<T>) -> T::Distance {
+        match self {
+            Metric::Euclidean => eucid_distance_sq(p1, p2),
+            Metric::Manhattan => manhattan_distance(p1, p2),
+            Metric::Chebyshev => chebyshev_distance(p1, p2),
+        }
+    }
+
+    /// Raw distance between two points that differ along a single axis by `d`.
+    #[inline]
+    pub(crate) fn raw_axis_distance<D: Copy + Mul<Output = D>>(self, d: D) -> D {
+        match self {
+            Metric::Euclidean => d * d,
+            Metric::Manhattan | Metric::Chebyshev => d,
+        }
+    }
+
+    /// Converts a raw distance back into the distance reported by the public API.
+    ///
+    /// Square roots are taken in `f64`, as `f32` only has 24 bits of mantissa and
+    /// would round large squared distances before the root is even taken.
+    #[inline]
+    pub(crate) fn finish<T: Coordinate>(self, raw: T::Distance) -> f32 {
+        self.finish_f64::<T>(raw) as f32
+    }
+
+    /// Same as [`Metric::finish`], but keeps the distance in `f64`.
+    #[inline]
+    pub(crate) fn finish_f64<T: Coordinate>(self, raw: T::Distance) -> f64 {
+        let raw = T::distance_to_f64(raw);
+        match self {
+            Metric::Euclidean => sqrt(raw),
+            Metric::Manhattan | Metric::Chebyshev => raw,
+        }
+    }
+}
+
+/// Ways to measure the distance between two clusters of points, used by `cluster_distance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linkage {
+    /// The distance between the closest pair, one point from each cluster.
+    Single,
+    /// The distance between the farthest pair, one point from each cluster.
+    Complete,
+    /// The mean distance over every pair with one point from each cluster.
+    Average,
+}
+
+/// Rules for combining the distance between two [`WeightedPoint`]s with their weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightMode {
+    /// The distance divided by the product of the weights, `d / (w_i * w_j)`, so
+    /// heavier pairs count as closer. Weights should be positive.
+    Product,
+    /// The gap between circles whose radii are the weights, `d - w_i - w_j`.
+    /// Overlapping circles give a negative gap, and the deeper the overlap the
+    /// smaller it is.
+    Surface,
+}
+
+impl WeightMode {
+    /// Weighted distance between two points, computed in `f64`.
+    #[inline]
+    pub(crate) fn score(self, a: &WeightedPoint, b: &WeightedPoint) -> f64 {
+        let d = sqrt(u32::distance_to_f64(eucid_distance_sq(
+            &a.point(),
+            &b.point(),
+        )));
+        match self {
+            WeightMode::Product => d / (a.w as f64 * b.w as f64),
+            WeightMode::Surface => d - a.w as f64 - b.w as f64,
+        }
+    }
+}
+
+/// `f64::sqrt` needs `std`, so `no_std` builds take the root from `libm` instead.
+#[inline]
+pub(crate) fn sqrt(v: f64) -> f64 {
+    #[cfg(feature = "std")]
+    {
+        v.sqrt()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        libm::sqrt(v)
+    }
+}
+
+/// `f64::powf` needs `std` as well, so `no_std` builds use `libm::pow`.
+#[inline]
+pub(crate) fn powf(v: f64, n: f64) -> f64 {
+    #[cfg(feature = "std")]
+    {
+        v.powf(n)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        libm::pow(v, n)
+    }
+}
+
+/// Calculates the distance between two points under the given metric.
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+/// * `metric` - The metric to measure the distance with
+///
+/// # Returns
+///
+/// The distance between p1 and p2 as a f32 value.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// assert_eq!(distance(&p1, &p2, Metric::Euclidean), 5.0);
+/// assert_eq!(distance(&p1, &p2, Metric::Manhattan), 7.0);
+/// assert_eq!(distance(&p1, &p2, Metric::Chebyshev), 4.0);
+/// ```
+pub fn distance<T: Coordinate>(p1: &Point<T>, p2: &Point<T>, metric: Metric) -> f32 {
+    metric.finish::<T>(metric.raw_distance(p1, p2))
+}
+
+/// Calculates the Minkowski (Lp) distance between two points.
+///
+/// `p = 1` gives the Manhattan distance and `p = 2` the Euclidean distance, and as
+/// `p` grows the distance approaches the Chebyshev distance, which an infinite `p`
+/// gives exactly. Only brute force searches can use this distance: the strip
+/// pruning in `closest_pair_optimized` relies on Euclidean geometry, which is why
+/// Lp distances aren't offered as a [`Metric`].
+///
+/// # Arguments
+///
+/// * `p1` - The first point
+/// * `p2` - The second point
+/// * `p` - The order of the distance, at least 1
+///
+/// # Returns
+///
+/// The distance between p1 and p2 as a f32 value.
+///
+/// # Panics
+///
+/// Panics if `p` is less than 1 or NaN, since the result is not a distance then.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p1 = Point { x: 0, y: 0 };
+/// let p2 = Point { x: 3, y: 4 };
+/// assert_eq!(minkowski_distance(&p1, &p2, 1.0), 7.0);
+/// assert_eq!(minkowski_distance(&p1, &p2, 2.0), 5.0);
+/// assert!(approx_eq(minkowski_distance(&p1, &p2, 3.0), 4.4979, DISTANCE_EPSILON));
+/// assert_eq!(minkowski_distance(&p1, &p2, f32::INFINITY), 4.0);
+/// ```
+pub fn minkowski_distance<T: Coordinate>(p1: &Point<T>, p2: &Point<T>, p: f32) -> f32 {
+    assert!(p >= 1.0, "Minkowski distance needs p >= 1, got {p}");
+    if p == 1.0 {
+        return distance(p1, p2, Metric::Manhattan);
+    }
+    if p == 2.0 {
+        return distance(p1, p2, Metric::Euclidean);
+    }
+
+    let dx = T::distance_to_f64(p1.x.axis_distance(p2.x));
+    let dy = T::distance_to_f64(p1.y.axis_distance(p2.y));
+    let (lo, hi) = if dx < dy { (dx, dy) } else { (dy, dx) };
+    if p.is_infinite() || hi == 0.0 {
+        return hi as f32;
+    }
+
+    // Dividing by the larger difference keeps the powers from overflowing. For a
+    // large p, (lo / hi)^p underflows to 0 and the result settles on hi, the
+    // Chebyshev distance.
+    let p = p as f64;
+    (hi * powf(1.0 + powf(lo / hi, p), 1.0 / p)) as f32
+}
+
+/// Calculates the Euclidean distance from a point to a location given by float coordinates.
+///
+/// For queries that aren't points of the input, such as a centroid, which
+/// generally falls between integer coordinates.
+///
+/// # Arguments
+///
+/// * `p` - The point
+/// * `x` - The x-coordinate of the location
+/// * `y` - The y-coordinate of the location
+///
+/// # Returns
+///
+/// The Euclidean distance between p and (x, y) as a f32 value, computed in `f64`.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let p = Point { x: 3, y: 4 };
+/// assert_eq!(distance_to_coord(&p, 0.0, 0.0), 5.0);
+/// assert_eq!(distance_to_coord(&p, 3.0, 4.5), 0.5);
+/// ```
+#[inline]
+pub fn distance_to_coord<T: Coordinate>(p: &Point<T>, x: f32, y: f32) -> f32 {
+    let dx = p.x.to_f64() - x as f64;
+    let dy = p.y.to_f64() - y as f64;
+    sqrt(dx * dx + dy * dy) as f32
+}
+
+/// Default tolerance for comparing distances with [`approx_eq`].
+///
+/// Suits distances up to a few thousand. Further out the gap between neighbouring
+/// `f32` values is larger than this, so any tolerance should grow with the
+/// distance, or the distances be compared exactly.
+pub const DISTANCE_EPSILON: f32 = 1e-3;
+
+/// Whether two distances are equal up to an absolute tolerance.
+///
+/// # Arguments
+///
+/// * `a` - The first distance
+/// * `b` - The second distance
+/// * `eps` - The largest difference still counted as equal, such as [`DISTANCE_EPSILON`]
+///
+/// # Returns
+///
+/// `true` when `a` and `b` differ by at most `eps`. Equal infinities count as
+/// equal, NaN never equals anything.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// assert!(approx_eq(2.0f32.sqrt(), 1.4142, DISTANCE_EPSILON));
+/// assert!(!approx_eq(2.0f32.sqrt(), 1.41, DISTANCE_EPSILON));
+/// ```
+#[inline]
+pub fn approx_eq(a: f32, b: f32, eps: f32) -> bool {
+    a == b || (a - b).abs() <= eps
+}
+
+/// Unpacks a single number into two positive numbers.
+///
+/// This function extracts two positive integers that were previously combined
+/// using the `pack_numbers` function, with each number using the specified
+/// number of bits.
+///
+/// # Arguments
+///
+/// * `packed` - The combined number to unpack
+/// * `bits` - Number of bits used for each original number, from 1 to 32
+///
+/// # Returns
+///
+/// A tuple containing the two extracted positive integers (num1, num2)
+///
+/// # Panics
+///
+/// Panics if `bits` is 0 or more than 32, like [`pack_numbers`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let packed = pack_numbers(42, 127, 8);
+/// let (a, b) = unpack_numbers(packed, 8);
+/// assert_eq!(a, 42);
+/// assert_eq!(b, 127);
+/// ```
+pub fn unpack_numbers(packed: u64, bits: u8) -> (u32, u32) {
+    let mask = low_bits_mask(bits);
+    // let sign_bit = 1 << (bits - 1);
+
+    // Extract numbers
+    let num1 = (packed >> bits) & mask;
+    let num2 = packed & mask;
+
+    // cant handle negative nums, as explained in pack_numbers ;-;
+
+    // let num1 = if (num1 & sign_bit) != 0 {
+    //     -((num1 ^ mask) + 1)
+    // } else {
+    //     num1
+    // };
+
+    // let num2 = if (num2 & sign_bit) != 0 {
+    //     -((num2 ^ mask) + 1)
+    // } else {
+    //     num2
+    // };
+    (num1 as u32, num2 as u32)
+}
+
+/// Packs any number of positive numbers into a single number.
+///
+/// The generalization of [`pack_numbers`] to more than two numbers, such as the
+/// three coordinates of a 3D point. The first number ends up in the highest bits
+/// and the last in the lowest, so sorting packed values sorts by the first number,
+/// then the second, and so on.
+///
+/// # Arguments
+///
+/// * `coords` - The numbers to pack
+/// * `bits` - Number of bits to use for each number, from 1 to 32. Higher bits
+///   of the numbers are dropped
+///
+/// # Returns
+///
+/// A u128 containing all numbers packed together
+///
+/// # Panics
+///
+/// Panics if `bits` is 0 or more than 32, or if `coords.len() * bits` is more
+/// than 128, as the numbers wouldn't fit in a u128.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let packed = pack_n(&[1, 2, 3], 8);
+/// assert_eq!(packed, 0x01_02_03);
+/// assert_eq!(unpack_n(packed, 3, 8), vec![1, 2, 3]);
+/// ```
+pub fn pack_n(coords: &[u32], bits: u8) -> u128 {
+    let mask = low_bits_mask(bits) as u128;
+    check_packed_width(coords.len(), bits);
+
+    coords
+        .iter()
+        .fold(0, |packed, &c| (packed << bits) | (c as u128 & mask))
+}
+
+/// Unpacks a single number into any number of positive numbers.
+///
+/// Reverses [`pack_n`], given the same count and number of bits.
+///
+/// # Arguments
+///
+/// * `packed` - The combined number to unpack
+/// * `count` - How many numbers were packed
+/// * `bits` - Number of bits used for each original number, from 1 to 32
+///
+/// # Returns
+///
+/// The extracted numbers, in the order they were packed
+///
+/// # Panics
+///
+/// Panics if `bits` is 0 or more than 32, or if `count * bits` is more than 128,
+/// like [`pack_n`].
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let packed = pack_n(&[7, 0, 300, 65535], 16);
+/// assert_eq!(unpack_n(packed, 4, 16), vec![7, 0, 300, 65535]);
+/// ```
+pub fn unpack_n(packed: u128, count: usize, bits: u8) -> Vec<u32> {
+    let mask = low_bits_mask(bits) as u128;
+    check_packed_width(count, bits);
+
+    (0..count)
+        .rev()
+        .map(|i| ((packed >> (i * bits as usize)) & mask) as u32)
+        .collect()
+}
+
+/// Asserts that `count` numbers of `bits` bits each fit in a u128.
+fn check_packed_width(count: usize, bits: u8) {
+    assert!(
+        count * bits as usize <= 128,
+        "Cannot pack {count} numbers of {bits} bits into 128 bits"
+    );
+}
+
+/// Mask of the lowest `bits` bits, for packing numbers of `bits` bits each.
+///
+/// Built by shifting all ones right, which can't overflow the way `(1 << bits) - 1`
+/// does once `bits` reaches the width of the integer.
+#[inline]
+fn low_bits_mask(bits: u8) -> u64 {
+    assert!(
+        (1..=32).contains(&bits),
+        "Bits per number must be between 1 and 32, got {bits}"
+    );
+    u64::MAX >> (64 - bits)
+}
+
+/// Spreads the 32 bits of `v` out over the even bits of a u64.
+#[inline]
+fn spread_bits(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
+
+/// Gathers the even bits of `v` back into a u32, undoing [`spread_bits`].
+#[inline]
+fn compact_bits(v: u64) -> u32 {
+    let mut v = v & 0x5555_5555_5555_5555;
+    v = (v | (v >> 1)) & 0x3333_3333_3333_3333;
+    v = (v | (v >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v >> 4)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v >> 8)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v >> 16)) & 0x0000_0000_FFFF_FFFF;
+    v as u32
+}
+
+/// Encodes a point as a Z-order (Morton) code by interleaving the bits of its coordinates.
+///
+/// Unlike [`pack_numbers`], which puts all the bits of x before all the bits of y,
+/// interleaving keeps points that are close in 2D close in the sorted order: every
+/// aligned square of side `2^k` maps to a run of `4^k` consecutive codes.
+///
+/// # Arguments
+///
+/// * `x` - The x coordinate, stored in the even bits
+/// * `y` - The y coordinate, stored in the odd bits
+///
+/// # Returns
+///
+/// A u64 holding the interleaved bits of x and y
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// assert_eq!(morton_encode(0b11, 0b00), 0b0101);
+/// assert_eq!(morton_encode(0b00, 0b11), 0b1010);
+/// assert_eq!(morton_decode(morton_encode(123, 456)), (123, 456));
+/// ```
+#[inline]
+pub fn morton_encode(x: u32, y: u32) -> u64 {
+    spread_bits(x) | (spread_bits(y) << 1)
+}
+
+/// Decodes a Z-order (Morton) code back into its coordinates.
+///
+/// # Arguments
+///
+/// * `code` - A code produced by [`morton_encode`]
+///
+/// # Returns
+///
+/// A tuple of the x and y coordinates
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// assert_eq!(morton_decode(0b1001), (0b01, 0b10));
+/// ```
+#[inline]
+pub fn morton_decode(code: u64) -> (u32, u32) {
+    (compact_bits(code), compact_bits(code >> 1))
+}
+
+/// Finds the smallest axis-aligned box containing every point.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to measure
+///
+/// # Returns
+///
+/// The corner of the box with the smallest x and y, and the corner with the
+/// largest x and y. Neither corner has to be one of the points.
+///
+/// # Panics
+///
+/// * When the input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = vec![
+///     Point { x: 4, y: 1 },
+///     Point { x: 9, y: 7 },
+///     Point { x: 2, y: 5 }
+/// ];
+/// assert_eq!(bounding_box(&points), (Point { x: 2, y: 1 }, Point { x: 9, y: 7 }));
+/// ```
+pub fn bounding_box<T: Coordinate>(points: &[Point<T>]) -> (Point<T>, Point<T>) {
+    try_bounding_box(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Finds the smallest axis-aligned box containing every point, without panicking.
+///
+/// Same as [`bounding_box`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to measure
+///
+/// # Returns
+///
+/// The min and max corners of the box, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+/// use closest_pair_rs::error::ClosestPairError;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_bounding_box(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_bounding_box<T: Coordinate>(
+    points: &[Point<T>],
+) -> Result<(Point<T>, Point<T>), ClosestPairError> {
+    let (&first, rest) = points.split_first().ok_or(ClosestPairError::EmptyInput)?;
+
+    let (mut min, mut max) = (first, first);
+    for p in rest {
+        if p.x < min.x {
+            min.x = p.x;
+        }
+        if p.y < min.y {
+            min.y = p.y;
+        }
+        if p.x > max.x {
+            max.x = p.x;
+        }
+        if p.y > max.y {
+            max.y = p.y;
+        }
+    }
+
+    Ok((min, max))
+}
+
+/// Calculates the centroid of a set of points, the mean of their coordinates.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to average
+///
+/// # Returns
+///
+/// The mean x and mean y as f32 values, summed in `f64`. The centroid doesn't
+/// have to be one of the points.
+///
+/// # Panics
+///
+/// * When the input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 3, y: 0 },
+///     Point { x: 0, y: 6 }
+/// ];
+/// assert_eq!(centroid(&points), (1.0, 2.0));
+/// ```
+pub fn centroid<T: Coordinate>(points: &[Point<T>]) -> (f32, f32) {
+    try_centroid(points).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Calculates the centroid of a set of points, without panicking.
+///
+/// Same as [`centroid`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to average
+///
+/// # Returns
+///
+/// The mean x and mean y, or a [`ClosestPairError`] when:
+/// * The input vector is empty
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+/// use closest_pair_rs::error::ClosestPairError;
+///
+/// let points: Vec<Point> = Vec::new();
+/// assert_eq!(try_centroid(&points).unwrap_err(), ClosestPairError::EmptyInput);
+/// ```
+pub fn try_centroid<T: Coordinate>(points: &[Point<T>]) -> Result<(f32, f32), ClosestPairError> {
+    if points.is_empty() {
+        return Err(ClosestPairError::EmptyInput);
+    }
+
+    let (x, y) = mean_f64(points);
+    Ok((x as f32, y as f32))
+}
+
+/// The centroid of a non-empty set of points, summed and divided in `f64`.
+pub(crate) fn mean_f64<T: Coordinate>(points: &[Point<T>]) -> (f64, f64) {
+    let (sum_x, sum_y) = points.iter().fold((0.0, 0.0), |(sx, sy), p| {
+        (sx + p.x.to_f64(), sy + p.y.to_f64())
+    });
+    let n = points.len() as f64;
+    (sum_x / n, sum_y / n)
+}
+
+/// Calculates the Euclidean distance between every pair of points.
+///
+/// Meant for debugging and teaching on a handful of points: the matrix holds
+/// n² distances, so both the time and the memory taken grow quadratically.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to measure
+///
+/// # Returns
+///
+/// A symmetric matrix where `matrix[i][j]` is the distance between `points[i]`
+/// and `points[j]`, with 0.0 on the diagonal.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+/// assert_eq!(distance_matrix(&points), vec![vec![0.0, 5.0], vec![5.0, 0.0]]);
+/// ```
+pub fn distance_matrix<T: Coordinate>(points: &[Point<T>]) -> Vec<Vec<f32>> {
+    let n = points.len();
+    let mut matrix = alloc::vec![alloc::vec![0.0; n]; n];
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let dist = eucid_distance(&points[i], &points[j]);
+            matrix[i][j] = dist;
+            matrix[j][i] = dist;
+        }
+    }
+
+    matrix
+}
+
+/// Counts the Euclidean distances between every pair of points, in buckets of equal width.
+///
+/// Meant for statistics on small and medium sets: every pair is measured with
+/// [`eucid_distance`], so this takes O(n²) time.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to measure
+/// * `bucket_width` - Width of each bucket, so bucket `k` holds the distances `d`
+///   with `k * bucket_width <= d < (k + 1) * bucket_width`
+///
+/// # Returns
+///
+/// A vector of (bucket start, count) tuples for the buckets holding at least one
+/// distance, ordered by bucket start. The counts add up to the number of pairs.
+///
+/// # Panics
+///
+/// * When `bucket_width` is zero, negative or NaN
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }, Point { x: 0, y: 1 }];
+/// // The distances are 5.0, 1.0 and √18 ≈ 4.24
+/// assert_eq!(distance_histogram(&points, 2.0), vec![(0.0, 1), (4.0, 2)]);
+/// ```
+pub fn distance_histogram<T: Coordinate>(
+    points: &[Point<T>],
+    bucket_width: f32,
+) -> Vec<(f32, usize)> {
+    try_distance_histogram(points, bucket_width).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Counts the distances between every pair of points in buckets, without panicking.
+///
+/// Same as [`distance_histogram`], but reports invalid input as an error.
+///
+/// # Arguments
+///
+/// * `points` - Vector of points to measure
+/// * `bucket_width` - Width of each bucket
+///
+/// # Returns
+///
+/// The non-empty buckets as (bucket start, count) tuples, or
+/// [`ClosestPairError::InvalidBucketWidth`] when `bucket_width` is zero, negative
+/// or NaN.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+/// use closest_pair_rs::error::ClosestPairError;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+/// assert_eq!(
+///     try_distance_histogram(&points, 0.0).unwrap_err(),
+///     ClosestPairError::InvalidBucketWidth
+/// );
+/// ```
+pub fn try_distance_histogram<T: Coordinate>(
+    points: &[Point<T>],
+    bucket_width: f32,
+) -> Result<Vec<(f32, usize)>, ClosestPairError> {
+    if bucket_width.is_nan() || bucket_width <= 0.0 {
+        return Err(ClosestPairError::InvalidBucketWidth);
+    }
+
+    let mut buckets: BTreeMap<u64, usize> = BTreeMap::new();
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            // Distances are never negative, so truncating rounds down
+            let bucket = (eucid_distance(&points[i], &points[j]) / bucket_width) as u64;
+            *buckets.entry(bucket).or_insert(0) += 1;
+        }
+    }
+
+    Ok(buckets
+        .into_iter()
+        .map(|(bucket, count)| (bucket as f32 * bucket_width, count))
+        .collect())
+}
+
+/// Generates `n` pseudo-random points with coordinates in `0..=max_coord`.
+///
+/// The same seed always produces the same points, so a failing test or a
+/// benchmark run can be replayed exactly. The generator is a small built-in
+/// SplitMix64, which keeps the output stable across platforms and releases
+/// without pulling in a random number crate at runtime.
+///
+/// # Arguments
+///
+/// * `n` - Number of points to generate
+/// * `max_coord` - Largest value a coordinate can take
+/// * `seed` - Seed for the generator
+///
+/// # Returns
+///
+/// A vector of `n` points
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::*;
+///
+/// let points = random_points(100, 1000, 42);
+/// assert_eq!(points.len(), 100);
+/// assert!(points.iter().all(|p| p.x <= 1000 && p.y <= 1000));
+/// assert_eq!(points, random_points(100, 1000, 42));
+/// ```
+pub fn random_points(n: usize, max_coord: u32, seed: u64) -> Vec<Point> {
+    let mut rng = SplitMix64::new(seed);
+    let span = max_coord as u64 + 1;
+
+    // Scale the top 32 bits into 0..span
+    let mut next_coord = || (((rng.next_u64() >> 32) * span) >> 32) as u32;
+
+    (0..n)
+        .map(|_| Point {
+            x: next_coord(),
+            y: next_coord(),
+        })
+        .collect()
+}
+
+/// Small, fast pseudo-random number generator with a stable output for each seed.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod packing_unpacking {
+    use super::*;
+
+    #[test]
+    fn test_basic_packing_unpacking() {
+        let num1 = 42u32;
+        let num2 = 123u32;
+        let bits = 16u8;
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+    }
+
+    #[test]
+    fn test_with_large_numbers() {
+        let num1 = 65535u32; // 2^16 - 1
+        let num2 = 256u32; // 2^8
+        let bits = 16u8;
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+    }
+
+    #[test]
+    fn test_with_different_bit_sizes() {
+        // Test with 8 bits
+        let num1 = 127u32;
+        let num2 = 255u32;
+        let bits = 8u8;
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+
+        // Test with 24 bits
+        let num1 = 16777215u32; // 2^24 - 1
+        let num2 = 12345678u32;
+        let bits = 24u8;
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+    }
+
+    #[test]
+    fn test_truncation() {
+        // Test that values larger than the bit size are truncated
+        let num1 = 1000u32;
+        let num2 = 2000u32;
+        let bits = 8u8; // Only 8 bits, so numbers > 255 will be truncated
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1 & 0xFF, unpacked1); // Should be 232 (1000 % 256)
+        assert_eq!(num2 & 0xFF, unpacked2); // Should be 208 (2000 % 256)
+    }
+
+    #[test]
+    fn test_try_pack_in_range() {
+        for bits in [1u8, 8, 16, 31, 32] {
+            let max = (u64::MAX >> (64 - bits)) as u32;
+            let packed = try_pack_numbers(max, max / 2, bits).unwrap();
+            assert_eq!(packed, pack_numbers(max, max / 2, bits));
+            assert_eq!(unpack_numbers(packed, bits), (max, max / 2));
+        }
+    }
+
+    #[test]
+    fn test_try_pack_out_of_range() {
+        assert_eq!(
+            try_pack_numbers(1000, 2000, 8),
+            Err(ClosestPairError::CoordinateOutOfRange {
+                value: 1000,
+                bits: 8
+            })
+        );
+        assert_eq!(
+            try_pack_numbers(0, 1 << 16, 16),
+            Err(ClosestPairError::CoordinateOutOfRange {
+                value: 1 << 16,
+                bits: 16
+            })
+        );
+        assert_eq!(
+            try_pack_numbers(1, 1, 0),
+            Err(ClosestPairError::InvalidBits(0))
+        );
+        assert_eq!(
+            try_pack_numbers(1, 1, 33),
+            Err(ClosestPairError::InvalidBits(33))
+        );
+    }
+
+    #[test]
+    fn test_validate_fits() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1000, y: 5 },
+            Point { x: 255, y: 255 },
+            Point { x: 5, y: 256 },
+            Point { x: 300, y: 300 },
+        ];
+        assert_eq!(validate_fits(&points, 8), Err(vec![1, 3, 4]));
+        assert_eq!(validate_fits(&points, 9), Err(vec![1]));
+        assert_eq!(validate_fits(&points, 10), Ok(()));
+
+        let points = vec![Point {
+            x: u32::MAX,
+            y: u32::MAX,
+        }];
+        assert_eq!(validate_fits(&points, 31), Err(vec![0]));
+        assert_eq!(validate_fits(&points, 32), Ok(()));
+        assert_eq!(validate_fits(&[], 1), Ok(()));
+    }
+
+    #[test]
+    fn test_zero_values() {
+        let num1 = 0u32;
+        let num2 = 0u32;
+        let bits = 16u8;
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+        assert_eq!(packed, 0);
+    }
+
+    #[test]
+    fn test_bit_boundary() {
+        // Test packing at the maximum bit boundary
+        let num1 = u32::MAX; // A large number
+        let num2 = u32::MAX; // A large number
+        let bits = 32u8; // Maximum 32 bits for u32
+
+        let packed = pack_numbers(num1, num2, bits);
+        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+
+        assert_eq!(num1, unpacked1);
+        assert_eq!(num2, unpacked2);
+    }
+
+    #[test]
+    fn test_pack_n_round_trip() {
+        let coords = [u32::MAX, 0, 0x1234_5678, 1, 0xDEAD_BEEF];
+        for count in 2..=4 {
+            for bits in [1u8, 7, 8, 16, 21, 31, 32] {
+                if count * bits as usize > 128 {
+                    continue;
+                }
+                let mask = low_bits_mask(bits) as u32;
+                let input: Vec<u32> = coords[..count].to_vec();
+                let expected: Vec<u32> = input.iter().map(|c| c & mask).collect();
+
+                let packed = pack_n(&input, bits);
+                assert_eq!(
+                    unpack_n(packed, count, bits),
+                    expected,
+                    "count = {count}, bits = {bits}"
+                );
+                assert!(packed.leading_zeros() >= 128 - (count * bits as usize) as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pack_n_matches_pack_numbers() {
+        for bits in 1..=32u8 {
+            let (a, b) = (0xABCD_EF01, 0x1357_9BDF);
+            assert_eq!(pack_n(&[a, b], bits), pack_numbers(a, b, bits) as u128);
+        }
+    }
+
+    #[test]
+    fn test_pack_n_full_width() {
+        let coords = [u32::MAX; 4];
+        assert_eq!(pack_n(&coords, 32), u128::MAX);
+        assert_eq!(unpack_n(u128::MAX, 4, 32), coords);
+        assert_eq!(pack_n(&[], 8), 0);
+        assert!(unpack_n(0, 0, 8).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot pack 5 numbers of 32 bits into 128 bits")]
+    fn test_pack_n_too_wide() {
+        pack_n(&[1, 2, 3, 4, 5], 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot pack 9 numbers of 16 bits into 128 bits")]
+    fn test_unpack_n_too_wide() {
+        unpack_n(0, 9, 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bits per number must be between 1 and 32, got 33")]
+    fn test_pack_n_invalid_bits() {
+        pack_n(&[1, 2], 33);
+    }
+
+    #[test]
+    fn test_mask_near_boundaries() {
+        assert_eq!(low_bits_mask(1), 1);
+        assert_eq!(low_bits_mask(31), (1 << 31) - 1);
+        assert_eq!(low_bits_mask(32), u32::MAX as u64);
+
+        for bits in 1..=32u8 {
+            let max = low_bits_mask(bits) as u32;
+            let packed = pack_numbers(max, max - 1, bits);
+            assert_eq!(packed.leading_zeros(), 64 - 2 * bits as u32);
+            assert_eq!(unpack_numbers(packed, bits), (max, max - 1));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Bits per number must be between 1 and 32, got 33")]
+    fn test_pack_too_many_bits() {
+        pack_numbers(1, 2, 33);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bits per number must be between 1 and 32, got 64")]
+    fn test_unpack_too_many_bits() {
+        unpack_numbers(u64::MAX, 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bits per number must be between 1 and 32, got 0")]
+    fn test_zero_bits() {
+        pack_numbers(0, 0, 0);
+    }
+}
+
+#[cfg(test)]
+mod eucid_distance {
+    use super::*;
+
+    #[test]
+    fn test_f64_keeps_precision() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point { x: 3, y: 4 };
+        assert_eq!(eucid_distance_f64(&p1, &p2), 5.0);
+
+        // 2^25 + 1 isn't representable in f32
+        let p2 = Point {
+            x: 0,
+            y: (1 << 25) + 1,
+        };
+        assert_eq!(eucid_distance_f64(&p1, &p2), 33554433.0);
+        assert_eq!(eucid_distance(&p1, &p2), 33554432.0);
+    }
+
+    #[test]
+    fn test_zero_distance() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point { x: 0, y: 0 };
+        assert_eq!(eucid_distance(&p1, &p2), 0.0);
+    }
+
+    #[test]
+    fn test_horizontal_distance() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point { x: 3, y: 0 };
+        assert_eq!(eucid_distance(&p1, &p2), 3.0);
+    }
+
+    #[test]
+    fn test_vertical_distance() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point { x: 0, y: 4 };
+        assert_eq!(eucid_distance(&p1, &p2), 4.0);
+    }
+
+    #[test]
+    fn test_pythagorean_triple() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point { x: 3, y: 4 };
+        assert_eq!(eucid_distance(&p1, &p2), 5.0);
+    }
+
+    #[test]
+    fn test_reverse_direction() {
+        let p1 = Point { x: 5, y: 5 };
+        let p2 = Point { x: 2, y: 1 };
+        let distance = eucid_distance(&p1, &p2);
+        assert_eq!(distance, 5.0);
+    }
+
+    #[test]
+    fn test_signed_coordinates() {
+        let p1 = Pointi { x: -1, y: -2 };
+        let p2 = Pointi { x: 2, y: 2 };
+        assert_eq!(eucid_distance(&p1, &p2), 5.0);
+    }
+
+    #[test]
+    fn test_float_coordinates() {
+        let p1 = PointF64 { x: 0.5, y: -1.5 };
+        let p2 = PointF64 { x: 3.5, y: 2.5 };
+        assert_eq!(eucid_distance(&p1, &p2), 5.0);
+        assert_eq!(eucid_distance_sq(&p1, &p2), 25.0);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let p1 = Point { x: 1000, y: 2000 };
+        let p2 = Point { x: 4000, y: 6000 };
+        let expected = ((3000.0_f32 * 3000.0) + (4000.0_f32 * 4000.0)).sqrt();
+        assert_eq!(eucid_distance(&p1, &p2), expected);
+    }
+}
+
+#[cfg(test)]
+mod eucid_distance_sq {
+    use super::*;
+
+    #[test]
+    fn test_zero_distance() {
+        let p1 = Point { x: 7, y: 7 };
+        let p2 = Point { x: 7, y: 7 };
+        assert_eq!(eucid_distance_sq(&p1, &p2), 0u128);
+    }
+
+    #[test]
+    fn test_pythagorean_triple() {
+        let p1 = Point { x: 5, y: 5 };
+        let p2 = Point { x: 2, y: 1 };
+        assert_eq!(eucid_distance_sq(&p1, &p2), 25u128);
+    }
+
+    #[test]
+    fn test_no_u32_overflow() {
+        // dx * dx alone exceeds u32::MAX here
+        let p1 = Point { x: 0u32, y: 0 };
+        let p2 = Point {
+            x: 1 << 31,
+            y: 1 << 31,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), 1 << 63);
+    }
+
+    #[test]
+    fn test_exact_past_u64() {
+        // Each squared difference is just under 2^64, so their sum needs 65 bits
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point {
+            x: u32::MAX,
+            y: u32::MAX,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), 2 * (u32::MAX as u128).pow(2));
+        assert!(approx_eq(eucid_distance(&p1, &p2), 6.074e9, 1e6));
+
+        let p2 = Point { x: u32::MAX, y: 0 };
+        assert_eq!(eucid_distance_sq(&p1, &p2), (u32::MAX as u128).pow(2));
+    }
+
+    #[test]
+    fn test_signed_extremes() {
+        let p1 = Pointi {
+            x: i32::MIN,
+            y: i32::MIN,
+        };
+        let p2 = Pointi {
+            x: i32::MAX,
+            y: i32::MAX,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), 2 * (u32::MAX as u128).pow(2));
+    }
+
+    #[test]
+    fn test_matches_eucid_distance() {
+        let p1 = Point { x: 1000, y: 2000 };
+        let p2 = Point { x: 4000, y: 6000 };
+        assert_eq!(
+            (eucid_distance_sq(&p1, &p2) as f64).sqrt() as f32,
+            eucid_distance(&p1, &p2)
+        );
+    }
+}
+
+#[cfg(test)]
+mod metric_distance {
+    use super::*;
+
+    #[test]
+    fn test_points_equal_within() {
+        let p = Point { x: 100, y: 100 };
+        assert!(points_equal_within(&p, &p, 0));
+        assert!(points_equal_within(&p, &Point { x: 103, y: 97 }, 3));
+        assert!(!points_equal_within(&p, &Point { x: 103, y: 96 }, 3));
+        assert!(points_equal_within(
+            &Point { x: 0, y: 0 },
+            &Point {
+                x: u32::MAX,
+                y: u32::MAX
+            },
+            u32::MAX
+        ));
+    }
+
+    #[test]
+    fn test_manhattan_distance() {
+        let p1 = Point { x: 5, y: 1 };
+        let p2 = Point { x: 2, y: 5 };
+        assert_eq!(manhattan_distance(&p1, &p2), 7u128);
+        assert_eq!(distance(&p1, &p2, Metric::Manhattan), 7.0);
+    }
+
+    #[test]
+    fn test_chebyshev_distance() {
+        let p1 = Point { x: 5, y: 1 };
+        let p2 = Point { x: 2, y: 5 };
+        assert_eq!(chebyshev_distance(&p1, &p2), 4u128);
+        assert_eq!(distance(&p1, &p2, Metric::Chebyshev), 4.0);
+    }
+
+    #[test]
+    fn test_euclidean_matches_eucid_distance() {
+        let p1 = Point { x: 1000, y: 2000 };
+        let p2 = Point { x: 4000, y: 6000 };
+        assert_eq!(
+            distance(&p1, &p2, Metric::Euclidean),
+            eucid_distance(&p1, &p2)
+        );
+    }
+
+    #[test]
+    fn test_metric_ordering() {
+        // L∞ <= L2 <= L1 for any pair of points
+        let p1 = Pointi { x: -3, y: 7 };
+        let p2 = Pointi { x: 4, y: -2 };
+        let l1 = distance(&p1, &p2, Metric::Manhattan);
+        let l2 = distance(&p1, &p2, Metric::Euclidean);
+        let linf = distance(&p1, &p2, Metric::Chebyshev);
+        assert!(linf <= l2 && l2 <= l1);
+    }
+
+    #[test]
+    fn test_minkowski_p2_matches_eucid_distance() {
+        for p2 in random_points(100, 1 << 20, 7) {
+            let p1 = Point {
+                x: 1 << 19,
+                y: 1 << 19,
+            };
+            assert_eq!(minkowski_distance(&p1, &p2, 2.0), eucid_distance(&p1, &p2));
+        }
+    }
+
+    #[test]
+    fn test_minkowski_distance() {
+        let p1 = Pointi { x: -3, y: 7 };
+        let p2 = Pointi { x: 4, y: -2 };
+        // 7^3 + 9^3 = 1072
+        let expected = 1072.0f64.powf(1.0 / 3.0) as f32;
+        assert!(approx_eq(
+            minkowski_distance(&p1, &p2, 3.0),
+            expected,
+            DISTANCE_EPSILON
+        ));
+        // Just above 1 the distance is still close to Manhattan
+        assert!(approx_eq(minkowski_distance(&p1, &p2, 1.0001), 16.0, 0.01));
+        assert_eq!(minkowski_distance(&p1, &p1, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_minkowski_large_p_approaches_chebyshev() {
+        let p1 = Point { x: 0, y: 0 };
+        let p2 = Point {
+            x: u32::MAX,
+            y: u32::MAX / 2,
+        };
+        let linf = distance(&p1, &p2, Metric::Chebyshev);
+        assert_eq!(minkowski_distance(&p1, &p2, 1e6), linf);
+        assert_eq!(minkowski_distance(&p1, &p2, f32::INFINITY), linf);
+        // Naively raising u32::MAX to the 1000th power would overflow to infinity
+        assert!(minkowski_distance(&p1, &p2, 1000.0).is_finite());
+    }
+
+    #[test]
+    #[should_panic(expected = "Minkowski distance needs p >= 1")]
+    fn test_minkowski_rejects_p_below_one() {
+        minkowski_distance(&Point { x: 0, y: 0 }, &Point { x: 1, y: 1 }, 0.5);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_point_format() {
+        let p = Point { x: 3, y: 4 };
+        assert_eq!(serde_json::to_string(&p).unwrap(), r#"{"x":3,"y":4}"#);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let points: Vec<Point> = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 4 },
+            Point { x: u32::MAX, y: 7 },
+        ];
+
+        let json = serde_json::to_string(&points).unwrap();
+        let decoded: Vec<Point> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.len(), points.len());
+        for (a, b) in points.iter().zip(&decoded) {
+            assert_eq!((a.x, a.y), (b.x, b.y));
+        }
+    }
+
+    #[test]
+    fn test_float_points() {
+        let decoded: Vec<PointF64> = serde_json::from_str(r#"[{"x": 1.5, "y": -2.25}]"#).unwrap();
+        assert_eq!((decoded[0].x, decoded[0].y), (1.5, -2.25));
+    }
+}
+
+#[cfg(test)]
+mod point_traits {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Point { x: 3, y: 4 }.to_string(), "(3, 4)");
+        assert_eq!(
+            format!(
+                "{}",
+                Point {
+                    x: u32::MAX,
+                    y: 4_000_000_000u32
+                }
+            ),
+            "(4294967295, 4000000000)"
+        );
+        assert_eq!(Pointi { x: -7, y: 0 }.to_string(), "(-7, 0)");
+        assert_eq!(PointF64 { x: 1.5, y: -0.25 }.to_string(), "(1.5, -0.25)");
+        assert_eq!(Point3 { x: 1, y: 2, z: 3 }.to_string(), "(1, 2, 3)");
+    }
+
+    #[test]
+    fn test_hash_set_dedup() {
+        let points = vec![
+            Point { x: 1, y: 2 },
+            Point { x: 3, y: 4 },
+            Point { x: 1, y: 2 },
+            Point { x: 2, y: 1 },
+        ];
+
+        let unique: HashSet<Point> = points.into_iter().collect();
+        assert_eq!(unique.len(), 3);
+        assert!(unique.contains(&Point { x: 1, y: 2 }));
+        assert!(unique.contains(&Point { x: 2, y: 1 }));
+    }
+
+    #[test]
+    fn test_default_is_origin() {
+        assert_eq!(Point::default(), Point { x: 0, y: 0 });
+        assert_eq!(
+            vec![PointF64::default(); 2],
+            vec![PointF64 { x: 0.0, y: 0.0 }; 2]
+        );
+        assert_eq!(
+            Pointi {
+                x: -5,
+                ..Default::default()
+            },
+            Pointi { x: -5, y: 0 }
+        );
+    }
+
+    #[test]
+    fn test_sort_by_x_then_y() {
+        let mut points: Vec<Point> = vec![
+            Point { x: 5, y: 0 },
+            Point { x: 1, y: 9 },
+            Point { x: 1, y: 2 },
+            Point { x: 0, y: 100 },
+        ];
+        points.sort();
+
+        assert_eq!(
+            points,
+            vec![
+                Point { x: 0, y: 100 },
+                Point { x: 1, y: 2 },
+                Point { x: 1, y: 9 },
+                Point { x: 5, y: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float_partial_eq() {
+        assert_eq!(Point { x: 0.5, y: 1.0 }, Point { x: 0.5, y: 1.0 });
+        assert!(Point { x: 0.5, y: 9.0 } < Point { x: 1.0, y: 0.0 });
+    }
+}
 
-    // Extract numbers
-    let num1 = (packed >> bits) & mask;
-    let num2 = packed & mask;
+#[cfg(test)]
+mod eucid_distance_3d {
+    use super::*;
 
-    // cant handle negative nums, as explained in pack_numbers ;-;
+    #[test]
+    fn test_same_point() {
+        let p = Point3 { x: 4, y: 5, z: 6 };
+        assert_eq!(eucid_distance_3d(&p, &p), 0.0);
+    }
 
-    // let num1 = if (num1 & sign_bit) != 0 {
-    //     -((num1 ^ mask) + 1)
-    // } else {
-    //     num1
-    // };
+    #[test]
+    fn test_single_axis() {
+        let p1 = Point3 { x: 1, y: 1, z: 1 };
+        let p2 = Point3 { x: 1, y: 1, z: 9 };
+        assert_eq!(eucid_distance_3d(&p1, &p2), 8.0);
+        assert_eq!(eucid_distance_sq_3d(&p1, &p2), 64u128);
+    }
 
-    // let num2 = if (num2 & sign_bit) != 0 {
-    //     -((num2 ^ mask) + 1)
-    // } else {
-    //     num2
-    // };
-    (num1 as u32, num2 as u32)
+    #[test]
+    fn test_flat_matches_2d() {
+        let p1 = Point3 { x: 0, y: 0, z: 7 };
+        let p2 = Point3 { x: 3, y: 4, z: 7 };
+        assert_eq!(
+            eucid_distance_3d(&p1, &p2),
+            eucid_distance(&Point { x: 0, y: 0 }, &Point { x: 3, y: 4 })
+        );
+    }
+
+    #[test]
+    fn test_signed_and_float() {
+        let p1 = Point3 {
+            x: -1,
+            y: -2,
+            z: -2,
+        };
+        let p2 = Point3 { x: 0, y: 0, z: 0 };
+        assert_eq!(eucid_distance_3d(&p1, &p2), 3.0);
+
+        let p1 = Point3 {
+            x: 0.5,
+            y: 0.0,
+            z: 0.0,
+        };
+        let p2 = Point3 {
+            x: 0.5,
+            y: 1.5,
+            z: 2.0,
+        };
+        assert_eq!(eucid_distance_3d(&p1, &p2), 2.5);
+    }
 }
 
 #[cfg(test)]
-mod packing_unpacking {
+mod eucid_distance_n {
     use super::*;
 
     #[test]
-    fn test_basic_packing_unpacking() {
-        let num1 = 42u32;
-        let num2 = 123u32;
-        let bits = 16u8;
+    fn test_2d_matches_point() {
+        let p1 = PointN { coords: [1, 2] };
+        let p2 = PointN { coords: [4, 6] };
+        assert_eq!(eucid_distance_n(&p1, &p2), 5.0);
+        assert_eq!(eucid_distance_sq_n(&p1, &p2), 25u128);
+        assert_eq!((p2.x(), p2.y()), (4, 6));
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+        let (a, b): (Point, Point) = (p1.into(), p2.into());
+        assert_eq!(eucid_distance(&a, &b), 5.0);
+        assert_eq!(PointN::from(a), p1);
+    }
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
+    #[test]
+    fn test_3d_matches_point3() {
+        let p1 = Point3 { x: 0, y: 0, z: 0 };
+        let p2 = Point3 { x: 2, y: 3, z: 6 };
+        assert_eq!(eucid_distance_n(&PointN::from(p1), &PointN::from(p2)), 7.0);
     }
 
     #[test]
-    fn test_with_large_numbers() {
-        let num1 = 65535u32; // 2^16 - 1
-        let num2 = 256u32; // 2^8
-        let bits = 16u8;
+    fn test_4d() {
+        // 1 + 4 + 4 + 16 = 25
+        let p1 = PointN {
+            coords: [1, 1, 1, 1],
+        };
+        let p2 = PointN {
+            coords: [2, 3, 3, 5],
+        };
+        assert_eq!(eucid_distance_sq_n(&p1, &p2), 25u128);
+        assert_eq!(eucid_distance_n(&p1, &p2), 5.0);
+        assert_eq!(eucid_distance_n(&p1, &p1), 0.0);
+    }
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+    #[test]
+    fn test_signed_and_float() {
+        let p1 = PointN {
+            coords: [-1i32, -1, -1, -1],
+        };
+        let p2 = PointN {
+            coords: [1i32, 1, 1, 1],
+        };
+        assert_eq!(eucid_distance_n(&p1, &p2), 4.0);
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
+        let p1 = PointN {
+            coords: [0.5, 0.0, 0.0],
+        };
+        let p2 = PointN {
+            coords: [0.5, 1.5, 2.0],
+        };
+        assert_eq!(eucid_distance_n(&p1, &p2), 2.5);
     }
 
     #[test]
-    fn test_with_different_bit_sizes() {
-        // Test with 8 bits
-        let num1 = 127u32;
-        let num2 = 255u32;
-        let bits = 8u8;
+    fn test_display() {
+        assert_eq!(
+            PointN {
+                coords: [1, 2, 3, 4]
+            }
+            .to_string(),
+            "(1, 2, 3, 4)"
+        );
+        assert_eq!(PointN { coords: [-7] }.to_string(), "(-7)");
+    }
+}
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+#[cfg(test)]
+mod haversine_distance {
+    use super::*;
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
+    #[test]
+    fn test_known_distances() {
+        let paris = GeoPoint {
+            lat: 48.8566,
+            lon: 2.3522,
+        };
+        let london = GeoPoint {
+            lat: 51.5074,
+            lon: -0.1278,
+        };
+        assert!(approx_eq(haversine_distance(&paris, &london), 343.5, 1.0));
 
-        // Test with 24 bits
-        let num1 = 16777215u32; // 2^24 - 1
-        let num2 = 12345678u32;
-        let bits = 24u8;
+        // Half way around the Earth, through the poles
+        let north = GeoPoint {
+            lat: 90.0,
+            lon: 0.0,
+        };
+        let south = GeoPoint {
+            lat: -90.0,
+            lon: 0.0,
+        };
+        let half = core::f64::consts::PI * EARTH_RADIUS_KM;
+        assert!(approx_eq(
+            haversine_distance(&north, &south),
+            half as f32,
+            0.01
+        ));
+    }
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+    #[test]
+    fn test_antimeridian() {
+        let east = GeoPoint {
+            lat: 0.0,
+            lon: 179.0,
+        };
+        let west = GeoPoint {
+            lat: 0.0,
+            lon: -179.0,
+        };
+        let one_degree = haversine_distance(
+            &GeoPoint { lat: 0.0, lon: 0.0 },
+            &GeoPoint { lat: 0.0, lon: 1.0 },
+        );
+        assert!(approx_eq(
+            haversine_distance(&east, &west),
+            2.0 * one_degree,
+            0.01
+        ));
+    }
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
+    #[test]
+    fn test_longitude_at_pole() {
+        // Every longitude is the same place at the poles
+        let a = GeoPoint {
+            lat: 90.0,
+            lon: -120.0,
+        };
+        let b = GeoPoint {
+            lat: 90.0,
+            lon: 45.0,
+        };
+        assert!(haversine_distance(&a, &b) < 0.001);
     }
 
     #[test]
-    fn test_truncation() {
-        // Test that values larger than the bit size are truncated
-        let num1 = 1000u32;
-        let num2 = 2000u32;
-        let bits = 8u8; // Only 8 bits, so numbers > 255 will be truncated
+    fn test_symmetric_and_zero() {
+        let a = GeoPoint {
+            lat: -33.87,
+            lon: 151.21,
+        };
+        let b = GeoPoint {
+            lat: 35.68,
+            lon: 139.69,
+        };
+        assert_eq!(haversine_distance(&a, &b), haversine_distance(&b, &a));
+        assert_eq!(haversine_distance(&a, &a), 0.0);
+    }
+}
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+#[cfg(test)]
+mod random_points {
+    use super::*;
 
-        assert_eq!(num1 & 0xFF, unpacked1); // Should be 232 (1000 % 256)
-        assert_eq!(num2 & 0xFF, unpacked2); // Should be 208 (2000 % 256)
+    #[test]
+    fn test_same_seed_same_points() {
+        assert_eq!(
+            random_points(500, 1 << 20, 7),
+            random_points(500, 1 << 20, 7)
+        );
+        assert_ne!(
+            random_points(500, 1 << 20, 7),
+            random_points(500, 1 << 20, 8)
+        );
     }
 
     #[test]
-    fn test_zero_values() {
-        let num1 = 0u32;
-        let num2 = 0u32;
-        let bits = 16u8;
+    fn test_coordinate_bounds() {
+        let points = random_points(1000, 9, 1);
+        assert!(points.iter().all(|p| p.x <= 9 && p.y <= 9));
+        // Both ends of the range show up
+        assert!(points.iter().any(|p| p.x == 0));
+        assert!(points.iter().any(|p| p.x == 9));
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+        assert!(random_points(10, 0, 1).iter().all(|p| p.x == 0 && p.y == 0));
+        assert_eq!(random_points(10, u32::MAX, 1).len(), 10);
+    }
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
-        assert_eq!(packed, 0);
+    #[test]
+    fn test_empty() {
+        assert!(random_points(0, 100, 3).is_empty());
     }
+}
+
+#[cfg(test)]
+mod morton {
+    use super::*;
 
     #[test]
-    fn test_bit_boundary() {
-        // Test packing at the maximum bit boundary
-        let num1 = u32::MAX; // A large number
-        let num2 = u32::MAX; // A large number
-        let bits = 32u8; // Maximum 32 bits for u32
+    fn test_round_trip() {
+        let values = [0, 1, 2, 3, 255, 256, 12345, 1 << 31, u32::MAX - 1, u32::MAX];
+        for &x in &values {
+            for &y in &values {
+                assert_eq!(morton_decode(morton_encode(x, y)), (x, y));
+            }
+        }
+        assert_eq!(morton_encode(u32::MAX, u32::MAX), u64::MAX);
+    }
 
-        let packed = pack_numbers(num1, num2, bits);
-        let (unpacked1, unpacked2) = unpack_numbers(packed, bits);
+    #[test]
+    fn test_interleaving() {
+        assert_eq!(morton_encode(0, 0), 0);
+        assert_eq!(morton_encode(1, 0), 1);
+        assert_eq!(morton_encode(0, 1), 2);
+        assert_eq!(morton_encode(1, 1), 3);
+        assert_eq!(morton_encode(2, 0), 4);
+        assert_eq!(morton_encode(u32::MAX, 0), 0x5555_5555_5555_5555);
+        assert_eq!(morton_encode(0, u32::MAX), 0xAAAA_AAAA_AAAA_AAAA);
+    }
 
-        assert_eq!(num1, unpacked1);
-        assert_eq!(num2, unpacked2);
+    #[test]
+    fn test_aligned_block_is_contiguous() {
+        // Every point of an aligned 16x16 block lands in a run of 256 codes
+        let (bx, by) = (1 << 20, 3 << 20);
+        let mut codes: Vec<u64> = Vec::new();
+        for dx in 0..16 {
+            for dy in 0..16 {
+                codes.push(morton_encode(bx + dx, by + dy));
+            }
+        }
+        codes.sort_unstable();
+
+        assert_eq!(codes[0], morton_encode(bx, by));
+        assert!(codes.windows(2).all(|w| w[1] == w[0] + 1));
+    }
+
+    #[test]
+    fn test_neighbours_closer_than_packing() {
+        // (1000, 5) and (1001, 5) are next to each other in space. Concatenating
+        // the bits puts a whole column of y values between them, interleaving doesn't
+        let (a, b) = ((1000, 5), (1001, 5));
+        let morton_gap = morton_encode(b.0, b.1) - morton_encode(a.0, a.1);
+        let packed_gap = pack_numbers(b.0, b.1, 32) - pack_numbers(a.0, a.1, 32);
+
+        assert_eq!(morton_gap, 1);
+        assert_eq!(packed_gap, 1 << 32);
     }
 }
 
 #[cfg(test)]
-mod eucid_distance {
+mod bounding_box {
     use super::*;
 
     #[test]
-    fn test_zero_distance() {
-        let p1 = Point { x: 0, y: 0 };
-        let p2 = Point { x: 0, y: 0 };
-        assert_eq!(eucid_distance(&p1, &p2), 0.0);
+    fn test_scattered_points() {
+        let points = vec![
+            Pointi { x: 3, y: -8 },
+            Pointi { x: -4, y: 2 },
+            Pointi { x: 10, y: 0 },
+            Pointi { x: 0, y: 6 },
+        ];
+        assert_eq!(
+            bounding_box(&points),
+            (Pointi { x: -4, y: -8 }, Pointi { x: 10, y: 6 })
+        );
     }
 
     #[test]
-    fn test_horizontal_distance() {
-        let p1 = Point { x: 0, y: 0 };
-        let p2 = Point { x: 3, y: 0 };
-        assert_eq!(eucid_distance(&p1, &p2), 3.0);
+    fn test_contains_random_points() {
+        let points = random_points(1000, 5000, 8);
+        let (min, max) = bounding_box(&points);
+        assert!(points
+            .iter()
+            .all(|p| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y));
+        assert!(points.iter().any(|p| p.x == min.x));
+        assert!(points.iter().any(|p| p.y == max.y));
     }
 
     #[test]
-    fn test_vertical_distance() {
-        let p1 = Point { x: 0, y: 0 };
-        let p2 = Point { x: 0, y: 4 };
-        assert_eq!(eucid_distance(&p1, &p2), 4.0);
+    fn test_identical_points() {
+        let p = PointF64 { x: 1.5, y: -2.5 };
+        assert_eq!(bounding_box(&[p, p, p]), (p, p));
+        assert_eq!(bounding_box(&[p]), (p, p));
     }
 
     #[test]
-    fn test_pythagorean_triple() {
-        let p1 = Point { x: 0, y: 0 };
-        let p2 = Point { x: 3, y: 4 };
-        assert_eq!(eucid_distance(&p1, &p2), 5.0);
+    fn test_empty_input() {
+        let points: Vec<Point> = Vec::new();
+        assert_eq!(
+            try_bounding_box(&points).unwrap_err(),
+            ClosestPairError::EmptyInput
+        );
+    }
+}
+
+#[cfg(test)]
+mod distance_matrix {
+    use super::*;
+
+    #[test]
+    fn test_small_set() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 4 },
+            Point { x: 6, y: 0 },
+            Point { x: 0, y: 0 },
+        ];
+        let matrix = distance_matrix(&points);
+
+        assert_eq!(matrix.len(), 4);
+        for i in 0..4 {
+            assert_eq!(matrix[i].len(), 4);
+            assert_eq!(matrix[i][i], 0.0);
+            for j in 0..4 {
+                assert_eq!(matrix[i][j], matrix[j][i]);
+                assert_eq!(matrix[i][j], eucid_distance(&points[i], &points[j]));
+            }
+        }
+        assert_eq!(matrix[0][1], 5.0);
+        assert_eq!(matrix[1][2], 5.0);
+        assert_eq!(matrix[0][2], 6.0);
+        assert_eq!(matrix[0][3], 0.0);
     }
 
     #[test]
-    fn test_reverse_direction() {
-        let p1 = Point { x: 5, y: 5 };
-        let p2 = Point { x: 2, y: 1 };
-        let distance = eucid_distance(&p1, &p2);
-        assert_eq!(distance, 5.0);
+    fn test_signed_points() {
+        let points = vec![Pointi { x: -1, y: -1 }, Pointi { x: 2, y: 3 }];
+        assert_eq!(
+            distance_matrix(&points),
+            vec![vec![0.0, 5.0], vec![5.0, 0.0]]
+        );
     }
 
     #[test]
-    fn test_large_numbers() {
-        let p1 = Point { x: 1000, y: 2000 };
-        let p2 = Point { x: 4000, y: 6000 };
-        let expected = ((3000.0_f32 * 3000.0) + (4000.0_f32 * 4000.0)).sqrt();
+    fn test_empty() {
+        let points: Vec<Point> = Vec::new();
+        assert!(distance_matrix(&points).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod distance_histogram {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let points: Vec<Point> = (0..3)
+            .flat_map(|x| (0..3).map(move |y| Point { x, y }))
+            .collect();
+
+        // A 3x3 grid has 12 pairs at 1, 8 at √2, 6 at 2, 8 at √5 and 2 at √8
+        assert_eq!(
+            distance_histogram(&points, 0.25),
+            vec![(1.0, 12), (1.25, 8), (2.0, 14), (2.75, 2)]
+        );
+        assert_eq!(distance_histogram(&points, 1.0), vec![(1.0, 20), (2.0, 16)]);
+    }
+
+    #[test]
+    fn test_counts_every_pair() {
+        let points = random_points(100, 1000, 9);
+        let histogram = distance_histogram(&points, 7.5);
+        let total: usize = histogram.iter().map(|&(_, count)| count).sum();
+        assert_eq!(total, 100 * 99 / 2);
+        assert!(histogram.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_duplicates_fall_in_first_bucket() {
+        let points = vec![PointF64 { x: 1.5, y: 1.5 }; 3];
+        assert_eq!(distance_histogram(&points, 1.0), vec![(0.0, 3)]);
+    }
+
+    #[test]
+    fn test_too_few_points() {
+        let points: Vec<Point> = vec![Point { x: 1, y: 1 }];
+        assert!(distance_histogram(&points, 1.0).is_empty());
+        assert!(distance_histogram::<u32>(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_width() {
+        let points = vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }];
+        for width in [0.0, -1.0, f32::NAN] {
+            assert_eq!(
+                try_distance_histogram(&points, width).unwrap_err(),
+                ClosestPairError::InvalidBucketWidth
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Bucket width must be a positive number")]
+    fn test_zero_width_panics() {
+        distance_histogram(&[Point { x: 0, y: 0 }, Point { x: 1, y: 1 }], 0.0);
+    }
+}
+
+#[cfg(test)]
+mod wide_coordinates {
+    use super::*;
+
+    #[test]
+    fn test_compare_products() {
+        let big = u64::MAX as i128;
+        assert_eq!(
+            compare_products((big, big), (big, big - 1)),
+            Ordering::Greater
+        );
+        assert_eq!(compare_products((-big, big), (big, -big)), Ordering::Equal);
+        assert_eq!(
+            compare_products((-big, big), (-big, big - 1)),
+            Ordering::Less
+        );
+        assert_eq!(compare_products((0, -big), (big, 0)), Ordering::Equal);
+        assert_eq!(compare_products((0, -big), (-1, 1)), Ordering::Greater);
+        assert_eq!(compare_products((-big, -big), (big, 1)), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_cross_sign() {
+        let o = PointU64 { x: 0, y: 0 };
+        let x = PointU64 { x: u64::MAX, y: 0 };
+        let y = PointU64 { x: 0, y: u64::MAX };
+        assert_eq!(u64::cross_sign(o, x, o, y), Ordering::Greater);
+        assert_eq!(u64::cross_sign(o, y, o, x), Ordering::Less);
+        assert_eq!(
+            u64::cross_sign(
+                o,
+                x,
+                y,
+                PointU64 {
+                    x: u64::MAX,
+                    y: u64::MAX
+                }
+            ),
+            Ordering::Equal
+        );
+
+        let a = Point::<i64> {
+            x: i64::MIN,
+            y: i64::MIN,
+        };
+        let b = Point::<i64> {
+            x: i64::MAX,
+            y: i64::MAX,
+        };
+        assert_eq!(
+            i64::cross_sign(
+                a,
+                b,
+                a,
+                Point {
+                    x: i64::MIN,
+                    y: i64::MAX
+                }
+            ),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let p1 = PointU64 { x: 0, y: 0 };
+        let p2 = PointU64 {
+            x: 1 << 63,
+            y: 1 << 63,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), WideDistance::from(1 << 127));
+        assert_eq!(manhattan_distance(&p1, &p2), WideDistance::from(1 << 64));
+        assert_eq!(
+            eucid_distance_f64(&p1, &p2),
+            (2.0f64).sqrt() * (1u64 << 63) as f64
+        );
+
+        let p1 = Point::<i64> { x: i64::MIN, y: 0 };
+        let p2 = Point::<i64> { x: i64::MAX, y: 0 };
+        assert_eq!(
+            chebyshev_distance(&p1, &p2),
+            WideDistance::from(u64::MAX as u128)
+        );
+    }
+
+    #[test]
+    fn test_max_corners() {
+        let side = WideDistance::from((u64::MAX as u128).pow(2));
+        let expected = (u64::MAX as f64 * 2f64.sqrt()) as f32;
+
+        let p1 = PointU64 { x: 0, y: 0 };
+        let p2 = PointU64 {
+            x: u64::MAX,
+            y: u64::MAX,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), side + side);
         assert_eq!(eucid_distance(&p1, &p2), expected);
+
+        let p1 = Point::<i64> {
+            x: i64::MIN,
+            y: i64::MIN,
+        };
+        let p2 = Point::<i64> {
+            x: i64::MAX,
+            y: i64::MAX,
+        };
+        assert_eq!(eucid_distance_sq(&p1, &p2), side + side);
+        assert_eq!(eucid_distance(&p1, &p2), expected);
+
+        let p1 = PointN { coords: [0u64; 4] };
+        let p2 = PointN {
+            coords: [u64::MAX; 4],
+        };
+        assert_eq!(eucid_distance_sq_n(&p1, &p2), side + side + side + side);
+        assert_eq!(eucid_distance_n(&p1, &p2), (u64::MAX as f64 * 2.0) as f32);
+    }
+
+    #[test]
+    fn test_wide_distance_math() {
+        let max = WideDistance::from(u128::MAX);
+        let one = WideDistance::from(1);
+        assert_eq!(max + one, WideDistance { hi: 1, lo: 0 });
+        assert!(max + one > max);
+        assert!(WideDistance { hi: 1, lo: 0 } > WideDistance { hi: 0, lo: 5 });
+
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        assert_eq!(
+            max * max,
+            WideDistance {
+                hi: u128::MAX - 1,
+                lo: 1
+            }
+        );
+        assert_eq!(
+            WideDistance::from(u64::MAX as u128) * WideDistance::from(u64::MAX as u128),
+            WideDistance::from((u64::MAX as u128).pow(2))
+        );
+        assert_eq!(
+            WideDistance { hi: 3, lo: 2 } * WideDistance::from(5),
+            WideDistance { hi: 15, lo: 10 }
+        );
+        assert_eq!((max + one).to_f64(), 2f64.powi(128));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to multiply with overflow")]
+    fn test_wide_distance_overflow() {
+        let big = WideDistance { hi: 1, lo: 0 };
+        let _ = big * big;
+    }
+}
+
+#[cfg(test)]
+mod approx_eq {
+    use super::*;
+
+    #[test]
+    fn test_edges_of_epsilon() {
+        assert!(approx_eq(5.0, 5.0, 0.0));
+        assert!(approx_eq(5.0, 5.0009, DISTANCE_EPSILON));
+        assert!(approx_eq(5.0009, 5.0, DISTANCE_EPSILON));
+        assert!(!approx_eq(5.0, 5.0011, DISTANCE_EPSILON));
+        assert!(!approx_eq(5.0011, 5.0, DISTANCE_EPSILON));
+        assert!(approx_eq(1.0, 1.5, 0.5));
+        assert!(!approx_eq(1.0, 1.5, 0.4999));
+    }
+
+    #[test]
+    fn test_special_values() {
+        assert!(approx_eq(f32::INFINITY, f32::INFINITY, DISTANCE_EPSILON));
+        assert!(!approx_eq(f32::INFINITY, f32::MAX, DISTANCE_EPSILON));
+        assert!(!approx_eq(f32::NAN, f32::NAN, f32::INFINITY));
+        assert!(!approx_eq(f32::NAN, 0.0, f32::INFINITY));
+    }
+}
+
+#[cfg(test)]
+mod centroid {
+    use super::*;
+
+    #[test]
+    fn test_symmetric_set() {
+        // A square around (5, 5) with its centre, so every coordinate cancels out
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 0 },
+            Point { x: 0, y: 10 },
+            Point { x: 10, y: 10 },
+        ];
+        assert_eq!(centroid(&points), (5.0, 5.0));
+    }
+
+    #[test]
+    fn test_fractional() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 1, y: 1 },
+        ];
+        let (x, y) = centroid(&points);
+        assert!(approx_eq(x, 2.0 / 3.0, DISTANCE_EPSILON));
+        assert!(approx_eq(y, 1.0 / 3.0, DISTANCE_EPSILON));
+    }
+
+    #[test]
+    fn test_signed_points() {
+        let points = vec![Pointi { x: -4, y: 2 }, Pointi { x: 4, y: -2 }];
+        assert_eq!(centroid(&points), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_single_point() {
+        assert_eq!(centroid(&[Point { x: 7, y: 3 }]), (7.0, 3.0));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        // The sum overflows u32, but not the f64 it is taken in. The mean is a
+        // multiple of 256, so exact as a f32 this close to 2^32
+        let top = 1u32 << 31;
+        let points = vec![
+            Point {
+                x: top + (top - 1024),
+                y: 0,
+            },
+            Point {
+                x: top + (top - 512),
+                y: 2,
+            },
+        ];
+        assert_eq!(centroid(&points), (4_294_966_528.0, 1.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find closest pair with empty vector")]
+    fn test_empty_panics() {
+        centroid::<u32>(&[]);
+    }
+
+    #[test]
+    fn test_distance_to_coord() {
+        let p = Point { x: 1, y: 1 };
+        assert_eq!(distance_to_coord(&p, 1.0, 1.0), 0.0);
+        assert_eq!(distance_to_coord(&p, 4.0, 5.0), 5.0);
+        assert_eq!(distance_to_coord(&p, -2.0, -3.0), 5.0);
+        assert_eq!(distance_to_coord(&p, 1.5, 1.0), 0.5);
+    }
+}
+
+#[cfg(test)]
+mod point_distance_methods {
+    use super::*;
+
+    #[test]
+    fn test_match_free_functions() {
+        for (a, b) in random_points(100, 1 << 20, 12)
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+        {
+            assert_eq!(a.euclidean_to(&b), eucid_distance(&a, &b));
+            assert_eq!(a.squared_euclidean_to(&b), eucid_distance_sq(&a, &b));
+            assert_eq!(a.manhattan_to(&b), manhattan_distance(&a, &b));
+            assert_eq!(a.chebyshev_to(&b), chebyshev_distance(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_signed_and_float_points() {
+        let (a, b) = (Pointi { x: -1, y: 2 }, Pointi { x: 2, y: -2 });
+        assert_eq!(a.euclidean_to(&b), 5.0);
+        assert_eq!(a.squared_euclidean_to(&b), 25);
+        assert_eq!(a.manhattan_to(&b), 7);
+        assert_eq!(a.chebyshev_to(&b), 4);
+
+        let (a, b) = (PointF64 { x: 0.5, y: 0.0 }, PointF64 { x: 2.0, y: 2.0 });
+        assert_eq!(a.euclidean_to(&b), 2.5);
+        assert_eq!(a.squared_euclidean_to(&b), 6.25);
+        assert_eq!(a.manhattan_to(&b), 3.5);
+        assert_eq!(a.chebyshev_to(&b), 2.0);
     }
 }
@@ -0,0 +1,193 @@
+use crate::result::ClosestPairResult;
+use crate::utils::*;
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Width and height, in pixels, of the canvas drawn by [`to_svg`].
+pub const SVG_CANVAS_SIZE: u32 = 512;
+
+/// Space left around the points, in pixels, so circles on the edge aren't cut off.
+const MARGIN: f64 = 8.0;
+
+/// Draws a point set and its closest pair as an SVG image.
+///
+/// Same as [`to_svg_with_size`] on a [`SVG_CANVAS_SIZE`] canvas.
+///
+/// # Arguments
+///
+/// * `points` - The points to draw
+/// * `result` - The closest pair to highlight
+///
+/// # Returns
+///
+/// The SVG document as a string.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::result::ClosestPairResult;
+/// use closest_pair_rs::algorithms::closest_pair_optimized;
+/// use closest_pair_rs::viz::to_svg;
+///
+/// let points = vec![
+///     Point { x: 0, y: 0 },
+///     Point { x: 10, y: 10 },
+///     Point { x: 3, y: 4 }
+/// ];
+/// let result = ClosestPairResult::from(closest_pair_optimized(&points));
+/// let svg = to_svg(&points, &result);
+/// assert!(svg.starts_with("<svg"));
+/// assert_eq!(svg.matches("<circle").count(), 5);
+/// assert_eq!(svg.matches("<line").count(), 1);
+/// ```
+pub fn to_svg<T: Coordinate>(points: &[Point<T>], result: &ClosestPairResult<T>) -> String {
+    to_svg_with_size(points, result, SVG_CANVAS_SIZE)
+}
+
+/// Draws a point set and its closest pair as an SVG image of a given size.
+///
+/// Every point is a small black circle. The closest pair is drawn again on top
+/// as two larger red circles joined by a red line. The points are scaled to fit
+/// the square canvas with the same scale along both axes, and y grows upwards
+/// as in the plane, rather than downwards as in SVG.
+///
+/// # Arguments
+///
+/// * `points` - The points to draw
+/// * `result` - The closest pair to highlight
+/// * `size` - Width and height of the canvas in pixels
+///
+/// # Returns
+///
+/// The SVG document as a string.
+///
+/// # Examples
+///
+/// ```
+/// use closest_pair_rs::utils::Point;
+/// use closest_pair_rs::result::ClosestPairResult;
+/// use closest_pair_rs::viz::to_svg_with_size;
+///
+/// let points = vec![Point { x: 0, y: 0 }, Point { x: 3, y: 4 }];
+/// let result = ClosestPairResult::new(points[0], points[1], 5.0);
+/// let svg = to_svg_with_size(&points, &result, 100);
+/// assert!(svg.contains(r#"width="100" height="100""#));
+/// ```
+pub fn to_svg_with_size<T: Coordinate>(
+    points: &[Point<T>],
+    result: &ClosestPairResult<T>,
+    size: u32,
+) -> String {
+    // The pair is included, in case it isn't among `points`
+    let pair = [result.a(), result.b()];
+    let (min_x, min_y, max_x, max_y) = points.iter().chain(&pair).fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(min_x, min_y, max_x, max_y), p| {
+            let (x, y) = (p.x.to_f64(), p.y.to_f64());
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    );
+
+    // A single location, or points on a line, would otherwise divide by zero
+    let span = (max_x - min_x).max(max_y - min_y);
+    let scale = if span > 0.0 {
+        (size as f64 - 2.0 * MARGIN).max(0.0) / span
+    } else {
+        0.0
+    };
+    let project = |p: Point<T>| {
+        (
+            MARGIN + (p.x.to_f64() - min_x) * scale,
+            size as f64 - MARGIN - (p.y.to_f64() - min_y) * scale,
+        )
+    };
+
+    // Writing to a String never fails
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#
+    );
+    for &p in points {
+        let (x, y) = project(p);
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{x:.1}" cy="{y:.1}" r="2" fill="black"/>"#
+        );
+    }
+
+    let (x1, y1) = project(result.a());
+    let (x2, y2) = project(result.b());
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="red" stroke-width="1"/>"#
+    );
+    for (x, y) in [(x1, y1), (x2, y2)] {
+        let _ = writeln!(svg, r#"<circle cx="{x:.1}" cy="{y:.1}" r="4" fill="red"/>"#);
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod svg_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_brute_force;
+
+    #[test]
+    fn test_tiny_input() {
+        let points = vec![
+            Point { x: 0, y: 0 },
+            Point { x: 10, y: 10 },
+            Point { x: 9, y: 10 },
+        ];
+        let result = ClosestPairResult::from(closest_pair_brute_force(&points));
+        let svg = to_svg_with_size(&points, &result, 116);
+
+        // Scaled by 10, with y flipped and an 8 pixel margin
+        assert!(svg.contains(r#"<circle cx="8.0" cy="108.0" r="2" fill="black"/>"#));
+        assert!(svg.contains(r#"<circle cx="108.0" cy="8.0" r="2" fill="black"/>"#));
+        assert!(svg.contains(r#"<circle cx="98.0" cy="8.0" r="2" fill="black"/>"#));
+        assert!(svg.contains(
+            r#"<line x1="108.0" y1="8.0" x2="98.0" y2="8.0" stroke="red" stroke-width="1"/>"#
+        ));
+        assert!(svg.contains(r#"<circle cx="108.0" cy="8.0" r="4" fill="red"/>"#));
+        assert!(svg.contains(r#"<circle cx="98.0" cy="8.0" r="4" fill="red"/>"#));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="116""#));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_counts() {
+        let points = random_points(50, 1000, 4);
+        let result = ClosestPairResult::from(closest_pair_brute_force(&points));
+        let svg = to_svg(&points, &result);
+        assert_eq!(svg.matches("<circle").count(), 52);
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert!(svg.contains(r#"width="512" height="512""#));
+    }
+
+    #[test]
+    fn test_coincident_points() {
+        let p = Point { x: 5, y: 5 };
+        let result = ClosestPairResult::new(p, p, 0.0);
+        let svg = to_svg_with_size(&[p, p], &result, 100);
+        assert!(svg.contains(r#"<circle cx="8.0" cy="92.0" r="2" fill="black"/>"#));
+        assert!(!svg.contains("NaN"));
+    }
+
+    #[test]
+    fn test_float_points() {
+        let points = vec![PointF64 { x: -1.0, y: -1.0 }, PointF64 { x: 1.0, y: 1.0 }];
+        let result = ClosestPairResult::from(closest_pair_brute_force(&points));
+        let svg = to_svg_with_size(&points, &result, 116);
+        assert!(svg.contains(r#"<line x1="8.0" y1="108.0" x2="108.0" y2="8.0""#));
+    }
+}
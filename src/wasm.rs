@@ -0,0 +1,92 @@
+//! JavaScript bindings through `wasm-bindgen`, behind the `wasm` feature.
+//!
+//! Build with `wasm-pack build --features wasm` (or `cargo build --target
+//! wasm32-unknown-unknown --features wasm` followed by `wasm-bindgen`), then
+//! from JavaScript:
+//!
+//! ```js
+//! const pair = closestPair(new Uint32Array([0, 0, 10, 10, 3, 4]));
+//! console.log(pair.x1, pair.y1, pair.x2, pair.y2, pair.distance);
+//! ```
+
+use crate::algorithms::try_closest_pair_from_flat;
+use wasm_bindgen::prelude::*;
+
+/// The closest pair of points and their distance, as a JavaScript object.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WasmClosestPair {
+    pub x1: u32,
+    pub y1: u32,
+    pub x2: u32,
+    pub y2: u32,
+    pub distance: f32,
+}
+
+/// Finds the closest pair of points in interleaved coordinates `x0, y0, x1, y1, ...`.
+///
+/// Exported to JavaScript as `closestPair`, which takes a `Uint32Array` or an
+/// array of numbers. No points, a single point or an odd number of values throw
+/// an `Error` with the same message as [`try_closest_pair_from_flat`] reports.
+#[wasm_bindgen(js_name = closestPair)]
+pub fn closest_pair_wasm(coords: Vec<u32>) -> Result<WasmClosestPair, JsError> {
+    let (p1, p2, distance) =
+        try_closest_pair_from_flat(&coords).map_err(|err| JsError::new(&err.to_string()))?;
+
+    Ok(WasmClosestPair {
+        x1: p1.x,
+        y1: p1.y,
+        x2: p2.x,
+        y2: p2.y,
+        distance,
+    })
+}
+
+// Only the success path runs natively: building a `JsError` needs a JavaScript host
+#[cfg(test)]
+mod wasm_tests {
+    use super::*;
+    use crate::algorithms::closest_pair_optimized;
+    use crate::utils::*;
+
+    #[test]
+    fn test_flat_buffer() {
+        let pair = closest_pair_wasm(vec![0, 0, 10, 10, 3, 4]).unwrap();
+        assert_eq!(pair.distance, 5.0);
+        let points = [(pair.x1, pair.y1), (pair.x2, pair.y2)];
+        assert!(points == [(0, 0), (3, 4)] || points == [(3, 4), (0, 0)]);
+    }
+
+    #[test]
+    fn test_matches_points() {
+        let points = random_points(1000, 1 << 20, 16);
+        let coords = points.iter().flat_map(|&p| <[u32; 2]>::from(p)).collect();
+        let pair = closest_pair_wasm(coords).unwrap();
+
+        let (p1, p2, distance) = closest_pair_optimized(&points);
+        assert_eq!(
+            (pair.x1, pair.y1, pair.x2, pair.y2),
+            (p1.x, p1.y, p2.x, p2.y)
+        );
+        assert_eq!(pair.distance, distance);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_bindgen_tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_flat_buffer() {
+        let pair = closest_pair_wasm(vec![0, 0, 10, 10, 3, 4]).unwrap();
+        assert_eq!(pair.distance, 5.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_errors_throw() {
+        assert!(closest_pair_wasm(vec![]).is_err());
+        assert!(closest_pair_wasm(vec![1, 2]).is_err());
+        assert!(closest_pair_wasm(vec![1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "std")]
+
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+/// Runs the binary with `args`, feeding it `input` on stdin.
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_closest-pair"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The binary can exit on bad arguments before it reads any of its input
+    if let Err(err) = child.stdin.take().unwrap().write_all(input.as_bytes()) {
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+const INPUT: &str = "0 0\n10 10\n5 5\n8 9\n20 0\n";
+
+#[test]
+fn test_algorithms_agree() {
+    for args in [&[][..], &["--brute"], &["--optimized"], &["--bit-shift"]] {
+        let output = run(args, INPUT);
+        assert!(output.status.success(), "args = {args:?}");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout == "(8, 9) (10, 10) 2.236068\n" || stdout == "(10, 10) (8, 9) 2.236068\n",
+            "args = {args:?}, stdout = {stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_csv_input_and_timing() {
+    let output = run(&["--brute", "--time"], "x,y\n0,0\n3,4\n");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "(0, 0) (3, 4) 5\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Took "));
+}
+
+#[test]
+fn test_malformed_line() {
+    let output = run(&[], "0 0\n1 one\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: Line 2: expected `x,y`, got `1 one`\n"
+    );
+}
+
+#[test]
+fn test_single_point() {
+    let output = run(&[], "1 1\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: Need at least two points to find closest pair\n"
+    );
+}
+
+#[test]
+fn test_unknown_argument() {
+    let output = run(&["--fast"], INPUT);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("error: unknown argument `--fast`"));
+}